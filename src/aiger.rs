@@ -0,0 +1,319 @@
+use std::fmt;
+
+use core::*;
+use solving::*;
+
+/// A literal of an and-inverter graph: twice the index of its variable, plus one when it is
+/// negated. The variable 0 is the constant false (hence, the literal 1 is the constant true).
+pub type AigLiteral = u32;
+
+/// A latch (a state bit) of an and-inverter graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Latch {
+    /// The literal of the latch in the current state
+    pub current: AigLiteral,
+    /// The literal of the latch in the next state
+    pub next   : AigLiteral,
+    /// The initial value of the latch: 0, 1 or the latch literal itself when it is uninitialized
+    pub reset  : AigLiteral
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Aiger
+/// A sequential circuit described as an and-inverter graph, as read from an AIGER file (see
+/// `load_aiger`). The property of the circuit is that none of its bad state literals (or of its
+/// outputs, when it has no bad state literal) ever holds in a state satisfying its invariant
+/// constraints.
+///
+/// See `The AIGER And-Inverter Graph (AIG) Format Version 20071012` -- Biere (2007), and its
+/// 1.9 revision which adds the bad state and constraint literals.
+// -----------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Aiger {
+    /// The maximum variable index
+    pub max_var    : u32,
+    /// The (positive) literals of the inputs
+    pub inputs     : Vec<AigLiteral>,
+    /// The latches
+    pub latches    : Vec<Latch>,
+    /// The outputs
+    pub outputs    : Vec<AigLiteral>,
+    /// The bad state literals
+    pub bad        : Vec<AigLiteral>,
+    /// The invariant constraints
+    pub constraints: Vec<AigLiteral>,
+    /// The and gates: `lhs = rhs0 & rhs1`
+    pub ands       : Vec<(AigLiteral, AigLiteral, AigLiteral)>
+}
+
+impl Aiger {
+    /// Returns the literals whose holding is a violation of the property of the circuit: its bad
+    /// state literals, or its outputs when it has no bad state literal.
+    pub fn properties(&self) -> &[AigLiteral] {
+        if self.bad.is_empty() { &self.outputs } else { &self.bad }
+    }
+}
+
+/// A counterexample to the property of a circuit: the initial value of the latches and the
+/// value of the inputs at every step of an execution reaching a bad state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Witness {
+    /// The index of the property which is violated
+    pub property: usize,
+    /// The initial value of the latches
+    pub latches : Vec<bool>,
+    /// The value of the inputs at each step
+    pub inputs  : Vec<Vec<bool>>
+}
+
+/// The witness is displayed in the format of the hardware model checking competitions
+impl fmt::Display for Witness {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bits = |values: &[bool]| values.iter().map(|&v| if v { '1' } else { '0' }).collect::<String>();
+
+        writeln!(f, "1")?;
+        writeln!(f, "b{}", self.property)?;
+        writeln!(f, "{}", bits(&self.latches))?;
+        for inputs in self.inputs.iter() {
+            writeln!(f, "{}", bits(inputs))?;
+        }
+        write!(f, ".")
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Bmc
+/// Bounded model checking of a circuit: the transition relation of the circuit is unrolled one
+/// step (frame) after the other in the solver, and every frame is checked for a bad state in turn.
+///
+/// The checks are incremental: the frame being checked is only asked to reach a bad state by an
+/// assumption, so that the clauses learned while checking one frame are kept for the next ones.
+/// Once a frame is proven to be safe, the absence of a bad state in that frame is added as a unit.
+///
+/// The and gates of every frame are Tseitin-encoded and the latches of a frame are merely the
+/// literals of their next state in the previous frame.
+///
+/// See `Symbolic Model Checking without BDDs` -- Biere, Cimatti, Clarke, Zhu (TACAS 1999).
+// -----------------------------------------------------------------------------------------------
+#[derive(Debug)]
+pub struct Bmc {
+    /// The solver deciding the unrolled circuit
+    pub solver: Solver,
+    /// The checked circuit
+    aiger     : Aiger,
+    /// A literal which is always true (the constant of the circuit)
+    truth     : Literal,
+    /// The literal of every variable of the circuit in every frame unrolled so far
+    frames    : Vec<Vec<Literal>>,
+    /// The number of frames proven not to comprise any bad state
+    nb_safe   : usize
+}
+
+impl Bmc {
+    /// Creates the bounded model checker of the given circuit (nothing is unrolled yet)
+    pub fn new(aiger: Aiger) -> Bmc {
+        let mut solver = Solver::new(1);
+        let truth = lit(1);
+        let _ = solver.add_clause_literals(&[truth]);
+
+        Bmc { solver, aiger, truth, frames: vec![], nb_safe: 0 }
+    }
+
+    /// Returns the number of frames unrolled so far
+    #[inline]
+    pub fn nb_frames(&self) -> usize { self.frames.len() }
+
+    /// Looks for an execution of the circuit reaching a bad state within `depth` steps. The frames
+    /// are checked in increasing order, hence the execution found (if any) is a shortest one.
+    ///
+    /// # Return Value
+    /// A witness of that execution (whose length is one more than its number of steps), or None
+    /// when no bad state can be reached within `depth` steps.
+    pub fn check(&mut self, depth: usize) -> Option<Witness> {
+        for frame in self.nb_safe..=depth {
+            while self.frames.len() <= frame {
+                self.unroll();
+            }
+
+            let bad = self.bad(frame);
+            if self.solver.solve_with_assumptions(&[bad]) {
+                return Some(self.witness(frame));
+            }
+            // this frame is safe: it may as well be known
+            let _ = self.solver.add_clause_literals(&[!bad]);
+            self.nb_safe = frame + 1;
+        }
+        None
+    }
+
+    /// Returns the solver literal standing for the given circuit literal in the given frame
+    fn literal(&self, frame: usize, literal: AigLiteral) -> Literal {
+        let l = self.frames[frame][(literal >> 1) as usize];
+        if literal & 1 == 1 { !l } else { l }
+    }
+
+    /// Adds the next frame of the circuit to the solver
+    fn unroll(&mut self) {
+        let frame  = self.frames.len();
+        let nb_vars= self.aiger.max_var as usize + 1;
+
+        let mut literals = vec![None; nb_vars];
+        literals[0] = Some(!self.truth);
+        for latch in self.aiger.latches.iter() {
+            let current = (latch.current >> 1) as usize;
+            if frame > 0 {
+                literals[current] = Some(self.literal(frame - 1, latch.next));
+            } else if latch.reset < 2 {
+                literals[current] = Some(if latch.reset == 1 { self.truth } else { !self.truth });
+            }
+        }
+        // (the inputs, the gates and the uninitialized latches get a fresh variable)
+        let solver = &mut self.solver;
+        let literals = literals.into_iter()
+            .map(|l| l.unwrap_or_else(|| Literal::positive(solver.new_var())))
+            .collect();
+        self.frames.push(literals);
+
+        for &(lhs, rhs0, rhs1) in self.aiger.ands.iter() {
+            let (lhs, a, b) = (self.literal(frame, lhs), self.literal(frame, rhs0), self.literal(frame, rhs1));
+            let _ = self.solver.add_clause_literals(&[!lhs, a]);
+            let _ = self.solver.add_clause_literals(&[!lhs, b]);
+            let _ = self.solver.add_clause_literals(&[lhs, !a, !b]);
+        }
+        for &constraint in self.aiger.constraints.iter() {
+            let constraint = self.literal(frame, constraint);
+            let _ = self.solver.add_clause_literals(&[constraint]);
+        }
+    }
+
+    /// Returns a literal which holds when a bad state is reached in the given frame
+    fn bad(&mut self, frame: usize) -> Literal {
+        let properties: Vec<Literal> = self.aiger.properties().iter()
+            .map(|&p| self.literal(frame, p))
+            .collect();
+
+        if properties.len() == 1 {
+            return properties[0];
+        }
+
+        let bad = Literal::positive(self.solver.new_var());
+        let mut clause = properties;
+        clause.push(!bad);
+        let _ = self.solver.add_clause_literals(&clause);
+        bad
+    }
+
+    /// Returns the witness of the execution reaching a bad state in the given frame (as found by
+    /// the solver)
+    fn witness(&self, frame: usize) -> Witness {
+        let value = |l: Literal| self.solver.get_value(l) == Bool::True;
+
+        let property = self.aiger.properties().iter()
+            .position(|&p| value(self.literal(frame, p)))
+            .unwrap();
+        let latches = self.aiger.latches.iter()
+            .map(|latch| value(self.literal(0, latch.current)))
+            .collect();
+        let inputs = (0..=frame)
+            .map(|f| self.aiger.inputs.iter().map(|&i| value(self.literal(f, i))).collect())
+            .collect();
+
+        Witness { property, latches, inputs }
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Unit Tests
+// -----------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_combinational_circuit_is_checked_in_a_single_frame() {
+        // out = x & !y
+        let aiger = Aiger {
+            max_var: 3, inputs: vec![2, 4], outputs: vec![6], ands: vec![(6, 2, 5)], ..Aiger::default()
+        };
+        let mut tested = Bmc::new(aiger);
+
+        let witness = tested.check(0).unwrap();
+        assert_eq!(tested.nb_frames(), 1);
+        assert_eq!(witness.property, 0);
+        assert_eq!(witness.inputs, vec![vec![true, false]]);
+    }
+
+    #[test]
+    fn the_bad_states_are_found_at_the_shortest_depth() {
+        let aiger = Aiger {
+            max_var: 4,
+            latches: vec![Latch { current: 2, next: 3, reset: 0 }, Latch { current: 4, next: 6, reset: 0 }],
+            ands   : vec![(6, 2, 5), (8, 2, 4)],
+            ..Aiger::default()
+        };
+        // the first latch toggles: 0 1 0 1 ... ; the second one is set when the first is set while
+        // it is not: 0 0 1 0 1 ...
+        let mut bad = aiger.clone();
+        bad.max_var = 5;
+        bad.ands.push((10, 3, 4));
+        bad.bad = vec![10];
+        let mut tested = Bmc::new(bad);
+
+        assert_eq!(tested.check(1), None);
+        let witness = tested.check(5).unwrap();
+        assert_eq!(tested.nb_frames(), 3);
+        assert_eq!(witness.latches, vec![false, false]);
+        assert_eq!(witness.inputs.len(), 3);
+
+        // both latches are never set at once
+        let mut never = aiger;
+        never.bad = vec![8];
+        assert_eq!(Bmc::new(never).check(10), None);
+    }
+
+    #[test]
+    fn the_constraints_restrict_the_executions() {
+        // the latch is set as soon as the input is ; bad = latch ; constraint = !input
+        let aiger = Aiger {
+            max_var: 2, inputs: vec![2], latches: vec![Latch { current: 4, next: 2, reset: 0 }],
+            bad: vec![4], ..Aiger::default()
+        };
+        assert!(Bmc::new(aiger.clone()).check(1).is_some());
+
+        let constrained = Aiger { constraints: vec![3], ..aiger };
+        assert_eq!(Bmc::new(constrained).check(5), None);
+    }
+
+    #[test]
+    fn uninitialized_latches_are_free_in_the_first_frame() {
+        let aiger = Aiger {
+            max_var: 1, latches: vec![Latch { current: 2, next: 2, reset: 2 }], bad: vec![2],
+            ..Aiger::default()
+        };
+        let witness = Bmc::new(aiger).check(0).unwrap();
+        assert_eq!(witness.latches, vec![true]);
+    }
+
+    #[test]
+    fn the_witness_is_displayed_as_in_the_competitions() {
+        let witness = Witness {
+            property: 1, latches: vec![false, true], inputs: vec![vec![true], vec![false]]
+        };
+        assert_eq!(witness.to_string(), "1\nb1\n01\n1\n0\n.");
+    }
+
+    #[test]
+    fn several_properties_are_checked_at_once() {
+        // y is bad unless x ; x & y is bad too
+        let aiger = Aiger {
+            max_var: 4, inputs: vec![2, 4], bad: vec![6, 8], ands: vec![(6, 3, 4), (8, 2, 4)],
+            ..Aiger::default()
+        };
+        let witness = Bmc::new(aiger.clone()).check(0).unwrap();
+        assert!(witness.inputs[0][1]);
+        assert_eq!(witness.property, if witness.inputs[0][0] { 1 } else { 0 });
+
+        let unreachable = Aiger { constraints: vec![5], ..aiger };
+        assert_eq!(Bmc::new(unreachable).check(0), None);
+    }
+}
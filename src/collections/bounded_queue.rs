@@ -0,0 +1,156 @@
+use std::collections::VecDeque;
+
+// -----------------------------------------------------------------------------------------------
+/// # Bounded Queue
+/// A FIFO queue which remembers (at most) the `capa` most recently pushed values. Whenever a value
+/// is pushed onto a full queue, the oldest value is evicted to make room for the new one.
+///
+/// The queue maintains the sum of its values so that their average can be computed in O(1). This
+/// is what the dynamic restart strategies (a la Glucose) need to compare the recent behavior of
+/// the search with its global behavior.
+// -----------------------------------------------------------------------------------------------
+#[derive(Debug)]
+pub struct BoundedQueue {
+    /// The values (the oldest value is at the front of the queue)
+    values: VecDeque<u32>,
+    /// The sum of all the values currently in the queue
+    sum   : u64,
+    /// The maximum number of values the queue can hold
+    capa  : usize
+}
+
+impl BoundedQueue {
+    /// Creates a new empty queue capable of holding `capa` values.
+    pub fn new(capa: usize) -> BoundedQueue {
+        BoundedQueue {
+            values: VecDeque::with_capacity(capa),
+            sum   : 0,
+            capa
+        }
+    }
+
+    /// Pushes `value` at the back of the queue, evicting the oldest value if the queue is full.
+    #[inline]
+    pub fn push(&mut self, value: u32) {
+        if self.capa == 0 { return; }
+
+        if self.values.len() == self.capa {
+            let oldest = self.values.pop_front().unwrap();
+            self.sum  -= u64::from(oldest);
+        }
+        self.values.push_back(value);
+        self.sum += u64::from(value);
+    }
+
+    /// Removes all the values from the queue
+    #[inline]
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.sum = 0;
+    }
+
+    /// Returns the number of values currently in the queue
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true iff the queue holds no value at all
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns true iff pushing an other value would evict the oldest one
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.capa > 0 && self.values.len() == self.capa
+    }
+
+    /// Returns the maximum number of values the queue can hold
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capa
+    }
+
+    /// Returns the average of the values currently in the queue (0 when the queue is empty)
+    #[inline]
+    pub fn average(&self) -> f64 {
+        if self.is_empty() { 0.0 } else { self.sum as f64 / self.len() as f64 }
+    }
+
+    /// Returns an iterator over the values of the queue, from the oldest to the most recent one
+    pub fn iter(&self) -> impl Iterator<Item=&u32> {
+        self.values.iter()
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Unit Tests
+// -----------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_queue_is_empty() {
+        let tested = BoundedQueue::new(3);
+
+        assert!(tested.is_empty());
+        assert!(!tested.is_full());
+        assert_eq!(tested.len(), 0);
+        assert_eq!(tested.capacity(), 3);
+        assert_eq!(tested.average(), 0.0);
+    }
+
+    #[test]
+    fn push_fills_the_queue() {
+        let mut tested = BoundedQueue::new(3);
+
+        tested.push(1);
+        tested.push(2);
+        assert!(!tested.is_full());
+        tested.push(6);
+        assert!(tested.is_full());
+
+        assert_eq!(tested.len(), 3);
+        assert_eq!(tested.average(), 3.0);
+    }
+
+    #[test]
+    fn push_evicts_the_oldest_value_when_full() {
+        let mut tested = BoundedQueue::new(3);
+
+        for i in 1..6 {
+            tested.push(i);
+        }
+
+        assert_eq!(tested.len(), 3);
+        assert_eq!(tested.iter().cloned().collect::<Vec<u32>>(), vec![3, 4, 5]);
+        assert_eq!(tested.average(), 4.0);
+    }
+
+    #[test]
+    fn clear_empties_the_queue() {
+        let mut tested = BoundedQueue::new(3);
+        tested.push(4);
+        tested.push(4);
+        tested.push(4);
+
+        tested.clear();
+        assert!(tested.is_empty());
+        assert_eq!(tested.average(), 0.0);
+
+        tested.push(2);
+        assert_eq!(tested.average(), 2.0);
+    }
+
+    #[test]
+    fn zero_capacity_queue_never_holds_anything() {
+        let mut tested = BoundedQueue::new(0);
+        tested.push(4);
+
+        assert!(tested.is_empty());
+        assert!(!tested.is_full());
+    }
+}
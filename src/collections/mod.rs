@@ -1,8 +1,10 @@
 mod var_idx_vec;
 mod lit_idx_vec;
 mod var_heap;
+mod bounded_queue;
 
 // re-export everything
 pub use self::var_idx_vec::VarIdxVec;
 pub use self::lit_idx_vec::LitIdxVec;
-pub use self::var_heap::VarHeap;
\ No newline at end of file
+pub use self::var_heap::VarHeap;
+pub use self::bounded_queue::BoundedQueue;
\ No newline at end of file
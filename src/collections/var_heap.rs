@@ -76,6 +76,25 @@ impl VarHeap {
         return ret;
     }
 
+    /// Extends the capacity of the heap by one: the new variable (capa+1) is given the `score`
+    /// and placed on the heap.
+    ///
+    /// # Return Value
+    /// Returns the new variable
+    pub fn grow(&mut self, score: f64) -> Variable {
+        self.capa += 1;
+        let var = Variable::from(self.capa);
+
+        // the padding must still denote a non-existing variable
+        self.heap[0] = Variable::from(self.capa+1);
+        self.heap    .push(var);
+        self.position.push(self.capa);
+        self.score   .push(score);
+
+        self.push_back(var);
+        var
+    }
+
     /// return true iff there is no element left in the heap
     #[inline]
     pub fn is_empty(&self) -> bool {
@@ -137,6 +156,16 @@ impl VarHeap {
         return var;
     }
 
+    /// Returns the element with highest score without removing it from the heap.
+    ///
+    /// # Panics
+    /// - when one tries to peek an empty heap.
+    #[inline]
+    pub fn peek_top(&self) -> Variable {
+        debug_assert!( !self.is_empty(), "Cannot peek an empty heap");
+        self.heap[1]
+    }
+
     /// Returns the score associated with some given variable
     #[inline]
     pub fn get_score(&self, var: Variable) -> f64 {
@@ -265,6 +294,22 @@ mod tests {
     }
 
 
+    #[test]
+    fn grow_places_the_new_variable_on_the_heap(){
+        let mut tested = VarHeap::new(MAX);
+        // pop half of the vars
+        for _ in 0..MAX/2 { tested.pop_top(); }
+
+        let var = tested.grow(10.0);
+        assert_eq!(Variable::from(MAX+1), var);
+        assert_eq!(MAX/2 + 1, tested.len());
+        assert_eq!(var, tested.pop_top());
+
+        // the popped vars can still be pushed back
+        tested.push_back(Variable::from(1_usize));
+        assert_eq!(MAX/2 + 1, tested.len());
+    }
+
     #[test]
     #[should_panic]
     /// pushBack fails for zero
@@ -354,6 +399,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn peek_top_must_not_remove_the_item_with_highest_score(){
+        let mut tested = VarHeap::new(MAX);
+        tested.score[Variable::from(10_u32)] = 5.0;
+        tested.swim(Variable::from(10_u32));
+
+        assert_eq!(Variable::from(10_u32), tested.peek_top());
+        assert_eq!(MAX, tested.len());
+        assert_eq!(Variable::from(10_u32), tested.pop_top());
+    }
+
     #[test]
     fn get_score_should_return_the_score_of_some_given_variable() {
         let mut tested = VarHeap::new(MAX);
@@ -1,5 +1,6 @@
 use std::ops::*;
 use std::cmp::*;
+use std::hash::{Hash, Hasher};
 use super::*;
 
 // -----------------------------------------------------------------------------------------------
@@ -7,7 +8,12 @@ use super::*;
 /// In the same vein as the Variable type, Literal is a thin (but type safe) wrapper around a
 /// signed number that represents a literal.
 // -----------------------------------------------------------------------------------------------
+///
+/// _Note:_
+/// The representation of a literal is guaranteed to be the same as that of its number. This is
+/// what makes it possible for the clause arena to store the literals of the clauses as plain words.
 #[derive(Clone, Copy, Eq, Debug)]
+#[repr(transparent)]
 pub struct Literal(iint);
 
 impl Literal {
@@ -77,6 +83,14 @@ impl Not for Literal {
     }
 }
 
+/// Literals are hashed by their number (consistently with their equality) so that they can be
+/// used as the keys of a hash map
+impl Hash for Literal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
 impl Ord for Literal{
     fn cmp(&self, other: &Literal) -> Ordering {
         self.to_isize().cmp(&other.to_isize())
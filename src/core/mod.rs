@@ -8,6 +8,7 @@ mod variable;
 mod sign;
 mod literal;
 mod clause;
+mod natural;
 
 // re-export all types
 pub use self::bool::Bool;
@@ -15,6 +16,7 @@ pub use self::variable::Variable;
 pub use self::sign::Sign;
 pub use self::literal::Literal;
 pub use self::clause::Clause;
+pub use self::natural::Natural;
 
 /// A shortcut notation to make a literal out of a number value
 pub fn lit(l: iint) -> Literal  { Literal::from(l) }
@@ -0,0 +1,231 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::*;
+
+// -----------------------------------------------------------------------------------------------
+/// # Natural
+/// An arbitrary precision natural number. This is what the model counts are made of: the number
+/// of models of a problem easily exceeds the capacity of any machine integer (a problem having
+/// n unconstrained variables has 2^n models).
+///
+/// The number is stored as a vector of 32 bits digits, the least significant one first. The
+/// vector never ends with a zero digit (hence zero is the empty vector).
+// -----------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Natural { digits: Vec<u32> }
+
+impl Natural {
+    /// Returns the number 0
+    #[inline]
+    pub fn zero() -> Natural { Natural { digits: vec![] } }
+
+    /// Returns the number 1
+    #[inline]
+    pub fn one() -> Natural { Natural { digits: vec![1] } }
+
+    /// Tells whether this number is 0
+    #[inline]
+    pub fn is_zero(&self) -> bool { self.digits.is_empty() }
+
+    /// Drops the leading zero digits
+    fn normalize(mut self) -> Natural {
+        while self.digits.last() == Some(&0) {
+            self.digits.pop();
+        }
+        self
+    }
+}
+
+impl From<u64> for Natural {
+    fn from(n: u64) -> Natural {
+        Natural { digits: vec![n as u32, (n >> 32) as u32] }.normalize()
+    }
+}
+
+impl Ord for Natural {
+    fn cmp(&self, other: &Natural) -> Ordering {
+        // (there is no leading zero digit: the longest number is the greatest)
+        self.digits.len().cmp(&other.digits.len())
+            .then_with(|| self.digits.iter().rev().cmp(other.digits.iter().rev()))
+    }
+}
+
+impl PartialOrd for Natural {
+    fn partial_cmp(&self, other: &Natural) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Add<&Natural> for &Natural {
+    type Output = Natural;
+
+    fn add(self, other: &Natural) -> Natural {
+        let len = self.digits.len().max(other.digits.len());
+        let mut digits = Vec::with_capacity(len + 1);
+        let mut carry  = 0_u64;
+        for i in 0..len {
+            let x = *self .digits.get(i).unwrap_or(&0) as u64;
+            let y = *other.digits.get(i).unwrap_or(&0) as u64;
+            let sum = x + y + carry;
+            digits.push(sum as u32);
+            carry = sum >> 32;
+        }
+        digits.push(carry as u32);
+        Natural { digits }.normalize()
+    }
+}
+
+impl Mul<&Natural> for &Natural {
+    type Output = Natural;
+
+    fn mul(self, other: &Natural) -> Natural {
+        if self.is_zero() || other.is_zero() { return Natural::zero(); }
+
+        let mut digits = vec![0_u32; self.digits.len() + other.digits.len()];
+        for (i, &x) in self.digits.iter().enumerate() {
+            let mut carry = 0_u64;
+            for (j, &y) in other.digits.iter().enumerate() {
+                let product = x as u64 * y as u64 + digits[i + j] as u64 + carry;
+                digits[i + j] = product as u32;
+                carry = product >> 32;
+            }
+            digits[i + other.digits.len()] = carry as u32;
+        }
+        Natural { digits }.normalize()
+    }
+}
+
+/// Multiplies the number by 2^shift
+impl Shl<usize> for &Natural {
+    type Output = Natural;
+
+    fn shl(self, shift: usize) -> Natural {
+        if self.is_zero() { return Natural::zero(); }
+
+        let (words, bits) = (shift / 32, shift % 32);
+        let mut digits = vec![0_u32; words];
+        let mut carry  = 0_u32;
+        for &x in self.digits.iter() {
+            if bits == 0 {
+                digits.push(x);
+            } else {
+                digits.push((x << bits) | carry);
+                carry = x >> (32 - bits);
+            }
+        }
+        digits.push(carry);
+        Natural { digits }.normalize()
+    }
+}
+
+impl AddAssign<&Natural> for Natural {
+    fn add_assign(&mut self, other: &Natural) { *self = &*self + other; }
+}
+
+impl MulAssign<&Natural> for Natural {
+    fn mul_assign(&mut self, other: &Natural) { *self = &*self * other; }
+}
+
+impl ShlAssign<usize> for Natural {
+    fn shl_assign(&mut self, shift: usize) { *self = &*self << shift; }
+}
+
+/// Prints the number in base 10
+impl fmt::Display for Natural {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() { return write!(f, "0"); }
+
+        // the number is split in chunks of 9 decimal digits by repeated divisions
+        const CHUNK: u64 = 1_000_000_000;
+        let mut chunks = vec![];
+        let mut digits = self.digits.clone();
+        while !digits.is_empty() {
+            let mut remainder = 0_u64;
+            for d in digits.iter_mut().rev() {
+                let current = (remainder << 32) | *d as u64;
+                *d = (current / CHUNK) as u32;
+                remainder = current % CHUNK;
+            }
+            chunks.push(remainder);
+            while digits.last() == Some(&0) {
+                digits.pop();
+            }
+        }
+
+        write!(f, "{}", chunks[chunks.len() - 1])?;
+        for chunk in chunks.iter().rev().skip(1) {
+            write!(f, "{:09}", chunk)?;
+        }
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Unit Tests
+// -----------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_and_one() {
+        assert!(Natural::zero().is_zero());
+        assert!(!Natural::one().is_zero());
+        assert_eq!(Natural::from(0), Natural::zero());
+        assert_eq!(Natural::from(1), Natural::one());
+        assert_eq!(Natural::default(), Natural::zero());
+    }
+
+    #[test]
+    fn add() {
+        let max = Natural::from(u64::MAX);
+        assert_eq!((&max + &Natural::one()).to_string(), "18446744073709551616");
+        assert_eq!((&max + &max).to_string(), "36893488147419103230");
+        assert_eq!(&Natural::zero() + &max, max);
+
+        let mut sum = Natural::from(40);
+        sum += &Natural::from(2);
+        assert_eq!(sum, Natural::from(42));
+    }
+
+    #[test]
+    fn mul() {
+        let max = Natural::from(u64::MAX);
+        assert_eq!((&max * &max).to_string(), "340282366920938463426481119284349108225");
+        assert_eq!(&max * &Natural::zero(), Natural::zero());
+
+        let mut product = Natural::from(6);
+        product *= &Natural::from(7);
+        assert_eq!(product, Natural::from(42));
+    }
+
+    #[test]
+    fn shl() {
+        assert_eq!((&Natural::one() << 100).to_string(), "1267650600228229401496703205376");
+        assert_eq!(&Natural::from(21) << 1, Natural::from(42));
+        assert_eq!(&Natural::from(3) << 64, &Natural::from(3) * &(&Natural::one() << 64));
+        assert_eq!(&Natural::zero() << 10, Natural::zero());
+
+        let mut shifted = Natural::one();
+        shifted <<= 32;
+        assert_eq!(shifted, Natural::from(1 << 32));
+    }
+
+    #[test]
+    fn ordering() {
+        let big = &Natural::one() << 64;
+        assert!(Natural::zero() < Natural::one());
+        assert!(Natural::from(u64::MAX) < big);
+        assert!(&big + &Natural::one() > &big + &Natural::zero());
+        assert!(&Natural::from(2) << 40 > &Natural::from(3) << 39);
+
+        let mut sorted = vec![Natural::from(7), big.clone(), Natural::zero(), Natural::from(5)];
+        sorted.sort();
+        assert_eq!(sorted, vec![Natural::zero(), Natural::from(5), Natural::from(7), big]);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Natural::zero().to_string(), "0");
+        assert_eq!(Natural::from(1_000_000_000).to_string(), "1000000000");
+        assert_eq!(Natural::from(1_000_000_007_000_000_009).to_string(), "1000000007000000009");
+    }
+}
@@ -1,42 +1,174 @@
 use std::io;
 use std::io::*;
+use std::mem;
+use std::collections::VecDeque;
 
 use core::*;
 use solving::*;
+use maxsat::*;
+use pseudo_boolean::*;
+use formula::*;
+use aiger::*;
+use mus::*;
 
 extern crate time;
 
 //use self::time::PreciseTime;
 
-pub fn parse_header<Source>(input : &mut Lines<Source>) -> Solver
+/// The problem line (header) of a DIMACS-like input. It tells the format of the rest of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Header {
+    /// `p cnf <nb_vars> <nb_clauses>`: a plain cnf problem having `nb_vars` variables
+    Cnf(usize),
+    /// `p inccnf`: an incremental cnf problem in which the clauses are interleaved with cubes
+    Icnf,
+    /// `p wcnf <nb_vars> <nb_clauses> [<top>]`: an (old style) weighted maxsat problem having
+    /// `nb_vars` variables. The clauses whose weight is at least `top` (when given) are hard.
+    Wcnf(usize, Option<Weight>),
+    /// There is no problem line: this is how the (2022 style) weighted maxsat problems look like.
+    /// The first line of the problem (which had to be read to find it out) is kept here.
+    Headerless(String),
+    /// `* #variable= <nb_vars> #constraint= <nb_constraints>`: a pseudo-Boolean problem (in the
+    /// OPB format) having `nb_vars` variables
+    Opb(usize),
+    /// `p gcnf <nb_vars> <nb_clauses> <nb_groups>`: a group cnf problem having `nb_vars` variables
+    /// whose clauses are partitioned in groups (the clauses of group 0 are hard)
+    Gcnf(usize)
+}
+
+/// Reads the input up to (and including) its problem line and returns the corresponding header.
+/// An empty input is considered to be an empty cnf problem.
+pub fn read_header<Source>(input : &mut Lines<Source>) -> Header
+    where Source : io::BufRead {
+
+    read_header_and_projection(input).0
+}
+
+/// Same as `read_header`, except that the projection variables declared by the comments which
+/// precede the problem line (see `load_clauses_and_projection`) are returned along with the header.
+pub fn read_header_and_projection<Source>(input : &mut Lines<Source>) -> (Header, Vec<usize>)
     where Source : io::BufRead {
 
+    let mut projection = vec![];
     for line in input {
         let line = line.unwrap();
         let line = line.trim();
 
-        // it's a comment, skip it
-        if line.starts_with("c ") { continue; }
+        // it's a comment, skip it (unless it declares projection variables)
+        if let Some(shown) = projection_of(line) {
+            projection.extend(shown);
+            continue;
+        }
+        if line.starts_with("c ") || line == "c" || line.is_empty() { continue; }
 
         // it's the header, keep it
         if line.starts_with("p cnf ") {
             let mut tokens = line.split_whitespace();
-            return Solver::new(tokens.nth(2).unwrap().parse::<usize>().unwrap());
+            return (Header::Cnf(tokens.nth(2).unwrap().parse::<usize>().unwrap()), projection);
+        }
+        if line.starts_with("p gcnf ") {
+            let mut tokens = line.split_whitespace();
+            return (Header::Gcnf(tokens.nth(2).unwrap().parse::<usize>().unwrap()), projection);
+        }
+        if line.starts_with("p inccnf") {
+            return (Header::Icnf, projection);
+        }
+        if line.starts_with("p wcnf ") {
+            let mut tokens = line.split_whitespace().skip(2);
+            let nb_vars = tokens.next().unwrap().parse::<usize>().unwrap();
+            let top     = tokens.nth(1).map(|t| t.parse::<Weight>().unwrap());
+            return (Header::Wcnf(nb_vars, top), projection);
+        }
+
+        // OPB comments start with a '*', and so does its header
+        if line.starts_with('*') {
+            let mut tokens = line.split_whitespace().skip_while(|&t| t != "#variable=").skip(1);
+            let nb_vars = tokens.next().map_or(0, |t| t.parse::<usize>().unwrap());
+            return (Header::Opb(nb_vars), projection);
+        }
+
+        return (Header::Headerless(line.to_string()), projection);
+    }
+
+    (Header::Cnf(0), projection)
+}
+
+/// Returns the variables of the projection declared by the given line when it is a
+/// `c ind v1 v2 ... 0` or a `c p show v1 v2 ... 0` comment, None otherwise.
+fn projection_of(line: &str) -> Option<Vec<usize>> {
+    let comment = line.strip_prefix("c ")?.trim_start();
+    let shown   = comment.strip_prefix("ind ")
+        .or_else(|| comment.strip_prefix("p show "))?;
+
+    // (a comment merely starting like a declaration, e.g. `c ind ependent set`, is no declaration)
+    let shown = shown.split_whitespace()
+        .map(|t| t.parse::<usize>().ok())
+        .collect::<Option<Vec<usize>>>()?;
+    Some(shown.into_iter().take_while(|&v| v != 0).collect())
+}
+
+/// Returns the given projection variables, after having added those beyond the number of
+/// variables of the `solver` to it (a projection variable the header does not account for is a
+/// free variable).
+pub fn declare_projection(solver: &mut Solver, projection: &[usize]) -> Vec<Variable> {
+    for &v in projection.iter() {
+        while solver.nb_vars() < v {
+            solver.new_var();
         }
     }
+    projection.iter().map(|&v| Variable::from(v)).collect()
+}
 
-    return Solver::new(0);
+pub fn parse_header<Source>(input : &mut Lines<Source>) -> Solver
+    where Source : io::BufRead {
+
+    match read_header(input) {
+        Header::Cnf(nb_vars)    => Solver::new(nb_vars),
+        Header::Wcnf(nb_vars, _)=> Solver::new(nb_vars),
+        Header::Opb(nb_vars)    => Solver::new(nb_vars),
+        Header::Gcnf(nb_vars)   => Solver::new(nb_vars),
+        // the variables are only known as the problem is read
+        _                       => Solver::new(0)
+    }
 }
 
 pub fn load_clauses<Source>(solver: &mut Solver, input: &mut Lines<Source>)
     where Source : io::BufRead {
 
+    load_clauses_and_projection(solver, input);
+}
+
+/// Loads the clauses read from `input` into `solver` just like `load_clauses` does, and returns
+/// the variables of the projection (aka sampling set) declared in the comments of the problem
+/// with `c ind v1 v2 ... 0` or `c p show v1 v2 ... 0` lines. The projection is empty when none is
+/// declared. The projection variables beyond the number of variables given in the header are
+/// added to the solver (see `declare_projection`). The declarations which precede the header are
+/// read by `read_header_and_projection`.
+pub fn load_clauses_and_projection<Source>(solver: &mut Solver, input: &mut Lines<Source>) -> Vec<Variable>
+    where Source : io::BufRead {
+
+    let mut projection     = vec![];
     let mut ongoing_clause = vec![];
     for line in input {
         let line = line.unwrap();
         let line = line.trim();
-        // it's a comment, skip it
-        if line.starts_with("c ") { continue; }
+        // it's a comment, skip it (unless it declares projection variables)
+        if line.starts_with("c ") {
+            if let Some(shown) = projection_of(line) {
+                projection.extend(declare_projection(solver, &shown));
+            }
+            continue;
+        }
+
+        // it's an XOR constraint (`x1 -2 3 0` or `x 1 -2 3 0` as in CryptoMiniSat)
+        if let Some(xor) = line.strip_prefix('x') {
+            let literals: Vec<iint> = xor.split_whitespace()
+                .map(|t| t.parse::<iint>().unwrap())
+                .take_while(|&l| l != 0)
+                .collect();
+            solver.add_xor(&literals);
+            continue;
+        }
 
         let tokens = line.split_whitespace();
         for token in tokens {
@@ -45,7 +177,7 @@ pub fn load_clauses<Source>(solver: &mut Solver, input: &mut Lines<Source>)
                 ongoing_clause.push(lit);
             } else {
                 if solver.add_problem_clause(&mut ongoing_clause).is_err() {
-                    return;
+                    return projection;
                 }
                 ongoing_clause.clear();
             }
@@ -57,8 +189,657 @@ pub fn load_clauses<Source>(solver: &mut Solver, input: &mut Lines<Source>)
         // Note: the 'return' statement is useless, it only serves the point of using the result
         // of `solver.add_problem_clause()` and removing a compilation warning while doing so.
         if solver.add_problem_clause(&mut ongoing_clause).is_err() {
-            return; /* actually, this means ignore the error: solver is in unsat = true mode. */
+            return projection; /* actually, this means ignore the error: solver is in unsat = true mode. */
         }
     }
 
-}
\ No newline at end of file
+    projection
+}
+
+/// Loads the clauses read from `input` (past its header) into `mus`: the MUS is a subset of these
+/// clauses, whose indices are their positions in the input. The XOR constraints (`x` lines) are
+/// hard constraints.
+pub fn load_mus<Source>(mus: &mut Mus, input: &mut Lines<Source>)
+    where Source : io::BufRead {
+
+    let mut ongoing_clause = vec![];
+    for line in input {
+        let line = line.unwrap();
+        let line = line.trim();
+        // it's a comment, skip it
+        if line.starts_with('c') { continue; }
+
+        if let Some(xor) = line.strip_prefix('x') {
+            let literals: Vec<iint> = xor.split_whitespace()
+                .map(|t| t.parse::<iint>().unwrap())
+                .take_while(|&l| l != 0)
+                .collect();
+            mus.add_hard_xor(&literals);
+            continue;
+        }
+
+        for token in line.split_whitespace() {
+            let lit = token.parse::<iint>().unwrap();
+            if lit != 0 {
+                ongoing_clause.push(lit);
+            } else {
+                mus.add_clause(&ongoing_clause);
+                ongoing_clause.clear();
+            }
+        }
+    }
+
+    // the very last clause may lack its trailing zero
+    if !ongoing_clause.is_empty() {
+        mus.add_clause(&ongoing_clause);
+    }
+}
+
+/// Loads the group clauses read from `input` (past its header) into `mus`. Each clause comes on a
+/// line of its own, prefixed with its group between braces (`{g} 1 -2 0`): the clauses of group 0
+/// are hard, and the group g > 0 has the index g-1 in `mus`.
+pub fn load_gcnf<Source>(mus: &mut Mus, input: &mut Lines<Source>)
+    where Source : io::BufRead {
+
+    for line in input {
+        let line = line.unwrap();
+        let line = line.trim();
+        // it's a comment, skip it
+        if line.starts_with('c') || line.is_empty() { continue; }
+
+        let (group, clause) = line[1..].split_once('}').unwrap();
+        let group  = group.trim().parse::<usize>().unwrap();
+        let mut clause: Vec<iint> = clause.split_whitespace()
+            .map(|t| t.parse::<iint>().unwrap())
+            .take_while(|&l| l != 0)
+            .collect();
+
+        if group == 0 {
+            mus.add_hard_clause(&mut clause);
+        } else {
+            while mus.nb_groups() < group {
+                mus.new_group();
+            }
+            mus.add_group_clause(group - 1, &clause);
+        }
+    }
+}
+
+/// Loads the weighted clauses read from `input` (past its header) into `maxsat`. The clauses whose
+/// weight is at least `top` (when given) are hard.
+pub fn load_wcnf<Source>(maxsat: &mut MaxSat, input: &mut Lines<Source>, top: Option<Weight>)
+    where Source : io::BufRead {
+
+    for line in input {
+        load_wcnf_line(maxsat, &line.unwrap(), top);
+    }
+}
+
+/// Loads the weighted clause on the given `line` into `maxsat`. The line is either a comment, a
+/// hard clause (`h <lits> 0`, 2022 style) or a clause and its weight (`<weight> <lits> 0`). In
+/// the latter case, the clause is hard if its weight is at least `top` (when given).
+pub fn load_wcnf_line(maxsat: &mut MaxSat, line: &str, top: Option<Weight>) {
+    let line = line.trim();
+    // it's a comment, skip it
+    if line.starts_with('c') || line.is_empty() { return; }
+
+    let mut tokens = line.split_whitespace();
+    let weight = match tokens.next().unwrap() {
+        "h"    => None,
+        weight => {
+            let weight = weight.parse::<Weight>().unwrap();
+            if top.is_some_and(|top| weight >= top) { None } else { Some(weight) }
+        }
+    };
+
+    let mut clause: Vec<iint> = tokens
+        .map(|t| t.parse::<iint>().unwrap())
+        .take_while(|&l| l != 0)
+        .collect();
+
+    match weight {
+        None         => maxsat.add_hard_clause(&mut clause),
+        Some(weight) => maxsat.add_soft_clause(weight, &clause)
+    }
+}
+
+/// Loads the constraints and objective read from the OPB `input` (past its header) into `pb`.
+/// Every statement of the input is terminated by a ';' and may span several lines.
+pub fn load_opb<Source>(pb: &mut PseudoBoolean, input: &mut Lines<Source>)
+    where Source : io::BufRead {
+
+    let mut statement = String::new();
+    for line in input {
+        let line = line.unwrap();
+        let line = line.trim();
+        // it's a comment, skip it
+        if line.starts_with('*') { continue; }
+
+        statement.push_str(line);
+        statement.push(' ');
+        while let Some(end) = statement.find(';') {
+            load_opb_statement(pb, &statement[..end]);
+            statement.drain(..=end);
+        }
+    }
+}
+
+/// Loads the given OPB `statement` (without its terminating ';') into `pb`. The statement is
+/// either the objective (`min: <terms>`) or a constraint (`<terms> <relation> <rhs>`) where every
+/// term is a coefficient followed by a literal (`x<var>` or `~x<var>`) and the relation is one
+/// of `>=`, `=` or `<=`.
+///
+/// # Panics
+/// - when a term is not linear (a product of literals)
+pub fn load_opb_statement(pb: &mut PseudoBoolean, statement: &str) {
+    let statement = statement.trim();
+    if statement.is_empty() { return; }
+
+    let (is_objective, statement) = if let Some(terms) = statement.strip_prefix("min:") {
+        (true, terms)
+    } else {
+        (false, statement)
+    };
+
+    let mut terms       = vec![];
+    let mut coefficient = None;
+    let mut relation    = None;
+    let mut rhs         = 0;
+    for token in statement.split_whitespace() {
+        match token {
+            ">=" => relation = Some(Relation::AtLeast),
+            "="  => relation = Some(Relation::Equal),
+            "<=" => relation = Some(Relation::AtMost),
+            _ if relation.is_some() => rhs = token.parse::<Coefficient>().unwrap(),
+            _ if token.starts_with('x') || token.starts_with("~x") => {
+                let var = token.trim_start_matches('~')[1..].parse::<iint>().unwrap();
+                let literal = if token.starts_with('~') { -var } else { var };
+                let coefficient = coefficient.take()
+                    .unwrap_or_else(|| panic!("non linear term {} is not supported", token));
+                terms.push((coefficient, literal));
+            },
+            _ => coefficient = Some(token.parse::<Coefficient>().unwrap())
+        }
+    }
+
+    match relation {
+        Some(relation)      => pb.add_constraint(&terms, relation, rhs),
+        None if is_objective=> pb.set_objective(&terms),
+        None                => panic!("the constraint {} has no relation", statement)
+    }
+}
+
+/// Loads the formulas read from `input` into `circuit`. Every (non empty) line holds one formula
+/// written in the infix syntax of `Formula::parse`, and whatever follows a '#' is a comment.
+///
+/// # Panics
+/// - when a line is not a well formed formula
+pub fn load_formulas<Source>(circuit: &mut Circuit, input: &mut Lines<Source>)
+    where Source : io::BufRead {
+
+    for (number, line) in input.enumerate() {
+        let line = line.unwrap();
+        let line = line.split('#').next().unwrap().trim();
+        if line.is_empty() { continue; }
+
+        match Formula::parse(line) {
+            Ok(formula) => circuit.add_formula(&formula),
+            Err(message)=> panic!("line {}: {}", number + 1, message)
+        }
+    }
+}
+
+/// Loads the circuit read from the given AIGER `input`, which is either in the ASCII (`aag`) or in
+/// the binary (`aig`) format. The justice and fairness properties, the symbol table and the
+/// comments are ignored.
+///
+/// # Panics
+/// - when the input is not a well formed AIGER file
+pub fn load_aiger<Source>(input: &mut Source) -> Aiger
+    where Source : io::BufRead {
+
+    let header = aiger_line(input);
+    let mut tokens = header.split_whitespace();
+    let binary = match tokens.next() {
+        Some("aag") => false,
+        Some("aig") => true,
+        _           => panic!("not an AIGER file: {}", header)
+    };
+    // M I L O A [B C J F]
+    let counts: Vec<u32> = tokens.map(|t| t.parse::<u32>().unwrap()).collect();
+    assert!(counts.len() >= 5, "malformed AIGER header: {}", header);
+    let count = |i: usize| counts.get(i).cloned().unwrap_or(0);
+    let (nb_inputs, nb_latches) = (count(1), count(2));
+
+    let mut aiger = Aiger { max_var: count(0), ..Aiger::default() };
+    // (the binary format leaves out the literals of the inputs and latches: they come first)
+    aiger.inputs = if binary {
+        (1..=nb_inputs).map(|v| 2 * v).collect()
+    } else {
+        (0..nb_inputs).map(|_| aiger_numbers(input)[0]).collect()
+    };
+    for i in 0..nb_latches {
+        let numbers = aiger_numbers(input);
+        let (current, rest) = if binary {
+            (2 * (nb_inputs + i + 1), &numbers[..])
+        } else {
+            (numbers[0], &numbers[1..])
+        };
+        aiger.latches.push(Latch { current, next: rest[0], reset: rest.get(1).cloned().unwrap_or(0) });
+    }
+    aiger.outputs     = (0..count(3)).map(|_| aiger_numbers(input)[0]).collect();
+    aiger.bad         = (0..count(5)).map(|_| aiger_numbers(input)[0]).collect();
+    aiger.constraints = (0..count(6)).map(|_| aiger_numbers(input)[0]).collect();
+
+    // the justice properties (their sizes, then their literals) and the fairness constraints
+    let justice: u32 = (0..count(7)).map(|_| aiger_numbers(input)[0]).sum();
+    for _ in 0..justice + count(8) {
+        aiger_line(input);
+    }
+
+    for i in 0..count(4) {
+        if binary {
+            // lhs is implicit, rhs0 and rhs1 are given as the (7 bits encoded) deltas
+            // lhs - rhs0 and rhs0 - rhs1
+            let lhs  = 2 * (nb_inputs + nb_latches + i + 1);
+            let rhs0 = lhs  - aiger_delta(input);
+            let rhs1 = rhs0 - aiger_delta(input);
+            aiger.ands.push((lhs, rhs0, rhs1));
+        } else {
+            let numbers = aiger_numbers(input);
+            aiger.ands.push((numbers[0], numbers[1], numbers[2]));
+        }
+    }
+    aiger
+}
+
+/// Reads the next line of an AIGER input
+fn aiger_line<Source: io::BufRead>(input: &mut Source) -> String {
+    let mut line = String::new();
+    let read = input.read_line(&mut line).unwrap();
+    assert!(read > 0, "truncated AIGER file");
+    line
+}
+
+/// Reads the numbers of the next line of an AIGER input
+fn aiger_numbers<Source: io::BufRead>(input: &mut Source) -> Vec<AigLiteral> {
+    aiger_line(input).split_whitespace().map(|t| t.parse::<AigLiteral>().unwrap()).collect()
+}
+
+/// Reads the next delta of the and gates of a binary AIGER input: 7 bits per byte, the least
+/// significant ones first, the high bit of every byte but the last one being set.
+fn aiger_delta<Source: io::BufRead>(input: &mut Source) -> AigLiteral {
+    let mut delta = 0;
+    let mut shift = 0;
+    loop {
+        let mut byte = [0];
+        input.read_exact(&mut byte).expect("truncated AIGER file");
+        delta |= ((byte[0] & 0x7f) as AigLiteral) << shift;
+        if byte[0] & 0x80 == 0 { return delta; }
+        shift += 7;
+    }
+}
+
+/// An item of an iCNF input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IcnfItem {
+    /// A clause to add to the problem
+    Clause(Vec<iint>),
+    /// A cube (an `a` line) under which the problem must be solved
+    Cube(Vec<iint>)
+}
+
+/// Reads the clauses and cubes of an iCNF input (whose header has already been read) in the
+/// order in which they appear.
+pub struct IcnfReader<'a, Source: 'a> {
+    /// The lines of the input
+    lines  : &'a mut Lines<Source>,
+    /// The items which were read but not consumed yet
+    items  : VecDeque<IcnfItem>,
+    /// The literals of the item currently being read
+    ongoing: Vec<iint>,
+    /// Whether the item currently being read is a cube
+    is_cube: bool
+}
+
+impl<'a, Source: io::BufRead> IcnfReader<'a, Source> {
+    /// Creates a reader over the given lines
+    pub fn new(lines: &'a mut Lines<Source>) -> IcnfReader<'a, Source> {
+        IcnfReader { lines, items: VecDeque::new(), ongoing: vec![], is_cube: false }
+    }
+
+    /// Turns the item being read into a complete item
+    fn complete(&mut self) {
+        let literals = mem::take(&mut self.ongoing);
+        let item = if self.is_cube { IcnfItem::Cube(literals) } else { IcnfItem::Clause(literals) };
+
+        self.items.push_back(item);
+        self.is_cube = false;
+    }
+}
+
+impl<'a, Source: io::BufRead> Iterator for IcnfReader<'a, Source> {
+    type Item = IcnfItem;
+
+    fn next(&mut self) -> Option<IcnfItem> {
+        while self.items.is_empty() {
+            let line = match self.lines.next() {
+                Some(line) => line.unwrap(),
+                None       => {
+                    // the very last item may lack its trailing zero
+                    if !self.ongoing.is_empty() { self.complete(); }
+                    break;
+                }
+            };
+            let line = line.trim();
+            // it's a comment, skip it
+            if line.starts_with("c ") { continue; }
+
+            for token in line.split_whitespace() {
+                if token == "a" {
+                    self.is_cube = true;
+                    continue;
+                }
+
+                let lit = token.parse::<iint>().unwrap();
+                if lit != 0 {
+                    self.ongoing.push(lit);
+                } else {
+                    self.complete();
+                }
+            }
+        }
+
+        self.items.pop_front()
+    }
+}
+
+/// Writes the given `clauses` and `cubes` in the iCNF format (the incremental cnf format used by
+/// the cube and conquer solvers): the clauses are written as in a DIMACS CNF file and every cube
+/// is written on a line of its own starting with `a` (for assumptions).
+pub fn write_icnf<Sink>(out: &mut Sink, clauses: &[Vec<Literal>], cubes: &[Vec<Literal>]) -> io::Result<()>
+    where Sink : io::Write {
+
+    writeln!(out, "p inccnf")?;
+    for clause in clauses.iter() {
+        writeln!(out, "{}", literals_line(clause))?;
+    }
+    for cube in cubes.iter() {
+        writeln!(out, "a {}", literals_line(cube))?;
+    }
+    Ok(())
+}
+
+/// Formats the given literals as a zero terminated DIMACS line
+fn literals_line(literals: &[Literal]) -> String {
+    let mut line = String::new();
+    for l in literals.iter() {
+        line.push_str(&format!("{} ", l.to_isize()));
+    }
+    line.push('0');
+    line
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Unit Tests
+// -----------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_header_recognizes_the_format() {
+        let mut cnf  = Cursor::new("c comment\np cnf 4 2\n1 2 0\n").lines();
+        let mut icnf = Cursor::new("c comment\np inccnf\n1 2 0\n").lines();
+
+        assert_eq!(read_header(&mut cnf),  Header::Cnf(4));
+        assert_eq!(read_header(&mut icnf), Header::Icnf);
+        assert_eq!(read_header(&mut Cursor::new("").lines()), Header::Cnf(0));
+    }
+
+    #[test]
+    fn load_clauses_reads_the_xor_constraints() {
+        let text = "p cnf 3 2\nx1 2 0\nx -2 3 0\n1 -3 0\n";
+        let mut lines = Cursor::new(text).lines();
+        let mut solver = parse_header(&mut lines);
+        load_clauses(&mut solver, &mut lines);
+
+        assert_eq!(solver.nb_xors(), 2);
+        assert!(solver.solve());
+        // x1 xor x2 and x2 = x3 (and x1 or -x3) leave x1, -x2, -x3 as the only model
+        assert_eq!(solver.get_value(lit(1)), Bool::True);
+        assert_eq!(solver.get_value(lit(2)), Bool::False);
+        assert_eq!(solver.get_value(lit(3)), Bool::False);
+    }
+
+    #[test]
+    fn load_clauses_and_projection_reads_the_projection_variables() {
+        let text = "p cnf 4 2\nc ind 1 3 0\nc a comment 2 0\n1 2 0\nc p show 4 0\n-3 4 0\n\
+                    c ind ependent set computed by B+E\n";
+        let mut lines = Cursor::new(text).lines();
+        let mut solver = parse_header(&mut lines);
+        let projection = load_clauses_and_projection(&mut solver, &mut lines);
+
+        assert_eq!(projection, vec![var(1), var(3), var(4)]);
+        assert!(solver.solve());
+
+        let mut lines = Cursor::new("p cnf 2 1\n1 2 0\n").lines();
+        let mut solver = parse_header(&mut lines);
+        assert!(load_clauses_and_projection(&mut solver, &mut lines).is_empty());
+    }
+
+    #[test]
+    fn read_header_and_projection_reads_the_projection_declared_before_the_header() {
+        let text = "c ind 1 3 0\nc ind ependent set\nc p show 5 0\np cnf 4 1\nc ind 2 0\n1 2 0\n";
+        let mut lines = Cursor::new(text).lines();
+        let (header, declared) = read_header_and_projection(&mut lines);
+        assert_eq!(header, Header::Cnf(4));
+        assert_eq!(declared, vec![1, 3, 5]);
+
+        let mut solver = Solver::new(4);
+        let mut projection = declare_projection(&mut solver, &declared);
+        projection.extend(load_clauses_and_projection(&mut solver, &mut lines));
+        assert_eq!(projection, vec![var(1), var(3), var(5), var(2)]);
+        assert_eq!(solver.nb_vars(), 5);
+
+        let mut lines = Cursor::new("c ind 1 0\np cnf 2 1\n1 2 0\n").lines();
+        assert_eq!(read_header(&mut lines), Header::Cnf(2));
+    }
+
+    #[test]
+    fn load_clauses_and_projection_declares_the_projection_variables_beyond_the_header() {
+        let mut lines = Cursor::new("p cnf 2 1\nc ind 5 0\n1 2 0\n").lines();
+        let mut solver = parse_header(&mut lines);
+        let projection = load_clauses_and_projection(&mut solver, &mut lines);
+
+        assert_eq!(projection, vec![var(5)]);
+        assert_eq!(solver.nb_vars(), 5);
+        assert!(solver.solve());
+    }
+
+    #[test]
+    fn read_header_recognizes_the_wcnf_formats() {
+        let mut old    = Cursor::new("c comment\np wcnf 4 2 10\n10 1 2 0\n").lines();
+        let mut no_top = Cursor::new("p wcnf 4 2\n3 1 2 0\n").lines();
+        let mut recent = Cursor::new("c comment\nh 1 2 0\n3 -1 0\n").lines();
+
+        assert_eq!(read_header(&mut old),    Header::Wcnf(4, Some(10)));
+        assert_eq!(read_header(&mut no_top), Header::Wcnf(4, None));
+        assert_eq!(read_header(&mut recent), Header::Headerless(String::from("h 1 2 0")));
+    }
+
+    #[test]
+    fn read_header_recognizes_the_opb_format() {
+        let mut opb     = Cursor::new("* #variable= 5 #constraint= 2\n* comment\n+1 x1 >= 1;\n").lines();
+        let mut no_vars = Cursor::new("* comment\n+1 x1 >= 1;\n").lines();
+
+        assert_eq!(read_header(&mut opb),     Header::Opb(5));
+        assert_eq!(read_header(&mut no_vars), Header::Opb(0));
+    }
+
+    #[test]
+    fn load_opb_reads_the_constraints_and_the_objective() {
+        let text = "* comment\nmin: +2 x1 -1 x2 +3 ~x3 ;\n+1 x1 +1 x2\n +1 x3 >= 2 ;\n-1 x2 = -1;\n+1 x1 <= 0 ;";
+        let mut pb = PseudoBoolean::new(0);
+        load_opb(&mut pb, &mut Cursor::new(text).lines());
+
+        assert_eq!(pb.nb_vars(), 3);
+        assert!(pb.has_objective());
+        assert_eq!(pb.minimize(|_| {}), Some(-1));
+        assert_eq!(pb.model(), &[false, true, true]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn load_opb_rejects_non_linear_terms() {
+        let mut pb = PseudoBoolean::new(0);
+        load_opb_statement(&mut pb, "+1 x1 x2 >= 1");
+    }
+
+    #[test]
+    fn load_formulas_reads_one_formula_per_line() {
+        let text = "# comment\na -> b & c  # trailing comment\n\n!c | d\na\n";
+        let mut circuit = Circuit::new();
+        load_formulas(&mut circuit, &mut Cursor::new(text).lines());
+
+        assert_eq!(circuit.nb_vars(), 4);
+        assert!(circuit.solve());
+        assert_eq!(circuit.model(), vec![("a", true), ("b", true), ("c", true), ("d", true)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "line 2: unexpected end of formula")]
+    fn load_formulas_tells_which_line_is_wrong() {
+        let mut circuit = Circuit::new();
+        load_formulas(&mut circuit, &mut Cursor::new("a | b\na & (b | c").lines());
+    }
+
+    /// A two bits counter (starting at 0) which is bad when both its bits are set
+    const COUNTER_AAG: &str = "aag 6 0 2 0 4 1\n2 3\n4 11\n12\n6 4 3\n8 5 2\n10 9 7\n12 4 2\nl0 low\nc\ncomment\n";
+
+    #[test]
+    fn load_aiger_reads_the_ascii_format() {
+        let aiger = load_aiger(&mut Cursor::new(COUNTER_AAG));
+
+        assert_eq!(aiger.max_var, 6);
+        assert!(aiger.inputs.is_empty());
+        assert_eq!(aiger.latches, vec![Latch { current: 2, next: 3,  reset: 0 },
+                                       Latch { current: 4, next: 11, reset: 0 }]);
+        assert_eq!(aiger.properties(), &[12]);
+        assert_eq!(aiger.ands, vec![(6, 4, 3), (8, 5, 2), (10, 9, 7), (12, 4, 2)]);
+
+        // 00 -> 01 -> 10 -> 11
+        let mut bmc = Bmc::new(aiger);
+        assert_eq!(bmc.check(2), None);
+        let witness = bmc.check(3).unwrap();
+        assert_eq!(witness.inputs.len(), 4);
+    }
+
+    #[test]
+    fn load_aiger_reads_the_binary_format() {
+        let mut binary = b"aig 6 0 2 0 4 1\n3\n11\n12\n".to_vec();
+        binary.extend_from_slice(&[2, 1, 3, 3, 1, 2, 8, 2]);
+        binary.extend_from_slice(b"l0 low\n");
+
+        assert_eq!(load_aiger(&mut Cursor::new(binary)), load_aiger(&mut Cursor::new(COUNTER_AAG)));
+    }
+
+    #[test]
+    fn load_aiger_decodes_the_multi_byte_deltas() {
+        // 200 inputs, lhs = 402 = 401 & 1 (ie: delta0 = 1 and delta1 = 400 = 0x90 0x03)
+        let mut binary = b"aig 201 200 0 1 1\n402\n".to_vec();
+        binary.extend_from_slice(&[1, 0x90, 0x03]);
+
+        let aiger = load_aiger(&mut Cursor::new(binary));
+        assert_eq!(aiger.inputs.len(), 200);
+        assert_eq!(aiger.outputs, vec![402]);
+        assert_eq!(aiger.ands, vec![(402, 401, 1)]);
+    }
+
+    #[test]
+    fn load_aiger_skips_the_justice_and_fairness_properties() {
+        let text = "aag 3 2 0 0 1 0 1 1 1\n2\n4\n5\n2\n2\n4\n3\n6 2 5\n";
+        let aiger = load_aiger(&mut Cursor::new(text));
+
+        assert_eq!(aiger.constraints, vec![5]);
+        assert_eq!(aiger.ands, vec![(6, 2, 5)]);
+    }
+
+    #[test]
+    fn load_mus_indexes_the_clauses_in_input_order() {
+        let text = "c comment\n1\n2 0\nx1 -3 0\n-2 0 -3";
+        let mut mus = Mus::new(3);
+        load_mus(&mut mus, &mut Cursor::new(text).lines());
+
+        assert_eq!(mus.nb_clauses(), 3);
+        assert_eq!(mus.group(0), vec![&[lit(1), lit(2)][..]]);
+        assert_eq!(mus.group(2), vec![&[lit(-3)][..]]);
+        // the (hard) XOR constraint tells that 1 and 3 are equivalent
+        assert_eq!(mus.extract(), Some(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn load_gcnf_partitions_the_clauses_in_groups() {
+        let text = "c comment\np gcnf 3 6 3\n{0} -1 -2 0\n{1} 1 0\n{3} 3 0\n{2} 2 0\n{1} -3 0\n{3} -3 2 0\n";
+        let mut lines = Cursor::new(text).lines();
+        assert_eq!(read_header(&mut lines), Header::Gcnf(3));
+
+        let mut mus = Mus::new(3);
+        load_gcnf(&mut mus, &mut lines);
+        assert_eq!(mus.nb_groups(), 3);
+        assert_eq!(mus.nb_clauses(), 5);
+        assert_eq!(mus.group(0), vec![&[lit(1)][..], &[lit(-3)][..]]);
+        assert_eq!(mus.group(2), vec![&[lit(3)][..], &[lit(-3), lit(2)][..]]);
+        // 1 and 2 cannot both hold (hard clause)
+        assert_eq!(mus.extract(), Some(vec![0, 1]));
+    }
+
+    #[test]
+    fn load_wcnf_distinguishes_the_hard_clauses() {
+        let mut maxsat = MaxSat::new(0);
+        load_wcnf_line(&mut maxsat, "h 1 2 0", None);
+        load_wcnf(&mut maxsat, &mut Cursor::new("c comment\n5 -1 0\n10 -2 0\n4 -2 0\n").lines(), Some(10));
+
+        assert_eq!(maxsat.nb_vars(), 2);
+        // -2 is hard (its weight reaches top), hence 1 must be true
+        assert_eq!(maxsat.solve(), Some(5));
+        assert_eq!(maxsat.model(), &[true, false]);
+    }
+
+    #[test]
+    fn icnf_reader_yields_the_clauses_and_cubes_in_order() {
+        let text = "p inccnf\n1 2 0\nc comment\na -1 0\n-2 3 0 4\n5 0\na 2 -3 0\n-4";
+        let mut lines = Cursor::new(text).lines();
+        read_header(&mut lines);
+
+        let items: Vec<IcnfItem> = IcnfReader::new(&mut lines).collect();
+        assert_eq!(items, vec![
+            IcnfItem::Clause(vec![1, 2]),
+            IcnfItem::Cube(vec![-1]),
+            IcnfItem::Clause(vec![-2, 3]),
+            IcnfItem::Clause(vec![4, 5]),
+            IcnfItem::Cube(vec![2, -3]),
+            IcnfItem::Clause(vec![-4])
+        ]);
+    }
+
+    #[test]
+    fn write_icnf_produces_what_the_reader_reads() {
+        let clauses = vec![vec![lit(1), lit(-2)], vec![lit(3)]];
+        let cubes   = vec![vec![lit(-1)], vec![]];
+
+        let mut out = vec![];
+        write_icnf(&mut out, &clauses, &cubes).unwrap();
+
+        let mut lines = Cursor::new(out).lines();
+        assert_eq!(read_header(&mut lines), Header::Icnf);
+
+        let items: Vec<IcnfItem> = IcnfReader::new(&mut lines).collect();
+        assert_eq!(items, vec![
+            IcnfItem::Clause(vec![1, -2]),
+            IcnfItem::Clause(vec![3]),
+            IcnfItem::Cube(vec![-1]),
+            IcnfItem::Cube(vec![])
+        ]);
+    }
+}
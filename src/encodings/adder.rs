@@ -0,0 +1,120 @@
+use std::collections::VecDeque;
+
+use core::*;
+use solving::*;
+use encodings::fresh_literal;
+
+/// Encodes `sum of w_i * l_i <= k` with a network of adders computing the sum in binary, which is
+/// then compared to k. Each literal is placed in the 'bucket' of every bit set in its weight, and
+/// each bucket is reduced to a single bit with full and half adders whose carries are moved to the
+/// next bucket. This yields an encoding whose size is O(n.log(max w_i)), at the price of a
+/// propagation that is weaker than that of the other encodings.
+///
+/// See `Translating Pseudo-Boolean Constraints into SAT` -- Eén, Sörensson (JSAT 2006).
+pub fn at_most(solver: &mut Solver, terms: &[(u64, Literal)], k: u64) {
+    let mut buckets: Vec<VecDeque<Literal>> = vec![];
+    for &(weight, literal) in terms.iter() {
+        for bit in 0..64 {
+            if weight & (1 << bit) != 0 {
+                bucket(&mut buckets, bit).push_back(literal);
+            }
+        }
+    }
+
+    // the bits of the sum (None stands for a bit which is always zero)
+    let mut sum: Vec<Option<Literal>> = vec![];
+    let mut bit = 0;
+    while bit < buckets.len() {
+        while buckets[bit].len() >= 3 {
+            let a = buckets[bit].pop_front().unwrap();
+            let b = buckets[bit].pop_front().unwrap();
+            let c = buckets[bit].pop_front().unwrap();
+            let (s, carry) = full_adder(solver, a, b, c);
+            buckets[bit].push_back(s);
+            bucket(&mut buckets, bit+1).push_back(carry);
+        }
+        if buckets[bit].len() == 2 {
+            let a = buckets[bit].pop_front().unwrap();
+            let b = buckets[bit].pop_front().unwrap();
+            let (s, carry) = half_adder(solver, a, b);
+            buckets[bit].push_back(s);
+            bucket(&mut buckets, bit+1).push_back(carry);
+        }
+        sum.push(buckets[bit].pop_front());
+        bit += 1;
+    }
+
+    // the sum exceeds k iff it has some bit j set while bit j of k is not, and all the more
+    // significant bits of the sum are equal to those of k. Hence, whenever bit j is set in the
+    // sum but not in k, some more significant bit set in k must not be set in the sum.
+    let k_bit = |j: usize| j < 64 && k & (1 << j) != 0;
+    for j in 0..sum.len() {
+        if k_bit(j) { continue; }
+        if let Some(s) = sum[j] {
+            let mut clause = vec![!s];
+            let mut satisfied = false;
+            for (h, &bit) in sum.iter().enumerate().skip(j+1) {
+                if !k_bit(h) { continue; }
+                match bit {
+                    Some(s) => clause.push(!s),
+                    // that bit of the sum is never set, hence the sum is smaller than k
+                    None    => { satisfied = true; break; }
+                }
+            }
+            if !satisfied { let _ = solver.add_clause_literals(&clause); }
+        }
+    }
+}
+
+/// Returns the given bucket, creating it (and those before it) when needed
+fn bucket(buckets: &mut Vec<VecDeque<Literal>>, bit: usize) -> &mut VecDeque<Literal> {
+    while buckets.len() <= bit {
+        buckets.push(VecDeque::new());
+    }
+    &mut buckets[bit]
+}
+
+/// Returns the sum and the carry of the three given literals
+fn full_adder(solver: &mut Solver, a: Literal, b: Literal, c: Literal) -> (Literal, Literal) {
+    let sum   = fresh_literal(solver);
+    let carry = fresh_literal(solver);
+
+    // sum <-> a xor b xor c
+    for signs in 0..8 {
+        let x = if signs & 1 != 0 { a } else { !a };
+        let y = if signs & 2 != 0 { b } else { !b };
+        let z = if signs & 4 != 0 { c } else { !c };
+        let odd = (signs as u32).count_ones() % 2 == 1;
+        // (this clause is falsified when a = !x, b = !y and c = !z)
+        let _ = solver.add_clause_literals(&[x, y, z, if odd { !sum } else { sum }]);
+    }
+
+    // carry <-> at least two of a, b, c
+    let _ = solver.add_clause_literals(&[!a, !b, carry]);
+    let _ = solver.add_clause_literals(&[!a, !c, carry]);
+    let _ = solver.add_clause_literals(&[!b, !c, carry]);
+    let _ = solver.add_clause_literals(&[a, b, !carry]);
+    let _ = solver.add_clause_literals(&[a, c, !carry]);
+    let _ = solver.add_clause_literals(&[b, c, !carry]);
+
+    (sum, carry)
+}
+
+/// Returns the sum and the carry of the two given literals
+fn half_adder(solver: &mut Solver, a: Literal, b: Literal) -> (Literal, Literal) {
+    let sum   = fresh_literal(solver);
+    let carry = fresh_literal(solver);
+
+    // sum <-> a xor b
+    let _ = solver.add_clause_literals(&[!a, !b, !sum]);
+    let _ = solver.add_clause_literals(&[ a,  b, !sum]);
+    let _ = solver.add_clause_literals(&[!a,  b,  sum]);
+    let _ = solver.add_clause_literals(&[ a, !b,  sum]);
+
+    // carry <-> a and b
+    let _ = solver.add_clause_literals(&[!a, !b, carry]);
+    let _ = solver.add_clause_literals(&[a, !carry]);
+    let _ = solver.add_clause_literals(&[b, !carry]);
+
+    (sum, carry)
+}
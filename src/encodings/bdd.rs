@@ -0,0 +1,97 @@
+use std::cmp::{max, min};
+
+use core::*;
+use solving::*;
+use encodings::fresh_literal;
+
+/// A node of the decision diagram
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Node {
+    /// The constraint is satisfied whatever the value of the remaining literals
+    True,
+    /// The constraint cannot be satisfied anymore
+    False,
+    /// The literal which holds only if the remaining constraint is satisfied
+    Lit(Literal)
+}
+
+/// Encodes `sum of w_i * l_i <= k` with a reduced ordered binary decision diagram. The node
+/// (i, k') represents the constraint `sum_{j >= i} w_j * l_j <= k'`: its hi child is (i+1, k'-w_i)
+/// and its lo child is (i+1, k'). Every node remembers the whole interval of bounds it stands for,
+/// so that the nodes representing the same constraint are shared.
+///
+/// See `BDDs for Pseudo-Boolean Constraints -- Revisited` -- Abío, Nieuwenhuis, Oliveras,
+/// Rodríguez-Carbonell (SAT 2011).
+pub fn at_most(solver: &mut Solver, terms: &[(u64, Literal)], k: u64) {
+    let mut builder = Builder::new(terms);
+    let (_, root) = builder.build(solver, 0, k as i64);
+
+    match root {
+        Node::True   => {},
+        Node::False  => { let _ = solver.add_clause_literals(&[]); },
+        Node::Lit(l) => { let _ = solver.add_clause_literals(&[l]); }
+    }
+}
+
+/// The state of the construction of the decision diagram
+struct Builder<'a> {
+    /// The terms of the constraint
+    terms: &'a [(u64, Literal)],
+    /// `rest[i]` is the sum of the weights of the terms i, i+1, ...
+    rest : Vec<i64>,
+    /// The nodes built so far at each level, along with the interval of bounds they stand for
+    memo : Vec<Vec<((i64, i64), Node)>>
+}
+
+impl<'a> Builder<'a> {
+    fn new(terms: &'a [(u64, Literal)]) -> Builder<'a> {
+        let mut rest = vec![0; terms.len() + 1];
+        for i in (0..terms.len()).rev() {
+            rest[i] = rest[i+1] + terms[i].0 as i64;
+        }
+
+        Builder { terms, rest, memo: vec![vec![]; terms.len() + 1] }
+    }
+
+    /// Returns the node representing `sum_{j >= i} w_j * l_j <= k` and the interval of all the
+    /// bounds for which that node represents the constraint.
+    fn build(&mut self, solver: &mut Solver, i: usize, k: i64) -> ((i64, i64), Node) {
+        if k < 0 {
+            return ((i64::MIN, -1), Node::False);
+        }
+        if self.rest[i] <= k {
+            return ((self.rest[i], i64::MAX), Node::True);
+        }
+        if let Some(&entry) = self.memo[i].iter().find(|&&((lo, hi), _)| lo <= k && k <= hi) {
+            return entry;
+        }
+
+        let (weight, literal) = self.terms[i];
+        let weight = weight as i64;
+        let ((lo_a, hi_a), a) = self.build(solver, i+1, k);
+        let ((lo_b, hi_b), b) = self.build(solver, i+1, k - weight);
+
+        let interval = (
+            max(lo_a, lo_b.saturating_add(weight)),
+            min(hi_a, hi_b.saturating_add(weight))
+        );
+
+        let node = if a == b { a } else {
+            // (the lo child is never false since k >= 0)
+            debug_assert!(a != Node::False);
+            let node = fresh_literal(solver);
+            // node -> (the constraint holds when literal is false)
+            if let Node::Lit(a) = a { let _ = solver.add_clause_literals(&[!node, a]); }
+            // node -> (the constraint holds when literal is true)
+            match b {
+                Node::True   => {},
+                Node::False  => { let _ = solver.add_clause_literals(&[!node, !literal]); },
+                Node::Lit(b) => { let _ = solver.add_clause_literals(&[!node, !literal, b]); }
+            }
+            Node::Lit(node)
+        };
+
+        self.memo[i].push((interval, node));
+        (interval, node)
+    }
+}
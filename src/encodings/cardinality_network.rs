@@ -0,0 +1,106 @@
+use core::*;
+use solving::*;
+use encodings::fresh_literal;
+
+/// Encodes `at most k of literals` with a cardinality network: a sorting network which only keeps
+/// track of the m = 2^ceil(log2(k+1)) greatest values. The literals are padded with false up to a
+/// multiple of m, split in chunks of m literals which are sorted and then merged pairwise (only
+/// keeping the m first outputs of each merge). Only the 'upward' half of each comparator is
+/// encoded since that is all the constraint needs.
+///
+/// See `Cardinality Networks: a theoretical and empirical study` -- Asín, Nieuwenhuis, Oliveras,
+/// Rodríguez-Carbonell (Constraints 2011).
+pub fn at_most(solver: &mut Solver, literals: &[Literal], k: usize) {
+    let m = (k + 1).next_power_of_two();
+
+    let mut padded = literals.to_vec();
+    if !padded.len().is_multiple_of(m) {
+        let falsum = fresh_literal(solver);
+        let _ = solver.add_clause_literals(&[!falsum]);
+        while !padded.len().is_multiple_of(m) {
+            padded.push(falsum);
+        }
+    }
+
+    let outputs = card(solver, &padded, m);
+    let _ = solver.add_clause_literals(&[!outputs[k]]);
+}
+
+/// Returns the m first outputs of a network sorting the given literals (in decreasing order).
+/// The number of literals must be a multiple of m, which must be a power of two.
+fn card(solver: &mut Solver, literals: &[Literal], m: usize) -> Vec<Literal> {
+    if literals.len() == m { return half_sort(solver, literals); }
+
+    let (first, rest) = literals.split_at(m);
+    let a = card(solver, first, m);
+    let b = card(solver, rest,  m);
+
+    let mut outputs = simplified_merge(solver, &a, &b);
+    outputs.truncate(m);
+    outputs
+}
+
+/// Sorts the given literals (whose number is a power of two)
+fn half_sort(solver: &mut Solver, literals: &[Literal]) -> Vec<Literal> {
+    if literals.len() == 2 { return comparator(solver, literals[0], literals[1]); }
+
+    let (first, second) = literals.split_at(literals.len() / 2);
+    let a = half_sort(solver, first);
+    let b = half_sort(solver, second);
+    half_merge(solver, &a, &b)
+}
+
+/// Merges the two sorted sequences `a` and `b` (having the same length) into one sorted sequence
+fn half_merge(solver: &mut Solver, a: &[Literal], b: &[Literal]) -> Vec<Literal> {
+    let n = a.len();
+    if n == 1 { return comparator(solver, a[0], b[0]); }
+
+    let d = half_merge(solver, &odds(a), &odds(b));
+    let e = half_merge(solver, &evens(a), &evens(b));
+
+    let mut outputs = vec![d[0]];
+    for i in 0..n-1 {
+        outputs.extend(comparator(solver, d[i+1], e[i]));
+    }
+    outputs.push(e[n-1]);
+    outputs
+}
+
+/// Merges the two sorted sequences `a` and `b` (having the same length n) but only keeps the n+1
+/// greatest values.
+fn simplified_merge(solver: &mut Solver, a: &[Literal], b: &[Literal]) -> Vec<Literal> {
+    let n = a.len();
+    if n == 1 { return comparator(solver, a[0], b[0]); }
+
+    let d = simplified_merge(solver, &odds(a), &odds(b));
+    let e = simplified_merge(solver, &evens(a), &evens(b));
+
+    let mut outputs = vec![d[0]];
+    for i in 0..n/2 {
+        outputs.extend(comparator(solver, d[i+1], e[i]));
+    }
+    outputs
+}
+
+/// Sorts the two literals: the first output is their disjunction, the second one is their
+/// conjunction.
+fn comparator(solver: &mut Solver, a: Literal, b: Literal) -> Vec<Literal> {
+    let max = fresh_literal(solver);
+    let min = fresh_literal(solver);
+
+    let _ = solver.add_clause_literals(&[!a, max]);
+    let _ = solver.add_clause_literals(&[!b, max]);
+    let _ = solver.add_clause_literals(&[!a, !b, min]);
+
+    vec![max, min]
+}
+
+/// The literals at the odd positions of the sequence (1st, 3rd, ... counting from 1)
+fn odds(literals: &[Literal]) -> Vec<Literal> {
+    literals.iter().step_by(2).cloned().collect()
+}
+
+/// The literals at the even positions of the sequence (2nd, 4th, ... counting from 1)
+fn evens(literals: &[Literal]) -> Vec<Literal> {
+    literals.iter().skip(1).step_by(2).cloned().collect()
+}
@@ -0,0 +1,432 @@
+mod pairwise;
+mod sequential_counter;
+mod totalizer;
+mod modulo_totalizer;
+mod cardinality_network;
+mod bdd;
+mod adder;
+mod xor;
+
+pub use self::totalizer::Totalizer;
+
+use std::cmp::Reverse;
+
+use core::*;
+use solving::*;
+
+/// The encodings turning the cardinality constraints (at most k, at least k or exactly k of some
+/// literals are true) into clauses. The auxiliary variables an encoding needs are allocated
+/// through the solver itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CardinalityEncoding {
+    /// One clause per subset of k+1 literals (no auxiliary variable, only sensible for tiny k)
+    Pairwise,
+    /// Sinz' sequential counter: O(n.k) clauses and auxiliary variables
+    SequentialCounter,
+    /// Bailleux and Boufkhad's totalizer: O(n.log(n)) auxiliary variables, O(n^2) clauses
+    #[default]
+    Totalizer,
+    /// Ogawa et al.'s modulo totalizer: a totalizer counting modulo sqrt(k)
+    ModuloTotalizer,
+    /// Asín et al.'s cardinality networks: O(n.log^2(k)) clauses and auxiliary variables
+    CardinalityNetwork
+}
+
+impl CardinalityEncoding {
+    /// Returns the encoding having the given `name` (one of pairwise, seqcounter, totalizer,
+    /// mtotalizer or cardnetwork) or None when no encoding bears that name.
+    pub fn from_name(name: &str) -> Option<CardinalityEncoding> {
+        match name {
+            "pairwise"    => Some(CardinalityEncoding::Pairwise),
+            "seqcounter"  => Some(CardinalityEncoding::SequentialCounter),
+            "totalizer"   => Some(CardinalityEncoding::Totalizer),
+            "mtotalizer"  => Some(CardinalityEncoding::ModuloTotalizer),
+            "cardnetwork" => Some(CardinalityEncoding::CardinalityNetwork),
+            _             => None
+        }
+    }
+}
+
+/// Adds the clauses (and auxiliary variables) enforcing that at most `k` of the given `literals`
+/// are true to the solver.
+pub fn at_most_k(solver: &mut Solver, literals: &[Literal], k: usize, encoding: CardinalityEncoding) {
+    // the constraint is trivially satisfied
+    if k >= literals.len() { return; }
+
+    if k == 0 {
+        for &l in literals.iter() {
+            let _ = solver.add_clause_literals(&[!l]);
+        }
+        return;
+    }
+
+    match encoding {
+        CardinalityEncoding::Pairwise           => pairwise::at_most(solver, literals, k),
+        CardinalityEncoding::SequentialCounter  => sequential_counter::at_most(solver, literals, k),
+        CardinalityEncoding::Totalizer          => totalizer::at_most(solver, literals, k),
+        CardinalityEncoding::ModuloTotalizer    => modulo_totalizer::at_most(solver, literals, k),
+        CardinalityEncoding::CardinalityNetwork => cardinality_network::at_most(solver, literals, k)
+    }
+}
+
+/// Adds the clauses (and auxiliary variables) enforcing that at least `k` of the given `literals`
+/// are true to the solver. (At least k of the literals are true iff at most n-k of them are false).
+pub fn at_least_k(solver: &mut Solver, literals: &[Literal], k: usize, encoding: CardinalityEncoding) {
+    if k > literals.len() {
+        // the constraint cannot be satisfied
+        let _ = solver.add_clause_literals(&[]);
+        return;
+    }
+
+    let negated: Vec<Literal> = literals.iter().map(|&l| !l).collect();
+    at_most_k(solver, &negated, literals.len() - k, encoding);
+}
+
+/// Adds the clauses (and auxiliary variables) enforcing that exactly `k` of the given `literals`
+/// are true to the solver.
+pub fn exactly_k(solver: &mut Solver, literals: &[Literal], k: usize, encoding: CardinalityEncoding) {
+    at_most_k (solver, literals, k, encoding);
+    at_least_k(solver, literals, k, encoding);
+}
+
+/// The encodings turning the pseudo-Boolean constraints (linear constraints over literals having
+/// positive integer weights) into clauses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PbEncoding {
+    /// Abío et al.'s reduced ordered binary decision diagrams (arc consistent, possibly large)
+    #[default]
+    Bdd,
+    /// Eén and Sörensson's adder networks: O(n.log(max w)) clauses, but a weak propagation
+    Adder,
+    /// Asín et al.'s cardinality networks where each literal is repeated as many times as its
+    /// weight (only sensible for small weights)
+    Sorter
+}
+
+impl PbEncoding {
+    /// Returns the encoding having the given `name` (one of bdd, adder or sorter) or None when no
+    /// encoding bears that name.
+    pub fn from_name(name: &str) -> Option<PbEncoding> {
+        match name {
+            "bdd"    => Some(PbEncoding::Bdd),
+            "adder"  => Some(PbEncoding::Adder),
+            "sorter" => Some(PbEncoding::Sorter),
+            _        => None
+        }
+    }
+}
+
+/// Adds the clauses (and auxiliary variables) enforcing that the sum of the weights of the true
+/// literals among `terms` is at most `k` to the solver.
+pub fn pb_at_most(solver: &mut Solver, terms: &[(u64, Literal)], k: u64, encoding: PbEncoding) {
+    let mut kept: Vec<(u64, Literal)> = Vec::with_capacity(terms.len());
+    let mut total = 0u64;
+    for &(weight, literal) in terms.iter().filter(|&&(w, _)| w > 0) {
+        if weight > k {
+            // that literal alone would violate the constraint
+            let _ = solver.add_clause_literals(&[!literal]);
+        } else {
+            total += weight;
+            kept.push((weight, literal));
+        }
+    }
+    let mut terms = kept;
+    // the constraint is trivially satisfied
+    if total <= k { return; }
+
+    // dividing the weights by their gcd simplifies the encoded constraint
+    let divisor = terms.iter().fold(0, |g, &(w, _)| gcd(g, w));
+    let k = k / divisor;
+    for term in terms.iter_mut() { term.0 /= divisor; }
+
+    // the heaviest terms first make smaller diagrams
+    terms.sort_by_key(|&(w, _)| Reverse(w));
+
+    match encoding {
+        PbEncoding::Bdd    => bdd::at_most(solver, &terms, k),
+        PbEncoding::Adder  => adder::at_most(solver, &terms, k),
+        PbEncoding::Sorter => {
+            let literals: Vec<Literal> = terms.iter()
+                .flat_map(|&(w, l)| (0..w).map(move |_| l))
+                .collect();
+            cardinality_network::at_most(solver, &literals, k as usize)
+        }
+    }
+}
+
+/// Adds the clauses (and auxiliary variables) enforcing that the sum of the weights of the true
+/// literals among `terms` is at least `k` to the solver. (The weight of the true literals is at
+/// least k iff the weight of the false ones is at most W-k where W is the sum of all the weights).
+pub fn pb_at_least(solver: &mut Solver, terms: &[(u64, Literal)], k: u64, encoding: PbEncoding) {
+    let total: u64 = terms.iter().map(|&(w, _)| w).sum();
+    if k > total {
+        // the constraint cannot be satisfied
+        let _ = solver.add_clause_literals(&[]);
+        return;
+    }
+
+    let negated: Vec<(u64, Literal)> = terms.iter().map(|&(w, l)| (w, !l)).collect();
+    pb_at_most(solver, &negated, total - k, encoding);
+}
+
+/// Adds the clauses (and auxiliary variables) enforcing that the sum of the weights of the true
+/// literals among `terms` is exactly `k` to the solver.
+pub fn pb_exactly(solver: &mut Solver, terms: &[(u64, Literal)], k: u64, encoding: PbEncoding) {
+    pb_at_most (solver, terms, k, encoding);
+    pb_at_least(solver, terms, k, encoding);
+}
+
+/// Adds the clauses (and auxiliary variables) defining a literal which is true iff an odd number of
+/// the given `literals` are true to the solver, and returns that literal.
+pub fn xor_literal(solver: &mut Solver, literals: &[Literal]) -> Literal {
+    xor::define(solver, literals, None)
+}
+
+/// Same as `xor_literal` except that the definition of the literal only holds as long as the
+/// given `guard` is true. Once the guard is falsified, the literal and the auxiliary variables of
+/// the definition are left unconstrained (ie: they can be fixed at will).
+pub fn guarded_xor_literal(solver: &mut Solver, guard: Literal, literals: &[Literal]) -> Literal {
+    xor::define(solver, literals, Some(guard))
+}
+
+/// Adds the clauses (and auxiliary variables) enforcing that an odd number of the given `literals`
+/// (an even number when `parity` is false) are true to the solver.
+pub fn xor_constraint(solver: &mut Solver, literals: &[Literal], parity: bool) {
+    let xor = xor_literal(solver, literals);
+    let _ = solver.add_clause_literals(&[if parity { xor } else { !xor }]);
+}
+
+/// Returns the greatest common divisor of a and b
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Allocates a new auxiliary variable and returns its positive literal
+fn fresh_literal(solver: &mut Solver) -> Literal {
+    Literal::positive(solver.new_var())
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Unit Tests
+// -----------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENCODINGS: [CardinalityEncoding; 5] = [
+        CardinalityEncoding::Pairwise,
+        CardinalityEncoding::SequentialCounter,
+        CardinalityEncoding::Totalizer,
+        CardinalityEncoding::ModuloTotalizer,
+        CardinalityEncoding::CardinalityNetwork
+    ];
+
+    /// Checks that the constraint added by `encode` on n literals admits exactly the assignments
+    /// of these literals satisfying `expected` (given their number of true literals).
+    fn check<E, X>(n: usize, k: usize, encoding: CardinalityEncoding, encode: E, expected: X)
+        where E: Fn(&mut Solver, &[Literal], usize, CardinalityEncoding),
+              X: Fn(usize) -> bool {
+
+        let mut solver = Solver::new(n);
+        let literals: Vec<Literal> = (1..=n).map(|v| lit(v as iint)).collect();
+        encode(&mut solver, &literals, k, encoding);
+
+        for bits in 0..(1 << n) {
+            let assumptions: Vec<Literal> = literals.iter().enumerate()
+                .map(|(i, &l)| if bits & (1 << i) != 0 { l } else { !l })
+                .collect();
+            let count = (bits as u32).count_ones() as usize;
+
+            assert_eq!(solver.solve_with_assumptions(&assumptions), expected(count),
+                "{:?} n={} k={} count={}", encoding, n, k, count);
+        }
+    }
+
+    #[test]
+    fn at_most_k_admits_the_assignments_having_at_most_k_true_literals() {
+        for &encoding in ENCODINGS.iter() {
+            for n in 0..7 {
+                for k in 0..n+2 {
+                    check(n, k, encoding, at_most_k, |count| count <= k);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn at_least_k_admits_the_assignments_having_at_least_k_true_literals() {
+        for &encoding in ENCODINGS.iter() {
+            for n in 0..7 {
+                for k in 0..n+2 {
+                    check(n, k, encoding, at_least_k, |count| count >= k);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn exactly_k_admits_the_assignments_having_exactly_k_true_literals() {
+        for &encoding in ENCODINGS.iter() {
+            for n in 0..7 {
+                for k in 0..n+2 {
+                    check(n, k, encoding, exactly_k, |count| count == k);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn larger_constraints_are_correctly_encoded() {
+        for &encoding in ENCODINGS.iter().skip(1) {
+            for k in [0, 1, 3, 4, 5, 8, 9].iter() {
+                check(10, *k, encoding, at_most_k, |count| count <= *k);
+            }
+        }
+    }
+
+    const PB_ENCODINGS: [PbEncoding; 3] = [PbEncoding::Bdd, PbEncoding::Adder, PbEncoding::Sorter];
+
+    /// Checks that the pseudo-Boolean constraint added by `encode` on the given weights admits
+    /// exactly the assignments satisfying `expected` (given the weight of their true literals).
+    fn check_pb<E, X>(weights: &[u64], k: u64, encoding: PbEncoding, encode: E, expected: X)
+        where E: Fn(&mut Solver, &[(u64, Literal)], u64, PbEncoding),
+              X: Fn(u64) -> bool {
+
+        let n = weights.len();
+        let mut solver = Solver::new(n);
+        let terms: Vec<(u64, Literal)> = weights.iter().enumerate()
+            .map(|(i, &w)| (w, lit(i as iint + 1)))
+            .collect();
+        encode(&mut solver, &terms, k, encoding);
+
+        for bits in 0..(1 << n) {
+            let assumptions: Vec<Literal> = terms.iter().enumerate()
+                .map(|(i, &(_, l))| if bits & (1 << i) != 0 { l } else { !l })
+                .collect();
+            let weight: u64 = weights.iter().enumerate()
+                .filter(|&(i, _)| bits & (1 << i) != 0)
+                .map(|(_, &w)| w)
+                .sum();
+
+            assert_eq!(solver.solve_with_assumptions(&assumptions), expected(weight),
+                "{:?} weights={:?} k={} weight={}", encoding, weights, k, weight);
+        }
+    }
+
+    const WEIGHTS: [&[u64]; 6] = [
+        &[],
+        &[3],
+        &[1, 1, 1, 1],
+        &[2, 3, 5, 7, 11],
+        &[4, 4, 6, 0, 2, 8],
+        &[1, 2, 4, 8, 16, 3]
+    ];
+
+    #[test]
+    fn pb_at_most_admits_the_assignments_weighing_at_most_k() {
+        for &encoding in PB_ENCODINGS.iter() {
+            for weights in WEIGHTS.iter() {
+                let total: u64 = weights.iter().sum();
+                for k in 0..total+2 {
+                    check_pb(weights, k, encoding, pb_at_most, |weight| weight <= k);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pb_at_least_admits_the_assignments_weighing_at_least_k() {
+        for &encoding in PB_ENCODINGS.iter() {
+            for weights in WEIGHTS.iter() {
+                let total: u64 = weights.iter().sum();
+                for k in 0..total+2 {
+                    check_pb(weights, k, encoding, pb_at_least, |weight| weight >= k);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pb_exactly_admits_the_assignments_weighing_exactly_k() {
+        for &encoding in PB_ENCODINGS.iter() {
+            for weights in WEIGHTS.iter() {
+                let total: u64 = weights.iter().sum();
+                for k in 0..total+2 {
+                    check_pb(weights, k, encoding, pb_exactly, |weight| weight == k);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn xor_constraint_admits_the_assignments_having_the_right_parity() {
+        for n in 0..9 {
+            for &parity in [false, true].iter() {
+                let mut solver = Solver::new(n);
+                let literals: Vec<Literal> = (1..=n).map(|v| lit(v as iint)).collect();
+                xor_constraint(&mut solver, &literals, parity);
+
+                for bits in 0..(1_u32 << n) {
+                    let assumptions: Vec<Literal> = literals.iter().enumerate()
+                        .map(|(i, &l)| if bits & (1 << i) != 0 { l } else { !l })
+                        .collect();
+                    let odd = bits.count_ones() % 2 == 1;
+
+                    assert_eq!(solver.solve_with_assumptions(&assumptions), odd == parity,
+                        "n={} parity={} bits={:b}", n, parity, bits);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn xor_literal_tells_the_parity_of_the_literals() {
+        // the xor of a literal with itself and with its negation
+        let mut solver = Solver::new(2);
+        let same = xor_literal(&mut solver, &[lit(1), lit(1), lit(2)]);
+        let negated = xor_literal(&mut solver, &[lit(1), lit(-1)]);
+
+        assert!(solver.solve_with_assumptions(&[same, lit(2)]));
+        assert!(!solver.solve_with_assumptions(&[same, lit(-2)]));
+        assert!(solver.solve_with_assumptions(&[negated]));
+        assert!(!solver.solve_with_assumptions(&[!negated]));
+    }
+
+    #[test]
+    fn guarded_xor_literal_is_only_defined_when_the_guard_holds() {
+        let mut solver = Solver::new(4);
+        let guard = lit(4);
+        let xor = guarded_xor_literal(&mut solver, guard, &[lit(1), lit(2), lit(3)]);
+
+        assert!( solver.solve_with_assumptions(&[guard, lit(1), lit(-2), lit(-3), xor]));
+        assert!(!solver.solve_with_assumptions(&[guard, lit(1), lit(-2), lit(-3), !xor]));
+        assert!( solver.solve_with_assumptions(&[!guard, lit(1), lit(-2), lit(-3), !xor]));
+
+        // the guard is gone for good: the auxiliary variables can be fixed
+        let mut unit = vec![-4];
+        let _ = solver.add_problem_clause(&mut unit);
+        for v in 5..=solver.nb_vars() {
+            let _ = solver.add_problem_clause(&mut vec![-(v as iint)]);
+        }
+        assert!(solver.solve_with_assumptions(&[lit(1), lit(2), lit(3)]));
+        assert!(solver.solve_with_assumptions(&[lit(-1), lit(-2), lit(-3)]));
+    }
+
+    #[test]
+    fn pb_from_name_knows_all_encodings() {
+        assert_eq!(PbEncoding::from_name("bdd"),      Some(PbEncoding::Bdd));
+        assert_eq!(PbEncoding::from_name("adder"),    Some(PbEncoding::Adder));
+        assert_eq!(PbEncoding::from_name("sorter"),   Some(PbEncoding::Sorter));
+        assert_eq!(PbEncoding::from_name("whatever"), None);
+    }
+
+    #[test]
+    fn from_name_knows_all_encodings() {
+        assert_eq!(CardinalityEncoding::from_name("pairwise"),    Some(CardinalityEncoding::Pairwise));
+        assert_eq!(CardinalityEncoding::from_name("seqcounter"),  Some(CardinalityEncoding::SequentialCounter));
+        assert_eq!(CardinalityEncoding::from_name("totalizer"),   Some(CardinalityEncoding::Totalizer));
+        assert_eq!(CardinalityEncoding::from_name("mtotalizer"),  Some(CardinalityEncoding::ModuloTotalizer));
+        assert_eq!(CardinalityEncoding::from_name("cardnetwork"), Some(CardinalityEncoding::CardinalityNetwork));
+        assert_eq!(CardinalityEncoding::from_name("whatever"),    None);
+    }
+}
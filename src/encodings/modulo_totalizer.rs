@@ -0,0 +1,108 @@
+use std::cmp::{max, min};
+
+use core::*;
+use solving::*;
+use encodings::fresh_literal;
+
+/// The count of true literals as represented by a node of the modulo totalizer. The count is
+/// `p * upper + lower` where both the `upper` and `lower` parts are represented in unary
+/// (`upper[i]` means the upper part is at least i+1).
+struct Count {
+    /// The unary representation of the count divided by the modulus
+    upper: Vec<Literal>,
+    /// The unary representation of the count modulo the modulus
+    lower: Vec<Literal>
+}
+
+/// Encodes `at most k of literals` with a modulo totalizer. It is a totalizer whose every node
+/// counts modulo p = ceil(sqrt(k+1)) and remembers the number of times it wrapped around p. This
+/// way, the nodes need O(sqrt(k)) outputs instead of O(k), and O(n.sqrt(k)) clauses overall.
+///
+/// See `Modulo Based CNF Encoding of Cardinality Constraints and Its Application to MaxSAT
+/// Solvers` -- Ogawa, Liu, Hasegawa, Koshimura, Fujita (ICTAI 2013).
+pub fn at_most(solver: &mut Solver, literals: &[Literal], k: usize) {
+    let modulus = max(2, ((k + 1) as f64).sqrt().ceil() as usize);
+    let count   = build(solver, literals, modulus);
+
+    // k = modulus * upper_k + lower_k
+    let upper_k = k / modulus;
+    let lower_k = k % modulus;
+
+    // the upper part cannot exceed upper_k
+    if upper_k < count.upper.len() {
+        let _ = solver.add_clause_literals(&[!count.upper[upper_k]]);
+    }
+    // and when it reaches upper_k, the lower part cannot exceed lower_k
+    if lower_k < count.lower.len() {
+        let mut clause = vec![!count.lower[lower_k]];
+        if upper_k > 0 { clause.push(!count.upper[upper_k - 1]); }
+        let _ = solver.add_clause_literals(&clause);
+    }
+}
+
+/// Builds the modulo totalizer tree counting the true `literals` and returns the count at its
+/// root. The outputs are only propagated upwards (that is all the constraint needs).
+fn build(solver: &mut Solver, literals: &[Literal], modulus: usize) -> Count {
+    if literals.len() == 1 {
+        return Count { upper: vec![], lower: literals.to_vec() };
+    }
+
+    let (left, right) = literals.split_at(literals.len() / 2);
+    let a = build(solver, left,  modulus);
+    let b = build(solver, right, modulus);
+
+    let n     = literals.len();
+    let lower: Vec<Literal> = (0..min(modulus - 1, n)).map(|_| fresh_literal(solver)).collect();
+    let upper: Vec<Literal> = (0..n / modulus).map(|_| fresh_literal(solver)).collect();
+    // the carry is only needed when the sum of the lower parts may reach the modulus
+    let carry = if a.lower.len() + b.lower.len() >= modulus { Some(fresh_literal(solver)) } else { None };
+
+    // (in unary, x[i-1] means 'at least i' and x[0-1] is always true)
+    for i in 0..=a.lower.len() {
+        for j in 0..=b.lower.len() {
+            let sum = i + j;
+            if sum == 0 { continue; }
+
+            let mut premise = vec![];
+            if i > 0 { premise.push(!a.lower[i-1]); }
+            if j > 0 { premise.push(!b.lower[j-1]); }
+
+            if sum < modulus {
+                let mut clause = premise;
+                clause.push(lower[sum - 1]);
+                if let Some(c) = carry { clause.push(c); }
+                let _ = solver.add_clause_literals(&clause);
+            } else {
+                let mut clause = premise.clone();
+                clause.push(carry.unwrap());
+                let _ = solver.add_clause_literals(&clause);
+
+                if sum > modulus {
+                    let mut clause = premise;
+                    clause.push(lower[sum - modulus - 1]);
+                    let _ = solver.add_clause_literals(&clause);
+                }
+            }
+        }
+    }
+
+    let carries = if carry.is_some() { 2 } else { 1 };
+    for i in 0..=a.upper.len() {
+        for j in 0..=b.upper.len() {
+            for e in 0..carries {
+                let sum = i + j + e;
+                if sum == 0 { continue; }
+
+                let mut clause = vec![];
+                if i > 0  { clause.push(!a.upper[i-1]); }
+                if j > 0  { clause.push(!b.upper[j-1]); }
+                if e > 0  { clause.push(!carry.unwrap()); }
+                // (the carry cannot actually make the upper part overflow)
+                if sum <= upper.len() { clause.push(upper[sum - 1]); }
+                let _ = solver.add_clause_literals(&clause);
+            }
+        }
+    }
+
+    Count { upper, lower }
+}
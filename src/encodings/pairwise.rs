@@ -0,0 +1,24 @@
+use core::*;
+use solving::*;
+
+/// Encodes `at most k of literals` with one clause per subset of k+1 literals (forbidding all of
+/// them from being true at once). This encoding introduces no auxiliary variable but it yields
+/// C(n, k+1) clauses: it is only sensible for very small values of k (ie: k=1).
+pub fn at_most(solver: &mut Solver, literals: &[Literal], k: usize) {
+    let mut subset = Vec::with_capacity(k+1);
+    forbid_subsets(solver, literals, k+1, &mut subset);
+}
+
+/// Adds one clause per way to extend `subset` with `size` more literals from `literals`
+fn forbid_subsets(solver: &mut Solver, literals: &[Literal], size: usize, subset: &mut Vec<Literal>) {
+    if size == 0 {
+        let _ = solver.add_clause_literals(subset);
+        return;
+    }
+
+    for i in 0..(literals.len() + 1).saturating_sub(size) {
+        subset.push(!literals[i]);
+        forbid_subsets(solver, &literals[i+1..], size-1, subset);
+        subset.pop();
+    }
+}
@@ -0,0 +1,35 @@
+use core::*;
+use solving::*;
+use encodings::fresh_literal;
+
+/// Encodes `at most k of literals` with a sequential counter. The auxiliary variable `s[i][j]`
+/// means that at least j+1 of the first i+1 literals are true. (The counter only needs to be
+/// propagated upwards for the constraint to hold).
+///
+/// See `Towards an Optimal CNF Encoding of Boolean Cardinality Constraints` -- Sinz (CP 2005).
+pub fn at_most(solver: &mut Solver, literals: &[Literal], k: usize) {
+    let n = literals.len();
+    debug_assert!(0 < k && k < n);
+
+    // the registers of the counter: one per literal but the last one
+    let s: Vec<Vec<Literal>> = (0..n-1)
+        .map(|_| (0..k).map(|_| fresh_literal(solver)).collect())
+        .collect();
+
+    let _ = solver.add_clause_literals(&[!literals[0], s[0][0]]);
+    for &register in s[0].iter().skip(1) {
+        let _ = solver.add_clause_literals(&[!register]);
+    }
+
+    for i in 1..n-1 {
+        let _ = solver.add_clause_literals(&[!literals[i], s[i][0]]);
+        let _ = solver.add_clause_literals(&[!s[i-1][0], s[i][0]]);
+        for j in 1..k {
+            let _ = solver.add_clause_literals(&[!literals[i], !s[i-1][j-1], s[i][j]]);
+            let _ = solver.add_clause_literals(&[!s[i-1][j], s[i][j]]);
+        }
+        let _ = solver.add_clause_literals(&[!literals[i], !s[i-1][k-1]]);
+    }
+
+    let _ = solver.add_clause_literals(&[!literals[n-1], !s[n-2][k-1]]);
+}
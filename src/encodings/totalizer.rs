@@ -0,0 +1,196 @@
+use std::cmp::min;
+
+use core::*;
+use solving::*;
+use encodings::fresh_literal;
+
+// -----------------------------------------------------------------------------------------------
+/// # Totalizer
+/// A totalizer counts the number of true literals among its inputs in unary: its i-th output
+/// holds iff (at least) i+1 of its inputs are true. It is built as a binary tree whose every node
+/// sums the (unary) counts of its two children.
+///
+/// The outputs are fully constrained (both ways), which is what makes the totalizer incremental:
+/// once built, the bound on the number of true inputs can be tightened (or relaxed) at will by
+/// assuming one of its outputs.
+///
+/// See `Efficient CNF Encoding of Boolean Cardinality Constraints` -- Bailleux, Boufkhad (CP 2003).
+// -----------------------------------------------------------------------------------------------
+#[derive(Debug)]
+pub struct Totalizer {
+    /// The unary count of true inputs: `outputs[i]` holds iff at least i+1 inputs are true
+    outputs: Vec<Literal>
+}
+
+impl Totalizer {
+    /// Creates a totalizer counting the true literals among the given `literals`. Its clauses and
+    /// auxiliary variables are added to the solver.
+    pub fn new(solver: &mut Solver, literals: &[Literal]) -> Totalizer {
+        let outputs = if literals.is_empty() {
+            vec![]
+        } else {
+            build(solver, literals, literals.len(), true)
+        };
+
+        Totalizer { outputs }
+    }
+
+    /// Returns the number of literals counted by the totalizer
+    #[inline]
+    pub fn nb_inputs(&self) -> usize { self.outputs.len() }
+
+    /// Returns the unary count of true inputs: the i-th output holds iff at least i+1 inputs
+    /// are true.
+    #[inline]
+    pub fn outputs(&self) -> &[Literal] { &self.outputs }
+
+    /// Returns the literal to assume (or to add as a unit clause) for at most `k` inputs to be
+    /// true, or None when this is trivially the case.
+    #[inline]
+    pub fn at_most(&self, k: usize) -> Option<Literal> {
+        self.outputs.get(k).map(|&o| !o)
+    }
+
+    /// Returns the literal to assume (or to add as a unit clause) for at least `k` inputs to be
+    /// true, or None when this is trivially the case.
+    ///
+    /// # Panics
+    /// - when `k` exceeds the number of inputs
+    #[inline]
+    pub fn at_least(&self, k: usize) -> Option<Literal> {
+        if k == 0 { None } else { Some(self.outputs[k-1]) }
+    }
+}
+
+/// Encodes `at most k of literals` with a totalizer whose outputs are truncated to k+1 and only
+/// propagated upwards (that is all the constraint needs).
+pub fn at_most(solver: &mut Solver, literals: &[Literal], k: usize) {
+    let outputs = build(solver, literals, k+1, false);
+    let _ = solver.add_clause_literals(&[!outputs[k]]);
+}
+
+/// Builds the totalizer tree counting the true `literals` and returns its outputs. The count
+/// saturates at `limit` (there are at most `limit` outputs) and the outputs are only constrained
+/// downwards when `downwards` is set.
+fn build(solver: &mut Solver, literals: &[Literal], limit: usize, downwards: bool) -> Vec<Literal> {
+    if literals.len() == 1 { return literals.to_vec(); }
+
+    let (left, right) = literals.split_at(literals.len() / 2);
+    let a = build(solver, left,  limit, downwards);
+    let b = build(solver, right, limit, downwards);
+
+    let size = min(a.len() + b.len(), limit);
+    let r: Vec<Literal> = (0..size).map(|_| fresh_literal(solver)).collect();
+
+    // (in unary, a[i-1] means 'at least i', a[0-1] is always true and a[p+1-1] always false)
+    for i in 0..=a.len() {
+        for j in 0..=b.len() {
+            // upwards: at least i in a and j in b means at least i+j in r
+            if i + j > 0 {
+                let mut clause = vec![r[min(i + j, size) - 1]];
+                if i > 0 { clause.push(!a[i-1]); }
+                if j > 0 { clause.push(!b[j-1]); }
+                let _ = solver.add_clause_literals(&clause);
+            }
+            // downwards: at most i in a and j in b means at most i+j in r
+            if downwards && i + j < size {
+                let mut clause = vec![!r[i + j]];
+                if i < a.len() { clause.push(a[i]); }
+                if j < b.len() { clause.push(b[j]); }
+                let _ = solver.add_clause_literals(&clause);
+            }
+        }
+    }
+
+    r
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Unit Tests
+// -----------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literals(n: usize) -> Vec<Literal> {
+        (1..=n).map(|v| lit(v as iint)).collect()
+    }
+
+    #[test]
+    fn the_outputs_count_the_true_inputs() {
+        let mut solver = Solver::new(5);
+        let tested = Totalizer::new(&mut solver, &literals(5));
+        assert_eq!(tested.nb_inputs(), 5);
+
+        assert!(solver.solve_with_assumptions(&[lit(1), lit(-2), lit(3), lit(-4), lit(-5)]));
+        let values: Vec<Bool> = tested.outputs().iter().map(|&o| solver.get_value(o)).collect();
+        assert_eq!(values, vec![Bool::True, Bool::True, Bool::False, Bool::False, Bool::False]);
+    }
+
+    #[test]
+    fn the_bounds_hold_exactly_for_every_assignment() {
+        let n = 6;
+        let mut solver = Solver::new(n);
+        let inputs = literals(n);
+        let tested = Totalizer::new(&mut solver, &inputs);
+
+        for bits in 0..(1 << n) {
+            let mut assumptions: Vec<Literal> = inputs.iter().enumerate()
+                .map(|(i, &l)| if bits & (1 << i) != 0 { l } else { !l })
+                .collect();
+            let count = (bits as u32).count_ones() as usize;
+
+            for k in 1..=n {
+                assumptions.push(tested.at_most(k-1).unwrap());
+                assert_eq!(solver.solve_with_assumptions(&assumptions), count < k);
+                assumptions.pop();
+
+                assumptions.push(tested.at_least(k).unwrap());
+                assert_eq!(solver.solve_with_assumptions(&assumptions), count >= k);
+                assumptions.pop();
+            }
+        }
+    }
+
+    #[test]
+    fn the_bound_can_be_tightened_with_assumptions() {
+        let mut solver = Solver::new(6);
+        solver.add_problem_clause(&mut vec![1, 2]).unwrap();
+        solver.add_problem_clause(&mut vec![3, 4]).unwrap();
+        solver.add_problem_clause(&mut vec![5, 6]).unwrap();
+        let tested = Totalizer::new(&mut solver, &literals(6));
+
+        assert!(tested.at_most(6).is_none());
+        assert!( solver.solve_with_assumptions(&[tested.at_most(4).unwrap()]));
+        assert!(!solver.solve_with_assumptions(&[tested.at_most(2).unwrap()]));
+        assert!( solver.solve_with_assumptions(&[tested.at_most(3).unwrap()]));
+        // the failed assumption tells what bound is too tight
+        assert!(!solver.solve_with_assumptions(&[tested.at_most(2).unwrap()]));
+        assert_eq!(solver.failed_assumptions(), &[tested.at_most(2).unwrap()]);
+    }
+
+    #[test]
+    fn at_least_and_at_most_can_be_combined() {
+        let mut solver = Solver::new(4);
+        let tested = Totalizer::new(&mut solver, &literals(4));
+
+        let exactly_two = [tested.at_least(2).unwrap(), tested.at_most(2).unwrap()];
+        assert!(solver.solve_with_assumptions(&exactly_two));
+        let count = (1..=4).filter(|&v| solver.get_value(lit(v)) == Bool::True).count();
+        assert_eq!(count, 2);
+
+        assert!(tested.at_least(0).is_none());
+        assert!(tested.at_most(4).is_none());
+        assert!(!solver.solve_with_assumptions(&[tested.at_least(3).unwrap(), tested.at_most(1).unwrap()]));
+    }
+
+    #[test]
+    fn an_empty_totalizer_has_no_output() {
+        let mut solver = Solver::new(0);
+        let tested = Totalizer::new(&mut solver, &[]);
+
+        assert_eq!(tested.nb_inputs(), 0);
+        assert!(tested.at_most(0).is_none());
+        assert!(tested.at_least(0).is_none());
+    }
+}
@@ -0,0 +1,60 @@
+use core::*;
+use solving::*;
+use encodings::fresh_literal;
+
+/// The maximum number of inputs of each link of the chain
+const CUT: usize = 3;
+
+/// Returns a literal which is true iff an odd number of the given `literals` are true. The XOR of
+/// many literals is cut in a chain of small XOR gates (having at most `CUT` inputs, one of which is
+/// the output of the previous gate) whose outputs are auxiliary variables. Each gate is encoded by
+/// 2^CUT clauses: the encoding grows linearly with the number of literals whereas the direct one
+/// needs 2^(n-1) clauses.
+///
+/// When a `guard` is given, each clause is extended with its negation: the definition only holds
+/// when the guard is true (and all the clauses are satisfied once it is false).
+pub fn define(solver: &mut Solver, literals: &[Literal], guard: Option<Literal>) -> Literal {
+    if literals.is_empty() {
+        // the parity of nothing is even
+        let never = fresh_literal(solver);
+        add_guarded_clause(solver, vec![!never], guard);
+        return never;
+    }
+
+    let mut output  = literals[0];
+    let mut pending = &literals[1..];
+    while !pending.is_empty() {
+        let take = (CUT - 1).min(pending.len());
+        let mut inputs = vec![output];
+        inputs.extend_from_slice(&pending[..take]);
+        pending = &pending[take..];
+
+        output = gate(solver, &inputs, guard);
+    }
+    output
+}
+
+/// Returns the output of an XOR gate having the given inputs
+fn gate(solver: &mut Solver, inputs: &[Literal], guard: Option<Literal>) -> Literal {
+    let output = fresh_literal(solver);
+
+    // one clause forbids each assignment of the inputs along with the wrong output
+    for signs in 0_u32..1 << inputs.len() {
+        let mut clause: Vec<Literal> = inputs.iter().enumerate()
+            .map(|(i, &l)| if signs & (1 << i) != 0 { !l } else { l })
+            .collect();
+        // (this clause is falsified when the inputs whose bit is set are true, the others false)
+        let odd = signs.count_ones() % 2 == 1;
+        clause.push(if odd { output } else { !output });
+        add_guarded_clause(solver, clause, guard);
+    }
+    output
+}
+
+/// Adds the given clause to the solver, extended with the negation of the `guard` (if any)
+fn add_guarded_clause(solver: &mut Solver, mut clause: Vec<Literal>, guard: Option<Literal>) {
+    if let Some(guard) = guard {
+        clause.push(!guard);
+    }
+    let _ = solver.add_clause_literals(&clause);
+}
@@ -0,0 +1,45 @@
+// The problems shared by the unit tests of several modules
+
+use core::*;
+use solving::*;
+use mus::Mus;
+
+/// Returns a solver for the problem made of the given clauses (in DIMACS notation) over
+/// `nb_vars` variables.
+pub fn problem(nb_vars: usize, clauses: &[&[iint]]) -> Solver {
+    let mut solver = Solver::new(nb_vars);
+    for clause in clauses.iter() {
+        let _ = solver.add_problem_clause(&mut clause.to_vec());
+    }
+    solver
+}
+
+/// Same as `problem`, except that the clauses are those a MUS is extracted from (the i-th
+/// clause forms the i-th group).
+pub fn mus_problem(nb_vars: usize, clauses: &[&[iint]]) -> Mus {
+    let mut mus = Mus::new(nb_vars);
+    for clause in clauses.iter() {
+        mus.add_clause(clause);
+    }
+    mus
+}
+
+/// Returns a solver for the (unsatisfiable when there are more pigeons than holes) pigeon hole
+/// problem: every pigeon sits in some hole, and no two pigeons share a hole.
+pub fn pigeon_hole(pigeons: usize, holes: usize) -> Solver {
+    let var = |p: usize, h: usize| (1 + p * holes + h) as iint;
+    let mut solver = Solver::new(pigeons * holes);
+
+    for p in 0..pigeons {
+        let mut clause = (0..holes).map(|h| var(p, h)).collect();
+        solver.add_problem_clause(&mut clause).unwrap();
+    }
+    for h in 0..holes {
+        for p1 in 0..pigeons {
+            for p2 in (p1+1)..pigeons {
+                solver.add_problem_clause(&mut vec![-var(p1, h), -var(p2, h)]).unwrap();
+            }
+        }
+    }
+    solver
+}
@@ -0,0 +1,833 @@
+use std::collections::HashMap;
+
+use core::*;
+use solving::*;
+
+// -----------------------------------------------------------------------------------------------
+/// # Formula
+/// A propositional formula over named variables. Formulas are either built by hand or parsed from
+/// a simple infix syntax (see `parse`) and translated into clauses by a `Circuit`.
+// -----------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Formula {
+    /// The constant `true` or `false`
+    Const(bool),
+    /// The variable having the given name
+    Var(String),
+    /// `!f`
+    Not(Box<Formula>),
+    /// `f_1 & f_2 & ... & f_n` (true when empty)
+    And(Vec<Formula>),
+    /// `f_1 | f_2 | ... | f_n` (false when empty)
+    Or(Vec<Formula>),
+    /// `f -> g`
+    Implies(Box<Formula>, Box<Formula>),
+    /// `f <-> g`
+    Iff(Box<Formula>, Box<Formula>),
+    /// `f ^ g`
+    Xor(Box<Formula>, Box<Formula>),
+    /// `ite(c, t, e)`: t when c holds and e otherwise
+    Ite(Box<Formula>, Box<Formula>, Box<Formula>)
+}
+
+impl Formula {
+    /// Returns the variable having the given `name`
+    pub fn var(name: &str) -> Formula {
+        Formula::Var(name.to_string())
+    }
+
+    /// Parses the given `text` as a formula written with the usual infix operators. From the
+    /// loosest to the tightest binding, these are:
+    /// - `<->` (or `<=>`): equivalence
+    /// - `->`  (or `=>`) : implication (right associative)
+    /// - `|`   (or `||`) : disjunction
+    /// - `^`             : exclusive or
+    /// - `&`   (or `&&`) : conjunction
+    /// - `!`   (or `~`)  : negation
+    ///
+    /// Besides the parenthesized subformulas, the operands are the constants `true` and `false`,
+    /// the if-then-else `ite(c, t, e)` and the variables, whose names are made of letters,
+    /// digits, `_`, `.` and `'`. For instance: `ite(a, b & !c, d) -> (b <-> c)`.
+    ///
+    /// # Return Value
+    /// The parsed formula or a message telling what is wrong with the text (and where).
+    pub fn parse(text: &str) -> Result<Formula, String> {
+        let mut parser = Parser { text, tokens: tokenize(text)?, next: 0 };
+        let formula = parser.iff()?;
+        if parser.next < parser.tokens.len() {
+            return Err(parser.unexpected());
+        }
+        Ok(formula)
+    }
+}
+
+/// The kinds of tokens of the infix syntax
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token { Name, Not, And, Or, Xor, Implies, Iff, Open, Close, Comma }
+
+/// The operators and punctuation of the infix syntax (the longest ones first)
+const SYMBOLS: [(&str, Token); 14] = [
+    ("<->", Token::Iff), ("<=>", Token::Iff), ("->", Token::Implies), ("=>", Token::Implies),
+    ("&&", Token::And), ("||", Token::Or), ("&", Token::And), ("|", Token::Or), ("^", Token::Xor),
+    ("!", Token::Not), ("~", Token::Not), ("(", Token::Open), (")", Token::Close), (",", Token::Comma)
+];
+
+/// Returns true iff `c` may occur in the name of a variable
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '\''
+}
+
+/// Splits the given `text` into tokens. Each token is given along with its (byte) offset and its
+/// length in the text.
+fn tokenize(text: &str) -> Result<Vec<(usize, usize, Token)>, String> {
+    let mut tokens = vec![];
+    let mut offset = 0;
+    while let Some(c) = text[offset..].chars().next() {
+        let rest = &text[offset..];
+        if c.is_whitespace() {
+            offset += c.len_utf8();
+        } else if is_name_char(c) {
+            let length = rest.find(|c| !is_name_char(c)).unwrap_or(rest.len());
+            tokens.push((offset, length, Token::Name));
+            offset += length;
+        } else {
+            match SYMBOLS.iter().find(|s| rest.starts_with(s.0)) {
+                Some(&(symbol, token)) => {
+                    tokens.push((offset, symbol.len(), token));
+                    offset += symbol.len();
+                },
+                None => return Err(format!("unexpected `{}` at offset {}", c, offset))
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// A recursive descent parser for the infix syntax (one method per precedence level)
+struct Parser<'a> {
+    /// The parsed text
+    text  : &'a str,
+    /// The tokens of the text
+    tokens: Vec<(usize, usize, Token)>,
+    /// The position of the next token to consume
+    next  : usize
+}
+
+impl<'a> Parser<'a> {
+    /// Consumes the next token iff it is of the given kind
+    fn accept(&mut self, token: Token) -> bool {
+        let found = self.tokens.get(self.next).is_some_and(|t| t.2 == token);
+        if found { self.next += 1; }
+        found
+    }
+
+    /// Consumes the next token, which must be of the given kind
+    fn expect(&mut self, token: Token) -> Result<(), String> {
+        if self.accept(token) { Ok(()) } else { Err(self.unexpected()) }
+    }
+
+    /// Returns the message telling that the next token was not expected
+    fn unexpected(&self) -> String {
+        match self.tokens.get(self.next) {
+            Some(&(offset, length, _)) =>
+                format!("unexpected `{}` at offset {}", &self.text[offset..offset+length], offset),
+            None =>
+                String::from("unexpected end of formula")
+        }
+    }
+
+    /// `iff := implies ('<->' implies)*`
+    fn iff(&mut self) -> Result<Formula, String> {
+        let mut formula = self.implies()?;
+        while self.accept(Token::Iff) {
+            formula = Formula::Iff(Box::new(formula), Box::new(self.implies()?));
+        }
+        Ok(formula)
+    }
+
+    /// `implies := or ('->' implies)?`
+    fn implies(&mut self) -> Result<Formula, String> {
+        let formula = self.or()?;
+        if self.accept(Token::Implies) {
+            Ok(Formula::Implies(Box::new(formula), Box::new(self.implies()?)))
+        } else {
+            Ok(formula)
+        }
+    }
+
+    /// `or := xor ('|' xor)*`
+    fn or(&mut self) -> Result<Formula, String> {
+        let mut operands = vec![self.xor()?];
+        while self.accept(Token::Or) {
+            operands.push(self.xor()?);
+        }
+        Ok(if operands.len() == 1 { operands.pop().unwrap() } else { Formula::Or(operands) })
+    }
+
+    /// `xor := and ('^' and)*`
+    fn xor(&mut self) -> Result<Formula, String> {
+        let mut formula = self.and()?;
+        while self.accept(Token::Xor) {
+            formula = Formula::Xor(Box::new(formula), Box::new(self.and()?));
+        }
+        Ok(formula)
+    }
+
+    /// `and := not ('&' not)*`
+    fn and(&mut self) -> Result<Formula, String> {
+        let mut operands = vec![self.not()?];
+        while self.accept(Token::And) {
+            operands.push(self.not()?);
+        }
+        Ok(if operands.len() == 1 { operands.pop().unwrap() } else { Formula::And(operands) })
+    }
+
+    /// `not := '!' not | atom`
+    fn not(&mut self) -> Result<Formula, String> {
+        if self.accept(Token::Not) {
+            Ok(Formula::Not(Box::new(self.not()?)))
+        } else {
+            self.atom()
+        }
+    }
+
+    /// `atom := '(' iff ')' | 'true' | 'false' | 'ite' '(' iff ',' iff ',' iff ')' | name`
+    fn atom(&mut self) -> Result<Formula, String> {
+        if self.accept(Token::Open) {
+            let formula = self.iff()?;
+            self.expect(Token::Close)?;
+            return Ok(formula);
+        }
+        if !self.accept(Token::Name) {
+            return Err(self.unexpected());
+        }
+
+        let (offset, length, _) = self.tokens[self.next - 1];
+        match &self.text[offset..offset+length] {
+            "true"  => Ok(Formula::Const(true)),
+            "false" => Ok(Formula::Const(false)),
+            "ite" if self.accept(Token::Open) => {
+                let condition = self.iff()?;
+                self.expect(Token::Comma)?;
+                let then = self.iff()?;
+                self.expect(Token::Comma)?;
+                let otherwise = self.iff()?;
+                self.expect(Token::Close)?;
+                Ok(Formula::Ite(Box::new(condition), Box::new(then), Box::new(otherwise)))
+            },
+            name => Ok(Formula::var(name))
+        }
+    }
+}
+
+/// The transformations turning the formulas into clauses. Both introduce one auxiliary variable
+/// per (distinct) gate of the circuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Transformation {
+    /// Tseitin's: every auxiliary variable is equivalent to its gate
+    Tseitin,
+    /// Plaisted and Greenbaum's: an auxiliary variable only implies its gate (or is implied by
+    /// it) when it occurs positively (or negatively) in the formulas. This is about half the
+    /// clauses of Tseitin's.
+    #[default]
+    PlaistedGreenbaum
+}
+
+impl Transformation {
+    /// Returns the transformation having the given `name` (tseitin or pg) or None when no
+    /// transformation bears that name.
+    pub fn from_name(name: &str) -> Option<Transformation> {
+        match name {
+            "tseitin" => Some(Transformation::Tseitin),
+            "pg"      => Some(Transformation::PlaistedGreenbaum),
+            _         => None
+        }
+    }
+}
+
+/// The value of a (sub)formula in the circuit: either a constant or a literal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Node {
+    Const(bool),
+    Lit(Literal)
+}
+
+impl Node {
+    /// Returns the negation of this node
+    fn negate(self) -> Node {
+        match self {
+            Node::Const(value) => Node::Const(!value),
+            Node::Lit(l)       => Node::Lit(!l)
+        }
+    }
+}
+
+/// A gate of the circuit. The gates are normalized (their inputs are sorted and their polarity
+/// is pushed to their output) so that equal gates are detected by hashing them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Gate {
+    /// The conjunction of two (or more) literals over distinct variables
+    And(Vec<Literal>),
+    /// The exclusive or of two positive literals
+    Xor(Literal, Literal),
+    /// The if-then-else of three literals, the first two of which are positive
+    Ite(Literal, Literal, Literal)
+}
+
+/// The definition of an auxiliary variable
+#[derive(Debug, Clone)]
+struct Definition {
+    /// The gate the variable stands for
+    gate    : Gate,
+    /// Whether the clauses `v -> gate` were added to the solver
+    positive: bool,
+    /// Whether the clauses `!v -> !gate` were added to the solver
+    negative: bool
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Circuit
+/// Translates propositional formulas into the clauses of a `Solver`.
+///
+/// The formulas are first turned into a circuit of and, xor and if-then-else gates over the
+/// literals of their variables. The constants are propagated away and the structurally equal
+/// gates are shared (structural hashing): a subformula occurring several times is only encoded
+/// once. Every gate is then given an auxiliary variable whose definition is translated into
+/// clauses with the chosen `Transformation`.
+///
+/// Since the named variables are mapped onto variables of the solver, the models found by the
+/// solver are mapped back onto the names (see `model` and `value`).
+// -----------------------------------------------------------------------------------------------
+#[derive(Debug)]
+pub struct Circuit {
+    /// The solver deciding the translated formulas
+    pub solver        : Solver,
+    /// The transformation used to translate the formulas into clauses
+    pub transformation: Transformation,
+    /// The named variables (in the order in which they were declared) and their literals
+    names             : Vec<(String, Literal)>,
+    /// The position of every named variable in `names`
+    index             : HashMap<String, usize>,
+    /// The positive output literal of every gate
+    gates             : HashMap<Gate, Literal>,
+    /// The definition of every auxiliary variable
+    definitions       : HashMap<usize, Definition>,
+    /// A literal which is always true (only allocated when a constant needs a literal)
+    truth             : Option<Literal>,
+    /// The value of the named variables in the last assignment found
+    model             : Vec<bool>
+}
+
+impl Default for Circuit {
+    fn default() -> Circuit {
+        Circuit::new()
+    }
+}
+
+impl Circuit {
+    /// Creates an empty circuit (without any variable nor formula)
+    pub fn new() -> Circuit {
+        Circuit {
+            solver        : Solver::new(0),
+            transformation: Transformation::default(),
+            names         : vec![],
+            index         : HashMap::new(),
+            gates         : HashMap::new(),
+            definitions   : HashMap::new(),
+            truth         : None,
+            model         : vec![]
+        }
+    }
+
+    /// Returns the number of named variables
+    #[inline]
+    pub fn nb_vars(&self) -> usize { self.names.len() }
+
+    /// Returns the number of (distinct) gates of the circuit
+    #[inline]
+    pub fn nb_gates(&self) -> usize { self.gates.len() }
+
+    /// Returns the literal of the variable having the given `name` (the variable is declared if
+    /// need be).
+    pub fn variable(&mut self, name: &str) -> Literal {
+        if let Some(&i) = self.index.get(name) {
+            return self.names[i].1;
+        }
+
+        let literal = Literal::positive(self.solver.new_var());
+        self.index.insert(name.to_string(), self.names.len());
+        self.names.push((name.to_string(), literal));
+        literal
+    }
+
+    /// Adds the given `formula` to the problem: it must hold in every solution.
+    pub fn add_formula(&mut self, formula: &Formula) {
+        match self.build(formula) {
+            Node::Const(true)  => {},
+            Node::Const(false) => { let _ = self.solver.add_clause_literals(&[]); },
+            Node::Lit(l)       => self.assert(l)
+        }
+    }
+
+    /// Returns a literal equivalent to the given `formula`. This literal can be assumed (or
+    /// its negation) to solve the problem under the condition that the formula holds (or not).
+    pub fn literal(&mut self, formula: &Formula) -> Literal {
+        let literal = match self.build(formula) {
+            Node::Lit(l)       => l,
+            Node::Const(value) => {
+                let truth = self.truth();
+                if value { truth } else { !truth }
+            }
+        };
+        self.define(literal);
+        self.define(!literal);
+        literal
+    }
+
+    /// Finds an assignment satisfying all the formulas.
+    ///
+    /// # Return Value
+    /// True iff the formulas are satisfiable, in which case the assignment is available through
+    /// `model` and `value`.
+    pub fn solve(&mut self) -> bool {
+        self.solve_with_assumptions(&[])
+    }
+
+    /// Finds an assignment satisfying all the formulas in which all the given `assumptions`
+    /// hold (see `literal`).
+    pub fn solve_with_assumptions(&mut self, assumptions: &[Literal]) -> bool {
+        let satisfiable = self.solver.solve_with_assumptions(assumptions);
+        if satisfiable {
+            self.model = self.names.iter()
+                .map(|&(_, l)| self.solver.get_value(l) == Bool::True)
+                .collect();
+        }
+        satisfiable
+    }
+
+    /// Returns the value of every named variable (in the order in which they were declared) in
+    /// the last assignment found. This is only meaningful after a solution was found.
+    pub fn model(&self) -> Vec<(&str, bool)> {
+        self.names.iter().zip(self.model.iter())
+            .map(|((name, _), &value)| (name.as_str(), value))
+            .collect()
+    }
+
+    /// Returns the value of the variable having the given `name` in the last assignment found,
+    /// or None when there is no such variable (or it was declared after that assignment).
+    pub fn value(&self, name: &str) -> Option<bool> {
+        self.index.get(name).and_then(|&i| self.model.get(i).cloned())
+    }
+
+    /// Turns the given `formula` into a node of the circuit (without adding any clause)
+    fn build(&mut self, formula: &Formula) -> Node {
+        match *formula {
+            Formula::Const(value)     => Node::Const(value),
+            Formula::Var(ref name)    => Node::Lit(self.variable(name)),
+            Formula::Not(ref f)       => self.build(f).negate(),
+            Formula::And(ref fs)      => {
+                let nodes = fs.iter().map(|f| self.build(f)).collect();
+                self.and(nodes)
+            },
+            Formula::Or(ref fs)       => {
+                let nodes = fs.iter().map(|f| self.build(f).negate()).collect();
+                self.and(nodes).negate()
+            },
+            Formula::Implies(ref f, ref g) => {
+                let nodes = vec![self.build(f), self.build(g).negate()];
+                self.and(nodes).negate()
+            },
+            Formula::Iff(ref f, ref g) => {
+                let (f, g) = (self.build(f), self.build(g));
+                self.xor(f, g).negate()
+            },
+            Formula::Xor(ref f, ref g) => {
+                let (f, g) = (self.build(f), self.build(g));
+                self.xor(f, g)
+            },
+            Formula::Ite(ref c, ref t, ref e) => {
+                let (c, t, e) = (self.build(c), self.build(t), self.build(e));
+                self.ite(c, t, e)
+            }
+        }
+    }
+
+    /// Returns the node of the conjunction of the given `nodes`
+    fn and(&mut self, nodes: Vec<Node>) -> Node {
+        let mut literals = vec![];
+        for node in nodes {
+            match node {
+                Node::Const(true)  => {},
+                Node::Const(false) => return Node::Const(false),
+                Node::Lit(l)       => literals.push(l)
+            }
+        }
+        // (x and !x are next to each other once sorted)
+        literals.sort_by_key(|l| (usize::from(l.var()), l.to_isize()));
+        literals.dedup();
+        if literals.windows(2).any(|w| w[0] == !w[1]) {
+            return Node::Const(false);
+        }
+
+        match literals.len() {
+            0 => Node::Const(true),
+            1 => Node::Lit(literals[0]),
+            _ => Node::Lit(self.gate(Gate::And(literals)))
+        }
+    }
+
+    /// Returns the node of the exclusive or of `a` and `b`
+    fn xor(&mut self, a: Node, b: Node) -> Node {
+        match (a, b) {
+            (Node::Const(value), node) | (node, Node::Const(value)) =>
+                if value { node.negate() } else { node },
+            (Node::Lit(a), Node::Lit(b)) => {
+                if a.var() == b.var() {
+                    return Node::Const(a != b);
+                }
+                // a ^ b = !(!a ^ b) = !(a ^ !b)
+                let inverted = (a.to_isize() < 0) != (b.to_isize() < 0);
+                let (a, b) = (Literal::positive(a.var()), Literal::positive(b.var()));
+                let gate = self.gate(Gate::Xor(a.min(b), a.max(b)));
+                Node::Lit(if inverted { !gate } else { gate })
+            }
+        }
+    }
+
+    /// Returns the node of the if-then-else `ite(c, t, e)`
+    fn ite(&mut self, c: Node, t: Node, e: Node) -> Node {
+        let c = match c {
+            Node::Const(value) => return if value { t } else { e },
+            Node::Lit(c)       => c
+        };
+        if t == e {
+            return t;
+        }
+        // ite(!c, t, e) = ite(c, e, t)
+        if let Sign::Negative = c.sign() {
+            return self.ite(Node::Lit(!c), e, t);
+        }
+
+        match (t, e) {
+            // ite(c, true, e) = c | e
+            (Node::Const(true), e)  => self.and(vec![Node::Lit(!c), e.negate()]).negate(),
+            // ite(c, false, e) = !c & e
+            (Node::Const(false), e) => self.and(vec![Node::Lit(!c), e]),
+            // ite(c, t, true) = !c | t
+            (t, Node::Const(true))  => self.and(vec![Node::Lit(c), t.negate()]).negate(),
+            // ite(c, t, false) = c & t
+            (t, Node::Const(false)) => self.and(vec![Node::Lit(c), t]),
+            (Node::Lit(t), Node::Lit(e)) => {
+                // c is known to hold in the then branch, and not to hold in the else branch
+                if t.var() == c.var() {
+                    return self.ite(Node::Lit(c), Node::Const(t == c), Node::Lit(e));
+                }
+                if e.var() == c.var() {
+                    return self.ite(Node::Lit(c), Node::Lit(t), Node::Const(e != c));
+                }
+                // ite(c, t, !t) = c <-> t
+                if t == !e {
+                    return self.xor(Node::Lit(c), Node::Lit(t)).negate();
+                }
+                // ite(c, !t, e) = !ite(c, t, !e)
+                if let Sign::Negative = t.sign() {
+                    return Node::Lit(!self.gate(Gate::Ite(c, !t, !e)));
+                }
+                Node::Lit(self.gate(Gate::Ite(c, t, e)))
+            }
+        }
+    }
+
+    /// Returns the output literal of the given (normalized) `gate`. A new auxiliary variable is
+    /// allocated unless an equal gate already exists.
+    fn gate(&mut self, gate: Gate) -> Literal {
+        if let Some(&output) = self.gates.get(&gate) {
+            return output;
+        }
+
+        let output = Literal::positive(self.solver.new_var());
+        self.gates.insert(gate.clone(), output);
+        self.definitions.insert(usize::from(output.var()),
+                                Definition { gate, positive: false, negative: false });
+        output
+    }
+
+    /// Returns the literal which is always true
+    fn truth(&mut self) -> Literal {
+        if let Some(truth) = self.truth {
+            return truth;
+        }
+        let truth = Literal::positive(self.solver.new_var());
+        let _ = self.solver.add_clause_literals(&[truth]);
+        self.truth = Some(truth);
+        truth
+    }
+
+    /// Adds the clauses making the given literal hold. The top level conjunctions are split into
+    /// units and the top level disjunctions are added as clauses (they need no auxiliary variable).
+    fn assert(&mut self, literal: Literal) {
+        let gate = self.definitions.get(&usize::from(literal.var())).map(|d| d.gate.clone());
+        match (gate, literal.sign()) {
+            (Some(Gate::And(inputs)), Sign::Positive) => {
+                for input in inputs {
+                    self.assert(input);
+                }
+            },
+            (Some(Gate::And(inputs)), Sign::Negative) => {
+                let clause: Vec<Literal> = inputs.iter().map(|&l| !l).collect();
+                for &l in clause.iter() {
+                    self.define(l);
+                }
+                let _ = self.solver.add_clause_literals(&clause);
+            },
+            _ => {
+                self.define(literal);
+                let _ = self.solver.add_clause_literals(&[literal]);
+            }
+        }
+    }
+
+    /// Adds the clauses needed for the given literal to imply the gate it stands for (if any).
+    /// With Tseitin's transformation, the converse implication is added as well.
+    fn define(&mut self, literal: Literal) {
+        self.define_polarity(literal);
+        if self.transformation == Transformation::Tseitin {
+            self.define_polarity(!literal);
+        }
+    }
+
+    /// Adds the clauses `literal -> gate` (where `gate` is the gate of the variable of `literal`,
+    /// negated if the literal is negative) unless they were added already. The literals of the
+    /// gate are defined recursively in the polarity in which they occur in these clauses.
+    fn define_polarity(&mut self, literal: Literal) {
+        let gate = match self.definitions.get_mut(&usize::from(literal.var())) {
+            // it is not an auxiliary variable
+            None             => return,
+            Some(definition) => {
+                let done = match literal.sign() {
+                    Sign::Positive => &mut definition.positive,
+                    Sign::Negative => &mut definition.negative
+                };
+                if *done { return; }
+                *done = true;
+                definition.gate.clone()
+            }
+        };
+
+        // (the clauses of the negative polarity are those of the positive one where every
+        // input of the and, and the branches of the ite are negated)
+        let positive = literal.to_isize() > 0;
+        let flip = |l: Literal| if positive { l } else { !l };
+        match gate {
+            Gate::And(inputs) => {
+                if positive {
+                    for &input in inputs.iter() {
+                        self.define(input);
+                        let _ = self.solver.add_clause_literals(&[!literal, input]);
+                    }
+                } else {
+                    let mut clause = vec![!literal];
+                    for &input in inputs.iter() {
+                        self.define(!input);
+                        clause.push(!input);
+                    }
+                    let _ = self.solver.add_clause_literals(&clause);
+                }
+            },
+            Gate::Xor(a, b) => {
+                for &l in [a, !a, b, !b].iter() {
+                    self.define(l);
+                }
+                let _ = self.solver.add_clause_literals(&[!literal, flip(a), b]);
+                let _ = self.solver.add_clause_literals(&[!literal, !flip(a), !b]);
+            },
+            Gate::Ite(c, t, e) => {
+                for &l in [c, !c, flip(t), flip(e)].iter() {
+                    self.define(l);
+                }
+                let _ = self.solver.add_clause_literals(&[!literal, !c, flip(t)]);
+                let _ = self.solver.add_clause_literals(&[!literal,  c, flip(e)]);
+            }
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Unit Tests
+// -----------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSFORMATIONS: [Transformation; 2] =
+        [Transformation::Tseitin, Transformation::PlaistedGreenbaum];
+
+    fn parse(text: &str) -> Formula {
+        Formula::parse(text).unwrap()
+    }
+
+    fn var(name: &str) -> Box<Formula> {
+        Box::new(Formula::var(name))
+    }
+
+    /// Evaluates the formula given the value of its variables
+    fn evaluate(formula: &Formula, value: &dyn Fn(&str) -> bool) -> bool {
+        match *formula {
+            Formula::Const(v)              => v,
+            Formula::Var(ref name)         => value(name),
+            Formula::Not(ref f)            => !evaluate(f, value),
+            Formula::And(ref fs)           => fs.iter().all(|f| evaluate(f, value)),
+            Formula::Or(ref fs)            => fs.iter().any(|f| evaluate(f, value)),
+            Formula::Implies(ref f, ref g) => !evaluate(f, value) || evaluate(g, value),
+            Formula::Iff(ref f, ref g)     => evaluate(f, value) == evaluate(g, value),
+            Formula::Xor(ref f, ref g)     => evaluate(f, value) != evaluate(g, value),
+            Formula::Ite(ref c, ref t, ref e) =>
+                if evaluate(c, value) { evaluate(t, value) } else { evaluate(e, value) }
+        }
+    }
+
+    #[test]
+    fn parse_follows_the_precedence_of_the_operators() {
+        assert_eq!(parse("a | b & !c"),
+                   Formula::Or(vec![Formula::var("a"),
+                                    Formula::And(vec![Formula::var("b"), Formula::Not(var("c"))])]));
+        assert_eq!(parse("a -> b -> c"),
+                   Formula::Implies(var("a"), Box::new(Formula::Implies(var("b"), var("c")))));
+        assert_eq!(parse("a <=> b ^ c || d"),
+                   Formula::Iff(var("a"),
+                                Box::new(Formula::Or(vec![Formula::Xor(var("b"), var("c")),
+                                                          Formula::var("d")]))));
+        assert_eq!(parse("~(a && true) => ite(x.1, y', false)"),
+                   Formula::Implies(
+                       Box::new(Formula::Not(Box::new(Formula::And(vec![Formula::var("a"), Formula::Const(true)])))),
+                       Box::new(Formula::Ite(var("x.1"), var("y'"), Box::new(Formula::Const(false))))));
+        // ite is only a keyword when it is followed by a parenthesis
+        assert_eq!(parse("ite & b"), Formula::And(vec![Formula::var("ite"), Formula::var("b")]));
+    }
+
+    #[test]
+    fn parse_tells_what_is_wrong() {
+        assert_eq!(Formula::parse("a & (b | c"), Err(String::from("unexpected end of formula")));
+        assert_eq!(Formula::parse("a b"),        Err(String::from("unexpected `b` at offset 2")));
+        assert_eq!(Formula::parse("a & | b"),    Err(String::from("unexpected `|` at offset 4")));
+        assert_eq!(Formula::parse("a + b"),      Err(String::from("unexpected `+` at offset 2")));
+        assert_eq!(Formula::parse("ite(a, b)"),  Err(String::from("unexpected `)` at offset 8")));
+    }
+
+    #[test]
+    fn the_literal_of_a_formula_is_equivalent_to_it() {
+        let texts = [
+            "a & !b | c",
+            "(a -> b) <-> (!b -> !a) & c",
+            "a ^ b ^ c ^ !a",
+            "ite(a, b & c, b | !c) ^ (c -> a)",
+            "ite(!a, !b, c) | ite(a, a, b) & ite(b, c, !c)",
+            "ite(c, a & b, false) <-> (a | ite(b, true, c))",
+            "!(a | b) & (a <-> !b) | ite(a ^ b, a & c, a | c)"
+        ];
+        let names = ["a", "b", "c"];
+
+        for &transformation in TRANSFORMATIONS.iter() {
+            for text in texts.iter() {
+                let formula = parse(text);
+                let mut tested = Circuit::new();
+                tested.transformation = transformation;
+                let literal = tested.literal(&formula);
+
+                for bits in 0..8 {
+                    let value = |name: &str| bits & (1 << names.iter().position(|&n| n == name).unwrap()) != 0;
+                    let mut assumptions: Vec<Literal> = names.iter()
+                        .map(|&n| if value(n) { tested.variable(n) } else { !tested.variable(n) })
+                        .collect();
+
+                    assumptions.push(literal);
+                    assert_eq!(tested.solve_with_assumptions(&assumptions), evaluate(&formula, &value),
+                               "{} ({:?})", text, transformation);
+                    assumptions.pop();
+                    assumptions.push(!literal);
+                    assert_eq!(tested.solve_with_assumptions(&assumptions), !evaluate(&formula, &value),
+                               "{} ({:?})", text, transformation);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn the_models_satisfy_the_formulas() {
+        for &transformation in TRANSFORMATIONS.iter() {
+            let formulas = [parse("p -> q & r"), parse("p | s"), parse("!s"), parse("r ^ t")];
+            let mut tested = Circuit::new();
+            tested.transformation = transformation;
+            for formula in formulas.iter() {
+                tested.add_formula(formula);
+            }
+
+            assert!(tested.solve());
+            assert_eq!(tested.model(),
+                       vec![("p", true), ("q", true), ("r", true), ("s", false), ("t", false)]);
+            assert_eq!(tested.value("q"), Some(true));
+            assert_eq!(tested.value("u"), None);
+        }
+    }
+
+    #[test]
+    fn unsatisfiable_formulas_have_no_model() {
+        for &transformation in TRANSFORMATIONS.iter() {
+            let mut tested = Circuit::new();
+            tested.transformation = transformation;
+            tested.add_formula(&parse("(a <-> b) & (b <-> c)"));
+            tested.add_formula(&parse("a ^ c"));
+
+            assert!(!tested.solve());
+        }
+    }
+
+    #[test]
+    fn the_constants_are_propagated_away() {
+        let mut tested = Circuit::new();
+        tested.add_formula(&parse("true & (a | false) & !(b & false)"));
+        tested.add_formula(&parse("ite(true, c, d) -> ite(false, e, !c)"));
+
+        assert_eq!(tested.nb_gates(), 0);
+        assert!(tested.solve());
+        assert_eq!(tested.value("a"), Some(true));
+        assert_eq!(tested.value("c"), Some(false));
+
+        tested.add_formula(&parse("a -> false"));
+        assert!(!tested.solve());
+    }
+
+    #[test]
+    fn equal_subformulas_share_their_gate() {
+        let mut tested = Circuit::new();
+        let literal = tested.literal(&parse("(a & b) ^ c"));
+        assert_eq!(tested.literal(&parse("c ^ (b & a)")), literal);
+        assert_eq!(tested.literal(&parse("!c <-> (b & a & a)")), literal);
+        assert_eq!(tested.literal(&parse("!(c ^ !!(a & b))")), !literal);
+
+        // the only gates are a & b and c ^ (a & b)
+        assert_eq!(tested.nb_gates(), 2);
+        assert_eq!(tested.nb_vars(), 3);
+    }
+
+    #[test]
+    fn plaisted_greenbaum_needs_fewer_clauses_than_tseitin() {
+        let formula = parse("(a & b | c & d) -> (e | a & c)");
+        let mut sizes = vec![];
+        for &transformation in TRANSFORMATIONS.iter() {
+            let mut tested = Circuit::new();
+            tested.transformation = transformation;
+            tested.add_formula(&formula);
+            assert!(tested.solve());
+            sizes.push(tested.solver.clauses.len());
+        }
+        assert!(sizes[1] < sizes[0], "{:?}", sizes);
+    }
+
+    #[test]
+    fn transformation_from_name() {
+        assert_eq!(Transformation::from_name("tseitin"), Some(Transformation::Tseitin));
+        assert_eq!(Transformation::from_name("pg"),      Some(Transformation::PlaistedGreenbaum));
+        assert_eq!(Transformation::from_name("cnf"),     None);
+    }
+}
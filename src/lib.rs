@@ -3,9 +3,23 @@ mod core;
 mod collections;
 mod solving;
 mod dimacs;
+mod maxsat;
+mod encodings;
+mod pseudo_boolean;
+mod formula;
+mod aiger;
+mod mus;
+#[cfg(test)]
+mod fixtures;
 
 // re-export
 pub use self::core::*;
 pub use self::collections::*;
 pub use self::solving::*;
-pub use self::dimacs::*;
\ No newline at end of file
+pub use self::dimacs::*;
+pub use self::maxsat::*;
+pub use self::encodings::*;
+pub use self::pseudo_boolean::*;
+pub use self::formula::*;
+pub use self::aiger::*;
+pub use self::mus::*;
\ No newline at end of file
@@ -9,7 +9,7 @@ use rsolve::*;
 
 use argparse::*;
 
-use std::io::{stdin, BufRead, BufReader};
+use std::io::{stdin, BufRead, BufReader, Cursor, Lines};
 use std::fs::File;
 use xz2::bufread::XzDecoder;
 use bzip2::bufread::BzDecoder;
@@ -18,7 +18,6 @@ use flate2::bufread::GzDecoder;
 
 use std::time::*;
 
-// TODO: Test - Solver.rs -> partial restarts
 // TODO: Test - dimacs.rs -> *
 // TODO: Dev  - LRB
 // TODO: Dev  - LCM
@@ -34,7 +33,32 @@ use std::time::*;
 struct CliArgs {
     filename   : Option<String>,
     print_model: bool,
-    drat       : bool  // See: ''Trimming while Checking Clausal Proofs'' -- Heule, Hunt, Wetzler (FMCAD), 2013
+    drat       : bool, // See: ''Trimming while Checking Clausal Proofs'' -- Heule, Hunt, Wetzler (FMCAD), 2013
+    chrono     : bool, // See: ''Chronological Backtracking'' -- Nadel, Ryvchin (SAT), 2018
+    chrono_threshold: u32,
+    reuse_trail: bool, // See: ''Reusing the Assignment Trail in CDCL Solvers'' -- van der Tak, Ramos, Heule (JSAT), 2011
+    shrink     : bool, // See: ''Efficient All-UIP Learned Clause Minimization'' -- Fleury, Biere (SAT), 2021
+    binary_minimization: bool,
+    restarts   : String,
+    restart_blocking: bool, // See: ''Refining Restarts Strategies for SAT and UNSAT'' -- Audemard, Simon (CP), 2012
+    threads    : usize,
+    cube_depth : usize, // See: ''Cube and Conquer: Guiding CDCL SAT Solvers by Lookaheads'' -- Heule, Kullmann, Wieringa, Biere (HVC), 2011
+    icnf       : Option<String>,
+    pb_encoding: String,
+    gauss      : bool, // See: ''Gaussian Elimination with Boolean Constraint Propagation'' -- Han, Jiang (CAV), 2012
+    formula    : bool,
+    transformation: String, // See: ''A Structure-preserving Clause Form Translation'' -- Plaisted, Greenbaum (JSC), 1986
+    bmc_depth  : usize, // See: ''Symbolic Model Checking without BDDs'' -- Biere, Cimatti, Clarke, Zhu (TACAS), 1999
+    enumerate  : Option<usize>, // the maximum number of models to enumerate (0 means all of them)
+    enumeration: String, // See: ''Implementing Efficient All Solutions SAT Solvers'' -- Toda, Soh (JEA), 2016
+    count      : bool, // See: ''Implementing a Competitive #SAT Solver: sharpSAT'' -- Thurley (SAT), 2006
+    approx_count: bool, // See: ''Algorithmic Improvements in Approximate Counting for Probabilistic Inference'' -- Chakraborty, Meel, Vardi (IJCAI), 2016
+    epsilon    : f64,
+    delta      : f64,
+    seed       : u64,
+    mus        : bool, // See: ''On Improving MUS Extraction Algorithms'' -- Marques-Silva, Lynce (SAT), 2011
+    mus_format : String,
+    mcs        : Option<usize> // See: ''On Computing Minimal Correction Subsets'' -- Marques-Silva, Heras, Janota, Previti, Belov (IJCAI), 2013
 }
 
 fn main() {
@@ -42,29 +66,586 @@ fn main() {
 
     print_header();
     let args = arguments();
-    let mut lines = input(&args).lines();
-    let mut solver = parse_header(&mut lines);
 
-    solver.drat = args.drat;
-    /*
-    println!("start load clauses");
-    let start = PreciseTime::now();
-    */
-    load_clauses(&mut solver, &mut lines);
-    // solver.preprocess(); TODO
-    println!("clauses : {}", solver.clauses.len());
-    /*
-    let end = PreciseTime::now();
-    println!("end load clauses {}", start.to(end));*/
+    if (args.threads > 1 || args.cube_depth > 0) && args.drat {
+        eprintln!("DRAT proofs cannot be produced when solving with several threads or with cubes");
+        std::process::exit(2);
+    }
+
+    let mut input = input(&args);
+    if !args.formula && is_aiger(&mut input) {
+        require_single_search(&args, "AIGER");
+        solve_bmc(&args, load_aiger(&mut input), &now);
+        return;
+    }
+
+    let mut lines = input.lines();
+    if args.formula {
+        require_single_search(&args, "Propositional");
+        let mut circuit = new_circuit(&args);
+        load_formulas(&mut circuit, &mut lines);
+        solve_circuit(&args, circuit, &now);
+        return;
+    }
+
+    let (header, declared) = read_header_and_projection(&mut lines);
+    let nb_vars = match header {
+        Header::Cnf(nb_vars) => nb_vars,
+        Header::Icnf         => {
+            require_single_search(&args, "iCNF");
+            solve_incremental(&args, &mut lines, &now);
+            return;
+        },
+        Header::Wcnf(nb_vars, top) => {
+            require_single_search(&args, "WCNF");
+            let mut maxsat = new_maxsat(&args, nb_vars);
+            load_wcnf(&mut maxsat, &mut lines, top);
+            solve_maxsat(&args, maxsat, false, &now);
+            return;
+        },
+        Header::Headerless(first_line) => {
+            // without problem line, the input is assumed to be a (2022 style) WCNF problem
+            require_single_search(&args, "WCNF");
+            let mut maxsat = new_maxsat(&args, 0);
+            load_wcnf_line(&mut maxsat, &first_line, None);
+            load_wcnf(&mut maxsat, &mut lines, None);
+            solve_maxsat(&args, maxsat, true, &now);
+            return;
+        },
+        Header::Opb(nb_vars) => {
+            require_single_search(&args, "OPB");
+            let mut pb = new_pseudo_boolean(&args, nb_vars);
+            load_opb(&mut pb, &mut lines);
+            solve_pseudo_boolean(&args, pb, &now);
+            return;
+        },
+        Header::Gcnf(nb_vars) => {
+            require_single_search(&args, "GCNF");
+            let mut mus = new_mus(&args, nb_vars);
+            load_gcnf(&mut mus, &mut lines);
+            println!("clauses : {}", mus.nb_clauses());
+            println!("groups  : {}", mus.nb_groups());
+            if let Some(limit) = args.mcs {
+                enumerate_mcses(mus, limit, &now);
+            } else {
+                extract_group_mus(mus, &now);
+            }
+            return;
+        }
+    };
+
+    if let Some(limit) = args.mcs {
+        require_single_search(&args, "MCS enumeration");
+        let mut mus = new_mus(&args, nb_vars);
+        load_mus(&mut mus, &mut lines);
+        println!("clauses : {}", mus.nb_clauses());
+        enumerate_mcses(mus, limit, &now);
+        return;
+    }
+
+    if args.mus {
+        require_single_search(&args, "MUS extraction");
+        extract_mus(&args, nb_vars, &mut lines, &now);
+        return;
+    }
+
+    if args.approx_count {
+        require_single_search(&args, "Approximate model counting");
+        approx_count_models(&args, nb_vars, &declared, &mut lines, &now);
+        return;
+    }
+
+    if args.count {
+        require_single_search(&args, "Model counting");
+        count_models(&args, nb_vars, &declared, &mut lines, &now);
+        return;
+    }
+
+    if let Some(limit) = args.enumerate {
+        require_single_search(&args, "Enumeration");
+        enumerate_models(&args, nb_vars, &declared, &mut lines, limit, &now);
+        return;
+    }
+
+    let (satisfiable, solver) = if args.cube_depth > 0 {
+        let mut solvers = load_solvers(&args, nb_vars, &mut lines, args.threads.max(1));
+        print_size(&solvers[0]);
+
+        let cubes = Lookahead::new(&mut solvers[0], args.cube_depth).cubes();
+        println!("cubes   : {}", cubes.len());
+
+        if let Some(ref fname) = args.icnf {
+            let mut file = File::create(fname)
+                .unwrap_or_else(|_| panic!("could not create {}", fname));
+            write_icnf(&mut file, &solvers[0].problem_clauses(), &cubes)
+                .unwrap_or_else(|_| panic!("could not write {}", fname));
+            return;
+        }
+
+        CubeAndConquer::new(solvers, cubes).solve()
+    } else if args.threads > 1 {
+        let solvers = load_solvers(&args, nb_vars, &mut lines, args.threads);
+        print_size(&solvers[0]);
+
+        Portfolio::new(solvers).solve()
+    } else {
+        let mut solver = load_solver(&args, nb_vars, &mut lines);
+        // solver.preprocess(); TODO
+        print_size(&solver);
+
+        let satisfiable = solver.solve();
+        (satisfiable, solver)
+    };
+
+    print_answer(&solver, &args, satisfiable);
+    print_statistics(&solver, &elapsed(&now));
+}
+
+/// This function solves the iCNF problem read from the given `lines` (past its header). The
+/// problem is solved under each of its cubes in turn, and the answer is printed for each of them.
+/// The clauses learned while solving one cube are reused for the next ones.
+fn solve_incremental<Source: BufRead>(args: &CliArgs, lines: &mut Lines<Source>, now: &SystemTime) {
+    let mut solver = Solver::new(0);
+    configure(&mut solver, args);
+
+    let mut nb_cubes = 0;
+    for item in IcnfReader::new(lines) {
+        match item {
+            IcnfItem::Clause(mut clause) => {
+                declare_vars(&mut solver, &clause);
+                // an error only means the problem is unsat: all the cubes will be refuted
+                let _ = solver.add_problem_clause(&mut clause);
+            },
+            IcnfItem::Cube(cube) => {
+                declare_vars(&mut solver, &cube);
+                nb_cubes += 1;
+
+                let assumptions: Vec<Literal> = cube.iter().map(|&l| lit(l)).collect();
+                let satisfiable = solver.solve_with_assumptions(&assumptions);
+
+                println!("c cube {}", nb_cubes);
+                print_answer(&solver, args, satisfiable);
+            }
+        }
+    }
+
+    // without any cube, the problem is simply solved once
+    if nb_cubes == 0 {
+        let satisfiable = solver.solve();
+        print_answer(&solver, args, satisfiable);
+    }
+
+    print_statistics(&solver, &elapsed(now));
+}
+
+/// This function exits with an error when the cli-args ask for several threads or cubes to solve
+/// a problem in the given `format` (which only supports one single search).
+fn require_single_search(args: &CliArgs, format: &str) {
+    if args.threads > 1 || args.cube_depth > 0 {
+        eprintln!("{} problems can only be solved with a single thread and without cubes", format);
+        std::process::exit(2);
+    }
+}
+
+/// This function creates a MaxSAT problem having `nb_vars` variables whose solver is configured
+/// according to the cli-args.
+fn new_maxsat(args: &CliArgs, nb_vars: usize) -> MaxSat {
+    if args.drat {
+        eprintln!("DRAT proofs cannot be produced for MaxSAT problems");
+        std::process::exit(2);
+    }
+
+    let mut maxsat = MaxSat::new(nb_vars);
+    configure(&mut maxsat.solver, args);
+    maxsat
+}
+
+/// This function finds an optimal solution of the given MaxSAT problem and prints it the way the
+/// MaxSAT evaluations expect it: the cost on a `o` line, the status on a `s` line and the model
+/// on a `v` line. The model is printed as a string of 0 and 1 when `binary_model` is set (2022
+/// style), and as a list of literals otherwise.
+fn solve_maxsat(args: &CliArgs, mut maxsat: MaxSat, binary_model: bool, now: &SystemTime) {
+    println!("clauses : {}", maxsat.solver.clauses.len());
+
+    match maxsat.solve() {
+        Some(cost) => {
+            println!("o {}", cost);
+            println!("s OPTIMUM FOUND");
+            if args.print_model { print_maxsat_model(&maxsat, binary_model); }
+        },
+        None => println!("s UNSATISFIABLE")
+    }
+
+    print_statistics(&maxsat.solver, &elapsed(now));
+}
+
+/// This function creates a pseudo-Boolean problem having `nb_vars` variables whose solver and
+/// encoding are configured according to the cli-args.
+fn new_pseudo_boolean(args: &CliArgs, nb_vars: usize) -> PseudoBoolean {
+    if args.drat {
+        eprintln!("DRAT proofs cannot be produced for pseudo-Boolean problems");
+        std::process::exit(2);
+    }
+
+    let mut pb = PseudoBoolean::new(nb_vars);
+    configure(&mut pb.solver, args);
+    pb.encoding = match PbEncoding::from_name(&args.pb_encoding) {
+        Some(encoding) => encoding,
+        None           => {
+            eprintln!("Unknown pseudo-Boolean encoding `{}` (expected bdd, adder or sorter)", args.pb_encoding);
+            std::process::exit(2);
+        }
+    };
+    pb
+}
+
+/// This function solves the given pseudo-Boolean problem (optimally when it has an objective) and
+/// prints the answer the way the PB competitions expect it: the value of every improving solution
+/// on a `o` line, the status on a `s` line and the model on a `v` line.
+fn solve_pseudo_boolean(args: &CliArgs, mut pb: PseudoBoolean, now: &SystemTime) {
+    match pb.minimize(|value| println!("o {}", value)) {
+        Some(_) => {
+            println!("s {}", if pb.has_objective() { "OPTIMUM FOUND" } else { "SATISFIABLE" });
+            if args.print_model { print_pseudo_boolean_model(&pb); }
+        },
+        None => println!("s UNSATISFIABLE")
+    }
+    println!("clauses : {}", pb.solver.clauses.len());
+
+    print_statistics(&pb.solver, &elapsed(now));
+}
+
+/// This function creates an empty circuit whose solver and transformation are configured
+/// according to the cli-args.
+fn new_circuit(args: &CliArgs) -> Circuit {
+    if args.drat {
+        eprintln!("DRAT proofs cannot be produced for propositional formulas");
+        std::process::exit(2);
+    }
+
+    let mut circuit = Circuit::new();
+    configure(&mut circuit.solver, args);
+    circuit.transformation = match Transformation::from_name(&args.transformation) {
+        Some(transformation) => transformation,
+        None                 => {
+            eprintln!("Unknown transformation `{}` (expected tseitin or pg)", args.transformation);
+            std::process::exit(2);
+        }
+    };
+    circuit
+}
+
+/// This function solves the formulas of the given circuit and prints the answer. The model (when
+/// asked for) lists the named variables, negated when they are false.
+fn solve_circuit(args: &CliArgs, mut circuit: Circuit, now: &SystemTime) {
+    print_size(&circuit.solver);
+
+    if circuit.solve() {
+        println!("s SATISFIABLE");
+        if args.print_model { print_circuit_model(&circuit); }
+    } else {
+        println!("s UNSATISFIABLE");
+    }
+
+    print_statistics(&circuit.solver, &elapsed(now));
+}
+
+/// This function tells whether the given input is an AIGER circuit (in the ASCII or binary
+/// format) without consuming any of it.
+fn is_aiger(input: &mut dyn BufRead) -> bool {
+    match input.fill_buf() {
+        Ok(start) => start.starts_with(b"aag ") || start.starts_with(b"aig "),
+        Err(_)    => false
+    }
+}
+
+/// This function looks for an execution of the given circuit reaching a bad state within the
+/// depth of the cli-args, and prints the answer: SATISFIABLE when a bad state is reachable (along
+/// with the witness of that execution when a model is asked for) and UNSATISFIABLE otherwise.
+fn solve_bmc(args: &CliArgs, aiger: Aiger, now: &SystemTime) {
+    if args.drat {
+        eprintln!("DRAT proofs cannot be produced for AIGER circuits");
+        std::process::exit(2);
+    }
+
+    let mut bmc = Bmc::new(aiger);
+    configure(&mut bmc.solver, args);
+
+    match bmc.check(args.bmc_depth) {
+        Some(witness) => {
+            println!("s SATISFIABLE");
+            println!("c bad state reached at depth {}", witness.inputs.len() - 1);
+            if args.print_model { println!("{}", witness); }
+        },
+        None => println!("s UNSATISFIABLE")
+    }
+    print_size(&bmc.solver);
+
+    print_statistics(&bmc.solver, &elapsed(now));
+}
+
+/// This function enumerates the models of the DIMACS CNF problem read from the given `lines`
+/// (projected onto the variables of its `c ind` or `c p show` lines, if any, including those
+/// `declared` before its header) and prints each of them on a `v` line, but no more than `limit`
+/// of them unless it is 0.
+fn enumerate_models<Source: BufRead>(args: &CliArgs, nb_vars: usize, declared: &[usize], lines: &mut Lines<Source>, limit: usize, now: &SystemTime) {
+    if args.drat {
+        eprintln!("DRAT proofs cannot be produced while enumerating models");
+        std::process::exit(2);
+    }
+    let strategy = match Enumeration::from_name(&args.enumeration) {
+        Some(strategy) => strategy,
+        None           => {
+            eprintln!("Unknown enumeration strategy `{}` (expected blocking or decisions)", args.enumeration);
+            std::process::exit(2);
+        }
+    };
+
+    let mut solver = Solver::new(nb_vars);
+    configure(&mut solver, args);
+    let mut projection = declare_projection(&mut solver, declared);
+    projection.extend(load_clauses_and_projection(&mut solver, lines));
+    if args.gauss {
+        solver.detect_xors();
+    }
+    print_size(&solver);
+
+    let limit     = if limit == 0 { None } else { Some(limit) };
+    let nb_models = {
+        let mut allsat = AllSat::new(&mut solver, &projection);
+        allsat.strategy = strategy;
+        allsat.enumerate(limit, |model| {
+            let mut line = String::from("v ");
+            for l in model.iter() {
+                line.push_str(&format!("{} ", l.to_isize()));
+            }
+            line.push('0');
+            println!("{}", line);
+        })
+    };
+
+    println!("models  : {}", nb_models);
+    println!("s {}", if nb_models > 0 { "SATISFIABLE" } else { "UNSATISFIABLE" });
+    print_statistics(&solver, &elapsed(now));
+}
+
+/// This function counts the models of the DIMACS CNF problem read from the given `lines`
+/// (projected onto the variables of its `c ind` or `c p show` lines, if any, including those
+/// `declared` before its header) and prints their number in the format of the model counting
+/// competitions.
+fn count_models<Source: BufRead>(args: &CliArgs, nb_vars: usize, declared: &[usize], lines: &mut Lines<Source>, now: &SystemTime) {
+    if args.drat {
+        eprintln!("DRAT proofs cannot be produced while counting models");
+        std::process::exit(2);
+    }
+
+    let mut solver = Solver::new(nb_vars);
+    configure(&mut solver, args);
+    let mut projection = declare_projection(&mut solver, declared);
+    projection.extend(load_clauses_and_projection(&mut solver, lines));
+    if args.gauss {
+        solver.detect_xors();
+    }
+    print_size(&solver);
+
+    let (count, nb_cache_hits) = {
+        let mut counter = ModelCounter::new(&mut solver, &projection);
+        (counter.count(), counter.nb_cache_hits)
+    };
 
-    let satisfiable = solver.solve();
+    println!("s {}", if count.is_zero() { "UNSATISFIABLE" } else { "SATISFIABLE" });
+    println!("c s type {}", if projection.is_empty() { "mc" } else { "pmc" });
+    println!("c s exact arb int {}", count);
+    println!("c cache hits {}", nb_cache_hits);
+    print_statistics(&solver, &elapsed(now));
+}
+
+/// This function estimates the number of models of the DIMACS CNF problem read from the given
+/// `lines` (projected onto the variables of its `c ind` or `c p show` lines, if any, including
+/// those `declared` before its header) with the tolerance and confidence of the cli-args, and
+/// prints it in the format of the model counting competitions.
+fn approx_count_models<Source: BufRead>(args: &CliArgs, nb_vars: usize, declared: &[usize], lines: &mut Lines<Source>, now: &SystemTime) {
+    if args.drat {
+        eprintln!("DRAT proofs cannot be produced while counting models");
+        std::process::exit(2);
+    }
+    let guarantees = args.epsilon > 0.0 && args.delta > 0.0 && args.delta < 1.0;
+    if !guarantees {
+        eprintln!("The tolerance must be positive and the confidence must lie between 0 and 1");
+        std::process::exit(2);
+    }
+
+    let mut solver = Solver::new(nb_vars);
+    configure(&mut solver, args);
+    let mut projection = declare_projection(&mut solver, declared);
+    projection.extend(load_clauses_and_projection(&mut solver, lines));
+    if args.gauss {
+        solver.detect_xors();
+    }
+    print_size(&solver);
+
+    let (count, nb_solver_calls) = {
+        let mut counter = ApproxCounter::new(&mut solver, &projection, args.seed);
+        counter.epsilon     = args.epsilon;
+        counter.delta       = args.delta;
+        (counter.count(), counter.nb_solver_calls)
+    };
 
-    let duration = match now.elapsed() {
+    println!("s {}", if count.is_zero() { "UNSATISFIABLE" } else { "SATISFIABLE" });
+    println!("c s type {}", if projection.is_empty() { "mc" } else { "pmc" });
+    println!("c s approx arb int {}", count);
+    println!("c epsilon {} delta {}", args.epsilon, args.delta);
+    println!("c solver calls {}", nb_solver_calls);
+    print_statistics(&solver, &elapsed(now));
+}
+
+/// This function extracts a minimal unsatisfiable subset of the clauses of the DIMACS CNF problem
+/// read from the given `lines`, and prints it either as a DIMACS CNF problem or as the indices of
+/// its clauses in the input (on a `v` line, as in the MUS track of the SAT competition).
+fn extract_mus<Source: BufRead>(args: &CliArgs, nb_vars: usize, lines: &mut Lines<Source>, now: &SystemTime) {
+    if args.mus_format != "dimacs" && args.mus_format != "indices" {
+        eprintln!("Unknown MUS format `{}` (expected dimacs or indices)", args.mus_format);
+        std::process::exit(2);
+    }
+
+    let mut mus = new_mus(args, nb_vars);
+    load_mus(&mut mus, lines);
+    println!("clauses : {}", mus.nb_clauses());
+
+    match mus.extract() {
+        None         => println!("s SATISFIABLE"),
+        Some(subset) => {
+            println!("s UNSATISFIABLE");
+            if args.mus_format == "indices" {
+                let indices: Vec<String> = subset.iter().map(|i| (i + 1).to_string()).collect();
+                println!("v {} 0", indices.join(" "));
+            } else {
+                println!("p cnf {} {}", mus.nb_vars(), subset.len());
+                for &i in subset.iter() {
+                    let clause: Vec<String> = mus.group(i)[0].iter().map(|l| l.to_isize().to_string()).collect();
+                    println!("{} 0", clause.join(" "));
+                }
+            }
+        }
+    }
+
+    println!("c solver calls {}", mus.nb_solver_calls);
+    println!("c rotated {}", mus.nb_rotated);
+    print_statistics(&mus.solver, &elapsed(now));
+}
+
+/// This function extracts a minimal unsatisfiable subset of the groups of the given GCNF problem,
+/// and prints the (1-based) identifiers of its groups on a `v` line.
+fn extract_group_mus(mut mus: Mus, now: &SystemTime) {
+    match mus.extract() {
+        None         => println!("s SATISFIABLE"),
+        Some(subset) => {
+            println!("s UNSATISFIABLE");
+            let groups: Vec<String> = subset.iter().map(|i| (i + 1).to_string()).collect();
+            println!("v {} 0", groups.join(" "));
+        }
+    }
+
+    println!("c solver calls {}", mus.nb_solver_calls);
+    println!("c rotated {}", mus.nb_rotated);
+    print_statistics(&mus.solver, &elapsed(now));
+}
+
+/// This function enumerates the minimal correction subsets of the given problem (but no more
+/// than `limit` of them, 0 meaning all of them) and prints one `v` line per MCS: the positions of
+/// its clauses in the input, or the identifiers of its groups (counted from 1).
+fn enumerate_mcses(mut mus: Mus, limit: usize, now: &SystemTime) {
+    let limit = if limit == 0 { None } else { Some(limit) };
+    let mut satisfiable = false;
+    let nb_mcses = mus.enumerate_mcses(limit, |mcs| {
+        satisfiable = mcs.is_empty();
+        let mut line = String::from("v ");
+        for g in mcs.iter() {
+            line.push_str(&format!("{} ", g + 1));
+        }
+        line.push('0');
+        println!("{}", line);
+    });
+
+    println!("mcses   : {}", nb_mcses);
+    println!("s {}", if satisfiable { "SATISFIABLE" } else { "UNSATISFIABLE" });
+    println!("c solver calls {}", mus.nb_solver_calls);
+    print_statistics(&mus.solver, &elapsed(now));
+}
+
+/// This function creates an (empty) MUS extractor, configured according to the command line
+/// arguments, for a problem having `nb_vars` variables.
+fn new_mus(args: &CliArgs, nb_vars: usize) -> Mus {
+    if args.drat {
+        eprintln!("DRAT proofs cannot be produced while extracting a MUS or enumerating MCSes");
+        std::process::exit(2);
+    }
+
+    let mut mus = Mus::new(nb_vars);
+    configure(&mut mus.solver, args);
+    mus
+}
+
+/// This function adds the variables of the given `literals` the solver does not know of yet.
+fn declare_vars(solver: &mut Solver, literals: &[iint]) {
+    let max = literals.iter().map(|l| l.unsigned_abs() as usize).max().unwrap_or(0);
+    while solver.nb_vars() < max {
+        solver.new_var();
+    }
+}
+
+/// This function tells how much time has elapsed since `now`.
+fn elapsed(now: &SystemTime) -> Duration {
+    match now.elapsed() {
         Ok(t) => t,
         Err(t)=> t.duration() // totally unlikely !!!
-    };
+    }
+}
+
+/// This function creates `n` solvers having `nb_vars` variables configured according to the
+/// cli-args, each of which is loaded with its own copy of the clauses read from `lines`.
+fn load_solvers<Source: BufRead>(args: &CliArgs, nb_vars: usize, lines: &mut Lines<Source>, n: usize) -> Vec<Solver> {
+    let mut text = String::new();
+    for line in lines {
+        text.push_str(&line.expect("could not read the input"));
+        text.push('\n');
+    }
+
+    (0..n).map(|_| load_solver(args, nb_vars, &mut Cursor::new(text.as_bytes()).lines())).collect()
+}
+
+/// This function creates a solver having `nb_vars` variables configured according to the cli-args
+/// and loads it with the DIMACS CNF clauses read from the given `lines`.
+fn load_solver<Source: BufRead>(args: &CliArgs, nb_vars: usize, lines: &mut Lines<Source>) -> Solver {
+    let mut solver = Solver::new(nb_vars);
+    configure(&mut solver, args);
+    load_clauses(&mut solver, lines);
+    if args.gauss {
+        solver.detect_xors();
+    }
+    if args.drat && solver.nb_xors() > 0 {
+        eprintln!("DRAT proofs cannot be produced when reasoning on XOR constraints");
+        std::process::exit(2);
+    }
+    solver
+}
 
-    print_result(&solver,&args, satisfiable, &duration);
+/// This function configures the given solver according to the cli-args.
+fn configure(solver: &mut Solver, args: &CliArgs) {
+    solver.drat = args.drat;
+    solver.chrono_backtrack = args.chrono;
+    solver.chrono_threshold = args.chrono_threshold;
+    solver.reuse_trail = args.reuse_trail;
+    solver.shrink = args.shrink;
+    solver.binary_minimization = args.binary_minimization;
+    solver.restart_blocking = args.restart_blocking;
+    solver.restart_strat = match RestartStrategy::from_name(&args.restarts) {
+        Some(strategy) => strategy,
+        None           => {
+            eprintln!("Unknown restart strategy `{}` (expected inout, luby, glucose or ema)", args.restarts);
+            std::process::exit(2);
+        }
+    };
 }
 
 fn print_header() {
@@ -76,7 +657,7 @@ fn print_header() {
     println!("c ==============================================================================");
 }
 
-fn print_result(solver: &Solver, config: &CliArgs, satisfiable: bool, elapsed: &Duration){
+fn print_answer(solver: &Solver, config: &CliArgs, satisfiable: bool){
     if satisfiable {
         println!("s SATISFIABLE");
 
@@ -85,7 +666,16 @@ fn print_result(solver: &Solver, config: &CliArgs, satisfiable: bool, elapsed: &
     } else {
         println!("s UNSATISFIABLE");
     }
+}
+
+fn print_size(solver: &Solver) {
+    println!("clauses : {}", solver.clauses.len());
+    if solver.nb_xors() > 0 {
+        println!("xors    : {}", solver.nb_xors());
+    }
+}
 
+fn print_statistics(solver: &Solver, elapsed: &Duration){
     let elapsed_time = elapsed.as_secs() as f64 + elapsed.subsec_nanos() as f64 * 1e-9;
     println!("c ------------------------------------------------------------------------------");
     println!("c nb_conflicts {}"  , solver.nb_conflicts);
@@ -111,10 +701,72 @@ fn print_model(solver: &Solver) {
     println!("{}", model);
 }
 
+fn print_maxsat_model(maxsat: &MaxSat, binary_model: bool) {
+    let mut model = String::from("v ");
+    for (i, &value) in maxsat.model().iter().enumerate() {
+        if binary_model {
+            model.push(if value { '1' } else { '0' });
+        } else {
+            let var_value = if value { i as isize + 1 } else { -(i as isize + 1) };
+            model.push_str(&format!("{} ", var_value));
+        }
+    }
+    if !binary_model { model.push('0'); }
+
+    println!("{}", model);
+}
+
+fn print_pseudo_boolean_model(pb: &PseudoBoolean) {
+    let mut model = String::from("v");
+    for (i, &value) in pb.model().iter().enumerate() {
+        model.push_str(&format!(" {}x{}", if value { "" } else { "-" }, i+1));
+    }
+
+    println!("{}", model);
+}
+
+fn print_circuit_model(circuit: &Circuit) {
+    let mut model = String::from("v");
+    for (name, value) in circuit.model() {
+        model.push_str(&format!(" {}{}", if value { "" } else { "-" }, name));
+    }
+
+    println!("{}", model);
+}
+
 /// This function parses the command line arguments of the program and returns an object
 /// representing these arguments.
 fn arguments() -> CliArgs {
-    let mut options= CliArgs { filename: None, print_model: false, drat: false };
+    let mut options= CliArgs {
+        filename: None,
+        print_model: false,
+        drat: false,
+        chrono: false,
+        chrono_threshold: 100,
+        reuse_trail: false,
+        shrink: false,
+        binary_minimization: true,
+        restarts: String::from("inout"),
+        restart_blocking: true,
+        threads: 1,
+        cube_depth: 0,
+        icnf: None,
+        pb_encoding: String::from("bdd"),
+        gauss: false,
+        formula: false,
+        transformation: String::from("pg"),
+        bmc_depth: 0,
+        enumerate: None,
+        enumeration: String::from("blocking"),
+        count: false,
+        approx_count: false,
+        epsilon: 0.8,
+        delta: 0.2,
+        seed: 1,
+        mus: false,
+        mus_format: String::from("dimacs"),
+        mcs: None
+    };
 
     // This is where we actually handle the command line arguments with Argparse (like we'd do in
     // python3). Note, this scope is necessary since it allows us to close the borrow scope for
@@ -127,7 +779,7 @@ fn arguments() -> CliArgs {
         parser.refer(&mut options.filename)
             .add_argument("input_file",
                             StoreOption,
-                            "The input file. This should be a dimacs cnf file which may be \
+                            "The input file. This should be a dimacs cnf (or icnf, wcnf, gcnf, opb or aiger) file which may be \
                                    compressed with bz2 (bzip2) , gz (gzip) or xz (lzma)");
 
         parser.refer(&mut options.print_model)
@@ -140,12 +792,200 @@ fn arguments() -> CliArgs {
                         StoreTrue,
                         "Prints a proof of unsatisfiability in DRAT format (aka UNSAT certificate).");
 
-        parser.parse_args_or_exit();
+        parser.refer(&mut options.chrono)
+            .add_option(&["-c", "--chrono"],
+                        StoreTrue,
+                        "Enables chronological backtracking when a backjump would undo too many levels.");
+
+        parser.refer(&mut options.chrono_threshold)
+            .add_option(&["--chrono-threshold"],
+                        Store,
+                        "The number of levels a backjump may undo before the solver backtracks \
+                               chronologically (default: 100).");
+
+        parser.refer(&mut options.reuse_trail)
+            .add_option(&["-r", "--reuse-trail"],
+                        StoreTrue,
+                        "Performs partial restarts which keep the part of the trail that would be \
+                               rebuilt identically after a complete restart.");
+
+        parser.refer(&mut options.shrink)
+            .add_option(&["-s", "--shrink"],
+                        StoreTrue,
+                        "Shrinks the learned clauses by replacing the literals of each level with \
+                               the unique implication point of that level.");
+
+        parser.refer(&mut options.binary_minimization)
+            .add_option(&["--no-binary-minimization"],
+                        StoreFalse,
+                        "Disables the minimization of the learned clauses with binary clauses.");
+
+        parser.refer(&mut options.restarts)
+            .add_option(&["--restarts"],
+                        Store,
+                        "The restart strategy: one of inout, luby, glucose or ema (default: inout).");
+
+        parser.refer(&mut options.restart_blocking)
+            .add_option(&["--no-restart-blocking"],
+                        StoreFalse,
+                        "Disables the blocking of the (glucose or ema) restarts when the trail is \
+                               much larger than its recent average.");
+
+        parser.refer(&mut options.threads)
+            .add_option(&["-t", "--threads"],
+                        Store,
+                        "The number of threads. With more than one thread, a portfolio of \
+                               diversified solvers sharing their learned clauses is run (default: 1).");
+
+        parser.refer(&mut options.cube_depth)
+            .add_option(&["--cube-depth"],
+                        Store,
+                        "Splits the problem into cubes of (at most) this many literals with a \
+                               lookahead and solves them with all the threads (default: 0, disabled).");
+
+        parser.refer(&mut options.icnf)
+            .add_option(&["--icnf"],
+                        StoreOption,
+                        "Writes the problem and its cubes to the given file in iCNF format instead \
+                               of solving them (requires --cube-depth).");
+
+        parser.refer(&mut options.pb_encoding)
+            .add_option(&["--pb-encoding"],
+                        Store,
+                        "The encoding of the pseudo-Boolean constraints of an OPB problem: one of \
+                               bdd, adder or sorter (default: bdd).");
+
+        parser.refer(&mut options.gauss)
+            .add_option(&["-g", "--gauss"],
+                        StoreTrue,
+                        "Detects the XOR constraints encoded by the clauses and propagates them (as \
+                               well as those of the `x` lines) with Gauss-Jordan elimination.");
+
+        parser.refer(&mut options.formula)
+            .add_option(&["--formula"],
+                        StoreTrue,
+                        "Reads the input as propositional formulas written in infix syntax (one per \
+                               line) rather than as a DIMACS-like problem.");
+
+        parser.refer(&mut options.transformation)
+            .add_option(&["--transformation"],
+                        Store,
+                        "The transformation of the propositional formulas into clauses: one of \
+                               tseitin or pg (default: pg).");
+
+        parser.refer(&mut options.bmc_depth)
+            .add_option(&["--bmc-depth"],
+                        Store,
+                        "The number of steps the transition relation of an AIGER circuit is \
+                               unrolled for, looking for a reachable bad state (default: 0).");
+
+        parser.refer(&mut options.enumerate)
+            .metavar("N")
+            .add_option(&["-e", "--enumerate"],
+                        StoreConst(Some(0)),
+                        "Enumerates all the models of the problem (projected onto the variables of \
+                               its `c ind` lines, if any), or the first N of them with `--enumerate N`, \
+                               and prints one `v` line per model.")
+            .add_option(&["--max-models"],
+                        StoreOption,
+                        "Enumerates no more than the given number of models (0 means all of them).");
+
+        parser.refer(&mut options.enumeration)
+            .add_option(&["--enumeration"],
+                        Store,
+                        "The strategy of the model enumeration: one of blocking (blocking clauses) or \
+                               decisions (no clause is added) (default: blocking).");
+
+        parser.refer(&mut options.count)
+            .add_option(&["--count"],
+                        StoreTrue,
+                        "Counts the models of the problem (projected onto the variables of its \
+                               `c ind` lines, if any) with arbitrary precision.");
+
+        parser.refer(&mut options.approx_count)
+            .add_option(&["--approx-count"],
+                        StoreTrue,
+                        "Estimates the number of models of the problem (projected onto the variables \
+                               of its `c ind` lines, if any) by hashing them with random XOR constraints.");
+
+        parser.refer(&mut options.epsilon)
+            .add_option(&["--epsilon"],
+                        Store,
+                        "The tolerance of the approximate count: the estimate is within a factor \
+                               1 + epsilon of the actual count (default: 0.8).");
+
+        parser.refer(&mut options.delta)
+            .add_option(&["--delta"],
+                        Store,
+                        "The confidence of the approximate count: the estimate is out of tolerance \
+                               with a probability of at most delta (default: 0.2).");
+
+        parser.refer(&mut options.seed)
+            .add_option(&["--seed"],
+                        Store,
+                        "The seed of the random XOR constraints of the approximate count (default: 1).");
+
+        parser.refer(&mut options.mus)
+            .add_option(&["--mus"],
+                        StoreTrue,
+                        "Extracts a minimal unsatisfiable subset of the clauses of the problem \
+                               when it is unsatisfiable.");
+
+        parser.refer(&mut options.mus_format)
+            .add_option(&["--mus-format"],
+                        Store,
+                        "The output of the MUS: one of dimacs (the clauses of the MUS) or indices \
+                               (their positions in the input, counted from 1) (default: dimacs).");
+
+        parser.refer(&mut options.mcs)
+            .add_option(&["--mcs"],
+                        StoreConst(Some(0)),
+                        "Enumerates all the minimal correction subsets of the clauses (or groups) of \
+                               the problem and prints one `v` line per MCS.")
+            .add_option(&["--max-mcses"],
+                        StoreOption,
+                        "Enumerates no more than the given number of MCSes (0 means all of them).");
+
+        let args = with_optional_values(std::env::args().collect());
+        if let Err(code) = parser.parse(args, &mut std::io::stdout(), &mut std::io::stderr()) {
+            std::process::exit(code);
+        }
     }
 
     return options;
 }
 
+/// This function rewrites the optional value of `--enumerate [N]` (which argparse does not
+/// support) as `--enumerate --max-models N`. Hence, a number which follows `-e` or `--enumerate`
+/// is the maximum number of models, not the input file.
+fn with_optional_values(args: Vec<String>) -> Vec<String> {
+    let mut rewritten = Vec::with_capacity(args.len() + 1);
+    let mut args      = args.into_iter().peekable();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            rewritten.push(arg);
+            rewritten.extend(args);
+            break;
+        }
+        if let Some(limit) = arg.strip_prefix("--enumerate=") {
+            rewritten.push(String::from("--enumerate"));
+            rewritten.push(String::from("--max-models"));
+            rewritten.push(String::from(limit));
+            continue;
+        }
+
+        let enumerate = arg == "-e" || arg == "--enumerate";
+        rewritten.push(arg);
+        let limited = args.peek()
+            .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()));
+        if enumerate && limited {
+            rewritten.push(String::from("--max-models"));
+            rewritten.extend(args.next());
+        }
+    }
+    rewritten
+}
+
 /// This function returns the BufRead reader which can be used to iterate over all the lines of the
 /// DIMACS CNF input. If the cli-args did not provide any input file, then stdin is used to read the
 /// problem. Otherwise, the input file is read (and potentially unpacked).
@@ -0,0 +1,356 @@
+use core::*;
+use solving::*;
+
+/// The weight of a soft clause (the cost one has to pay when it is falsified)
+pub type Weight = u64;
+
+// -----------------------------------------------------------------------------------------------
+/// # MaxSat
+/// A weighted (partial) MaxSAT solver. Given a set of hard clauses and a set of weighted soft
+/// clauses, it finds an assignment which satisfies all the hard clauses and minimizes the total
+/// weight of the falsified soft clauses.
+///
+/// It implements the core-guided PM-RES algorithm on top of the incremental (assumption based)
+/// interface of the `Solver`. Every soft clause is represented by a literal which is assumed
+/// to hold. Whenever the solver fails under these assumptions, the failed assumptions form an
+/// unsatisfiable core: the cost of the solution is bound to increase by (at least) the minimum
+/// weight of the core, and the core is relaxed by MaxSAT resolution. The first assignment found
+/// is an optimal one.
+///
+/// See `Maximum Satisfiability Using Core-Guided MaxSAT Resolution` -- Narodytska, Bacchus (AAAI
+/// 2014).
+// -----------------------------------------------------------------------------------------------
+#[derive(Debug)]
+pub struct MaxSat {
+    /// The solver used to extract the cores and to find the optimal assignment
+    pub solver  : Solver,
+    /// The number of variables of the problem (the solver comprises auxiliary variables too)
+    nb_vars     : usize,
+    /// Whether the hard clauses are (trivially) unsatisfiable
+    is_unsat    : bool,
+    /// The number of soft clauses which were associated with a literal already
+    nb_relaxed  : usize,
+    /// The soft clauses and their weights
+    softs       : Vec<(Weight, Vec<Literal>)>,
+    /// The literals assumed to hold (one per soft clause) and their remaining weights
+    objective   : Vec<(Literal, Weight)>,
+    /// The cost which is paid regardless of the assignment (the lower bound on the optimum)
+    lower_bound : Weight,
+    /// The value of the variables of the problem in the best assignment found so far
+    model       : Vec<bool>
+}
+
+impl MaxSat {
+    /// Creates a MaxSAT problem with `nb_vars` variables, no hard and no soft clause. The
+    /// variables occurring in the clauses added afterwards are added as needed.
+    pub fn new(nb_vars: usize) -> MaxSat {
+        MaxSat {
+            solver     : Solver::new(nb_vars),
+            nb_vars,
+            is_unsat   : false,
+            nb_relaxed : 0,
+            softs      : vec![],
+            objective  : vec![],
+            lower_bound: 0,
+            model      : vec![]
+        }
+    }
+
+    /// Returns the number of variables of the problem (auxiliary variables excluded)
+    #[inline]
+    pub fn nb_vars(&self) -> usize { self.nb_vars }
+
+    /// Adds a hard clause (which must be satisfied) to the problem
+    pub fn add_hard_clause(&mut self, clause: &mut Vec<iint>) {
+        self.declare_vars(clause);
+        if self.solver.add_problem_clause(clause).is_err() {
+            self.is_unsat = true;
+        }
+    }
+
+    /// Adds a soft clause to the problem: falsifying it costs `weight`.
+    pub fn add_soft_clause(&mut self, weight: Weight, clause: &[iint]) {
+        self.declare_vars(clause);
+        if weight > 0 {
+            self.softs.push((weight, clause.iter().map(|&l| lit(l)).collect()));
+        }
+    }
+
+    /// Adds the variables of the given `literals` the problem does not know of yet.
+    fn declare_vars(&mut self, literals: &[iint]) {
+        let max = literals.iter().map(|l| l.unsigned_abs() as usize).max().unwrap_or(0);
+        while self.nb_vars < max {
+            self.solver.new_var();
+            self.nb_vars += 1;
+        }
+    }
+
+    /// Finds an optimal assignment of the problem.
+    ///
+    /// # Return Value
+    /// The cost of an optimal assignment (which is then available through `model`) or None when
+    /// the hard clauses are unsatisfiable.
+    pub fn solve(&mut self) -> Option<Weight> {
+        self.relax_soft_clauses();
+        if self.is_unsat { return None; }
+
+        // the soft clauses are stratified: the heaviest ones are considered first
+        let mut stratum = self.next_stratum(Weight::MAX);
+        loop {
+            let assumptions: Vec<Literal> = self.objective.iter()
+                .filter(|&&(_, w)| w > 0 && w >= stratum)
+                .map(|&(l, _)| l)
+                .collect();
+
+            if self.solver.solve_with_assumptions(&assumptions) {
+                self.model = (1..=self.nb_vars)
+                    .map(|v| self.solver.get_value(lit(v as iint)) == Bool::True)
+                    .collect();
+
+                // all the soft clauses were considered or the lower bound is reached
+                let next = self.next_stratum(stratum);
+                if next == 0 || self.cost() == self.lower_bound {
+                    debug_assert_eq!(self.cost(), self.lower_bound);
+                    return Some(self.lower_bound);
+                }
+                stratum = next;
+                continue;
+            }
+
+            let core = self.solver.failed_assumptions().to_vec();
+            if core.is_empty() { return None; }
+
+            self.relax_core(&core);
+        }
+    }
+
+    /// Returns the heaviest (remaining) weight of a soft constraint which is strictly lighter than
+    /// `stratum`, or 0 when there is no such soft constraint.
+    fn next_stratum(&self, stratum: Weight) -> Weight {
+        self.objective.iter()
+            .map(|&(_, w)| w)
+            .filter(|&w| w < stratum)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the value of each variable of the problem in the optimal assignment (the value of
+    /// variable `v` is at index `v-1`). This is only meaningful after `solve` found an optimum.
+    #[inline]
+    pub fn model(&self) -> &[bool] { &self.model }
+
+    /// Returns the total weight of the soft clauses falsified by the model
+    pub fn cost(&self) -> Weight {
+        self.softs.iter()
+            .filter(|(_, clause)| !clause.iter().any(|&l| self.model_value(l)))
+            .map(|&(w, _)| w)
+            .sum()
+    }
+
+    /// Tells whether the literal `l` holds in the model
+    #[inline]
+    fn model_value(&self, l: Literal) -> bool {
+        let value = self.model[usize::from(l.var()) - 1];
+        match l.sign() {
+            Sign::Positive =>  value,
+            Sign::Negative => !value
+        }
+    }
+
+    /// Associates one literal to each soft clause that was added since the last call. This literal
+    /// holds only if the clause is satisfied. (The empty soft clauses are always falsified, their
+    /// weight is paid right away).
+    fn relax_soft_clauses(&mut self) {
+        for i in self.nb_relaxed..self.softs.len() {
+            let (weight, ref clause) = self.softs[i];
+
+            let literal = match clause.len() {
+                0 => { self.lower_bound += weight; continue; },
+                1 => clause[0],
+                _ => {
+                    // r -> clause
+                    let relax = Literal::from_var(self.solver.new_var(), Sign::Positive);
+                    let mut hard: Vec<iint> = clause.iter().map(|l| l.to_isize() as iint).collect();
+                    hard.push(-(relax.to_isize() as iint));
+                    if self.solver.add_problem_clause(&mut hard).is_err() {
+                        self.is_unsat = true;
+                    }
+                    relax
+                }
+            };
+
+            self.add_objective(literal, weight);
+        }
+        self.nb_relaxed = self.softs.len();
+    }
+
+    /// Assumes `literal` with the given `weight` (the weights of a literal assumed several times
+    /// are summed up).
+    fn add_objective(&mut self, literal: Literal, weight: Weight) {
+        match self.objective.iter_mut().find(|&&mut (l, _)| l == literal) {
+            Some(&mut (_, ref mut w)) => *w += weight,
+            None                      => self.objective.push((literal, weight))
+        }
+    }
+
+    /// Relaxes the given core with MaxSAT resolution. The minimum weight of the core is added to
+    /// the lower bound and subtracted from the weight of each literal of the core. The new soft
+    /// constraints `s_i = l_{i+1} \/ (l_1 /\ ... /\ l_i)` (i in 1..k-1) ensure that every literal
+    /// of the core that is falsified -- beyond the first one -- still costs the minimum weight.
+    fn relax_core(&mut self, core: &[Literal]) {
+        let min_weight = core.iter()
+            .map(|&l| self.weight_of(l))
+            .min()
+            .unwrap();
+
+        self.lower_bound += min_weight;
+        for &l in core.iter() {
+            for &mut (o, ref mut w) in self.objective.iter_mut() {
+                if o == l { *w -= min_weight; }
+            }
+        }
+
+        // d_i -> l_1 /\ ... /\ l_i
+        let mut conj = core[0];
+        for i in 1..core.len() {
+            // s_i -> l_{i+1} \/ d_i
+            let soft = self.fresh_literal();
+            self.add_hard(&[!soft, core[i], conj]);
+            self.add_objective(soft, min_weight);
+
+            if i + 1 < core.len() {
+                let next = self.fresh_literal();
+                self.add_hard(&[!next, conj]);
+                self.add_hard(&[!next, core[i]]);
+                conj = next;
+            }
+        }
+    }
+
+    /// Returns the remaining weight of the assumed literal `l`
+    fn weight_of(&self, l: Literal) -> Weight {
+        self.objective.iter().find(|&&(o, _)| o == l).map_or(0, |&(_, w)| w)
+    }
+
+    /// Creates a new auxiliary variable and returns its positive literal
+    fn fresh_literal(&mut self) -> Literal {
+        Literal::from_var(self.solver.new_var(), Sign::Positive)
+    }
+
+    /// Adds the given auxiliary (hard) clause to the solver
+    fn add_hard(&mut self, clause: &[Literal]) {
+        if self.solver.add_clause_literals(clause).is_err() {
+            self.is_unsat = true;
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Unit Tests
+// -----------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_soft_clause_means_no_cost() {
+        let mut tested = MaxSat::new(2);
+        tested.add_hard_clause(&mut vec![1, 2]);
+        tested.add_hard_clause(&mut vec![-1]);
+
+        assert_eq!(tested.solve(), Some(0));
+        assert_eq!(tested.model(), &[false, true]);
+    }
+
+    #[test]
+    fn unsat_hard_clauses_have_no_optimum() {
+        let mut tested = MaxSat::new(1);
+        tested.add_hard_clause(&mut vec![1]);
+        tested.add_hard_clause(&mut vec![-1]);
+        tested.add_soft_clause(1, &[1]);
+
+        assert_eq!(tested.solve(), None);
+    }
+
+    #[test]
+    fn conflicting_soft_clauses_cost_the_lightest_weight() {
+        let mut tested = MaxSat::new(2);
+        tested.add_soft_clause(3, &[1]);
+        tested.add_soft_clause(5, &[-1, 2]);
+        tested.add_soft_clause(4, &[-2]);
+
+        assert_eq!(tested.solve(), Some(3));
+        assert_eq!(tested.model(), &[false, false]);
+        assert_eq!(tested.cost(), 3);
+    }
+
+    #[test]
+    fn hard_clauses_force_the_lightest_feasible_cost() {
+        let mut tested = MaxSat::new(3);
+        tested.add_hard_clause(&mut vec![1, 2, 3]);
+        tested.add_soft_clause(9, &[-1]);
+        tested.add_soft_clause(7, &[-2]);
+        tested.add_soft_clause(8, &[-3]);
+
+        assert_eq!(tested.solve(), Some(7));
+        assert_eq!(tested.model(), &[false, true, false]);
+    }
+
+    #[test]
+    fn falsifying_several_soft_clauses_of_a_core_costs_each_of_them() {
+        // at least two of x1, x2, x3 must be false
+        let mut tested = MaxSat::new(3);
+        tested.add_hard_clause(&mut vec![-1, -2]);
+        tested.add_hard_clause(&mut vec![-1, -3]);
+        tested.add_hard_clause(&mut vec![-2, -3]);
+        tested.add_soft_clause(1, &[1]);
+        tested.add_soft_clause(1, &[2]);
+        tested.add_soft_clause(1, &[3]);
+
+        assert_eq!(tested.solve(), Some(2));
+        assert_eq!(tested.cost(), 2);
+    }
+
+    #[test]
+    fn empty_soft_clauses_are_always_paid() {
+        let mut tested = MaxSat::new(1);
+        tested.add_soft_clause(4, &[]);
+        tested.add_soft_clause(2, &[1]);
+
+        assert_eq!(tested.solve(), Some(4));
+    }
+
+    #[test]
+    fn the_weights_of_duplicate_soft_clauses_add_up() {
+        let mut tested = MaxSat::new(1);
+        tested.add_soft_clause(2, &[1]);
+        tested.add_soft_clause(2, &[1]);
+        tested.add_soft_clause(3, &[-1]);
+
+        assert_eq!(tested.solve(), Some(3));
+        assert_eq!(tested.model(), &[true]);
+    }
+
+    #[test]
+    fn variables_are_declared_as_they_show_up() {
+        let mut tested = MaxSat::new(0);
+        tested.add_hard_clause(&mut vec![-4, 5]);
+        tested.add_soft_clause(1, &[4]);
+        tested.add_soft_clause(1, &[-5]);
+
+        assert_eq!(tested.nb_vars(), 5);
+        assert_eq!(tested.solve(), Some(1));
+        assert_eq!(tested.model().len(), 5);
+    }
+
+    #[test]
+    fn soft_clauses_can_be_added_after_solving() {
+        let mut tested = MaxSat::new(2);
+        tested.add_soft_clause(1, &[1, 2]);
+        assert_eq!(tested.solve(), Some(0));
+
+        tested.add_soft_clause(2, &[-1]);
+        tested.add_soft_clause(2, &[-2]);
+        assert_eq!(tested.solve(), Some(1));
+    }
+}
@@ -0,0 +1,637 @@
+use std::collections::HashSet;
+
+use core::*;
+use solving::*;
+
+/// What is known of a group during the extraction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    /// The group may or may not belong to the MUS
+    Unknown,
+    /// The group belongs to the MUS (the other groups of the MUS are satisfiable)
+    Necessary,
+    /// The group is left out of the MUS
+    Removed
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Mus
+/// An extractor of minimal unsatisfiable subsets (MUS): given an unsatisfiable set of clauses, it
+/// finds a subset of these clauses which is unsatisfiable as well, but becomes satisfiable as soon
+/// as any of its clauses is left out. The problem may comprise hard constraints too: these are
+/// not part of the subset but they always hold.
+///
+/// More generally, the clauses are partitioned in groups and the MUS is a minimal unsatisfiable
+/// set of groups (a group MUS). Unless stated otherwise, each clause makes a group of its own.
+///
+/// Every group is guarded by a selector literal (its clauses only hold when the selector does)
+/// so that the subsets of groups are checked with the incremental (assumption based) interface
+/// of the `Solver`. The MUS is extracted by deletion: the groups are left out one at a time, and
+///
+/// - when the other groups remain unsatisfiable, only the groups of the final conflict (the
+///   failed assumptions) are kept (clause-set refinement);
+/// - when they become satisfiable, the group is necessary. The model falsifies that group only:
+///   flipping the value of one of the variables of its falsified clauses may satisfy it, and when
+///   the resulting assignment falsifies one other group only, that group is necessary as well
+///   (recursive model rotation).
+///
+/// See `On Improving MUS Extraction Algorithms` -- Marques-Silva, Lynce (SAT 2011).
+///
+/// The same problem may also be used to enumerate its minimal correction subsets (MCS): the sets
+/// of groups whose removal makes the problem satisfiable, none of their subsets doing so. Each
+/// MCS is the complement of a maximal satisfiable subset, which is grown from a model by asking
+/// for one more group at a time (clause D). Every MCS is blocked once found, which modifies the
+/// problem: no MUS can be extracted once MCSes were enumerated.
+///
+/// See `On Computing Minimal Correction Subsets` -- Marques-Silva, Heras, Janota, Previti,
+/// Belov (IJCAI 2013).
+// -----------------------------------------------------------------------------------------------
+#[derive(Debug)]
+pub struct Mus {
+    /// The solver checking the subsets of clauses
+    pub solver : Solver,
+    /// The number of variables of the problem (the solver comprises the selectors too)
+    nb_vars    : usize,
+    /// Whether the hard constraints are (trivially) unsatisfiable
+    is_unsat   : bool,
+    /// Whether some hard constraints are XOR constraints (the model rotation is then disabled)
+    has_xors   : bool,
+    /// The hard clauses (which must be satisfied by a rotated model)
+    hard       : Vec<Vec<Literal>>,
+    /// The clauses the MUS is made of, in the order they were added
+    clauses    : Vec<Vec<Literal>>,
+    /// The group of each clause
+    group_of   : Vec<usize>,
+    /// The clauses of each group
+    groups     : Vec<Vec<usize>>,
+    /// The selector of each group
+    selectors  : Vec<Literal>,
+    /// The number of clauses which were handed over to the solver already
+    nb_guarded : usize,
+    /// The number of searches the extraction took
+    pub nb_solver_calls: usize,
+    /// The number of groups found necessary by model rotation
+    pub nb_rotated     : usize
+}
+
+impl Mus {
+    /// Creates an extractor for a problem having `nb_vars` variables and no constraint. The
+    /// variables occurring in the constraints added afterwards are added as needed.
+    pub fn new(nb_vars: usize) -> Mus {
+        Mus {
+            solver         : Solver::new(nb_vars),
+            nb_vars,
+            is_unsat       : false,
+            has_xors       : false,
+            hard           : vec![],
+            clauses        : vec![],
+            group_of       : vec![],
+            groups         : vec![],
+            selectors      : vec![],
+            nb_guarded     : 0,
+            nb_solver_calls: 0,
+            nb_rotated     : 0
+        }
+    }
+
+    /// Returns the number of variables of the problem (selectors excluded)
+    #[inline]
+    pub fn nb_vars(&self) -> usize { self.nb_vars }
+
+    /// Returns the number of clauses the MUS is extracted from (hard clauses excluded)
+    #[inline]
+    pub fn nb_clauses(&self) -> usize { self.clauses.len() }
+
+    /// Returns the number of groups the MUS is extracted from
+    #[inline]
+    pub fn nb_groups(&self) -> usize { self.groups.len() }
+
+    /// Returns the clauses of the group having the given index (in the order they were added)
+    pub fn group(&self, index: usize) -> Vec<&[Literal]> {
+        self.groups[index].iter().map(|&c| self.clauses[c].as_slice()).collect()
+    }
+
+    /// Adds an empty group to the problem and returns its index (groups are indexed in the order
+    /// they are created).
+    pub fn new_group(&mut self) -> usize {
+        self.groups.push(vec![]);
+        self.groups.len() - 1
+    }
+
+    /// Adds a clause to the given group
+    pub fn add_group_clause(&mut self, group: usize, clause: &[iint]) {
+        self.declare_vars(clause);
+        self.clauses.push(clause.iter().map(|&l| lit(l)).collect());
+        self.group_of.push(group);
+        self.groups[group].push(self.clauses.len() - 1);
+    }
+
+    /// Adds a clause making a group of its own to the problem and returns the index of that group.
+    pub fn add_clause(&mut self, clause: &[iint]) -> usize {
+        let group = self.new_group();
+        self.add_group_clause(group, clause);
+        group
+    }
+
+    /// Adds a hard clause (which always holds and is not part of the MUS) to the problem
+    pub fn add_hard_clause(&mut self, clause: &mut Vec<iint>) {
+        self.declare_vars(clause);
+        self.hard.push(clause.iter().map(|&l| lit(l)).collect());
+        if self.solver.add_problem_clause(clause).is_err() {
+            self.is_unsat = true;
+        }
+    }
+
+    /// Adds a hard XOR constraint (see `Solver::add_xor`) to the problem
+    pub fn add_hard_xor(&mut self, literals: &[iint]) {
+        self.declare_vars(literals);
+        self.has_xors = true;
+        self.solver.add_xor(literals);
+    }
+
+    /// Adds the variables of the given `literals` the problem does not know of yet.
+    fn declare_vars(&mut self, literals: &[iint]) {
+        let max = literals.iter().map(|l| l.unsigned_abs() as usize).max().unwrap_or(0);
+        while self.nb_vars < max {
+            self.solver.new_var();
+            self.nb_vars += 1;
+        }
+    }
+
+    /// Extracts a minimal unsatisfiable subset of the groups.
+    ///
+    /// # Return Value
+    /// The indices of the groups of the MUS (in increasing order), or None when the problem is
+    /// satisfiable. The MUS is empty when the hard constraints are unsatisfiable by themselves.
+    pub fn extract(&mut self) -> Option<Vec<usize>> {
+        self.select_groups();
+        if self.is_unsat { return Some(vec![]); }
+
+        let n = self.groups.len();
+        let mut status = vec![Status::Unknown; n];
+
+        let all = self.selectors.clone();
+        self.nb_solver_calls += 1;
+        if self.solver.solve_with_assumptions(&all) { return None; }
+        self.refine(&mut status);
+
+        let occurrences = self.occurrences();
+        while let Some(c) = (0..n).find(|&c| status[c] == Status::Unknown) {
+            // the group is left out, all the other ones which might belong to the MUS are kept
+            let mut assumptions: Vec<Literal> = (0..n)
+                .filter(|&d| d != c && status[d] == Status::Unknown)
+                .map(|d| self.selectors[d])
+                .collect();
+            assumptions.push(!self.selectors[c]);
+
+            self.nb_solver_calls += 1;
+            if self.solver.solve_with_assumptions(&assumptions) {
+                self.keep(c, &mut status);
+                if !self.has_xors {
+                    let model = self.model();
+                    self.rotate(c, model, &occurrences, &mut status);
+                }
+            } else {
+                self.refine(&mut status);
+            }
+        }
+
+        Some((0..n).filter(|&c| status[c] == Status::Necessary).collect())
+    }
+
+    /// Enumerates the minimal correction subsets of the groups, but no more than `limit` of them
+    /// (when a limit is given). Every MCS is handed over to `on_mcs` as the indices of its groups
+    /// (in increasing order). The only MCS of a satisfiable problem is empty, and there is none
+    /// when the hard constraints are unsatisfiable.
+    ///
+    /// # Return Value
+    /// The number of MCSes that were enumerated.
+    pub fn enumerate_mcses<F>(&mut self, limit: Option<usize>, mut on_mcs: F) -> usize
+        where F: FnMut(&[usize]) {
+
+        if limit == Some(0) { return 0; }
+        self.select_groups();
+        if self.is_unsat { return 0; }
+
+        let mut nb_mcses = 0;
+        loop {
+            self.nb_solver_calls += 1;
+            if !self.solver.solve() { break; }
+
+            let mcs = self.correction_set();
+            on_mcs(&mcs);
+            nb_mcses += 1;
+            if mcs.is_empty() || Some(nb_mcses) == limit { break; }
+
+            // the next MCSes must satisfy one of the groups of this one (or they would include it)
+            let mut blocking: Vec<iint> = mcs.iter().map(|&g| self.selectors[g].to_isize() as iint).collect();
+            if self.solver.add_problem_clause(&mut blocking).is_err() { break; }
+        }
+        nb_mcses
+    }
+
+    /// Returns the MCS that is the complement of a maximal satisfiable subset including the
+    /// groups satisfied by the current model of the solver. As long as some groups are falsified,
+    /// the satisfied ones are enforced (assumed) and one of the falsified ones is required: this
+    /// is clause D, which only holds under an activation literal. When that fails, the falsified
+    /// groups make an MCS.
+    fn correction_set(&mut self) -> Vec<usize> {
+        let n = self.groups.len();
+        let mut satisfied   = vec![false; n];
+        let mut activations = vec![];
+        let mcs = loop {
+            let model = self.model();
+            for (g, satisfied) in satisfied.iter_mut().enumerate() {
+                *satisfied = *satisfied || self.satisfies(&model, g);
+            }
+            let falsified: Vec<usize> = (0..n).filter(|&g| !satisfied[g]).collect();
+            if falsified.is_empty() { break falsified; }
+
+            let active = Literal::positive(self.solver.new_var());
+            activations.push(active);
+            let mut clause_d: Vec<iint> = falsified.iter().map(|&g| self.selectors[g].to_isize() as iint).collect();
+            clause_d.push((!active).to_isize() as iint);
+            let _ = self.solver.add_problem_clause(&mut clause_d);
+
+            let mut assumptions: Vec<Literal> = (0..n).filter(|&g| satisfied[g]).map(|g| self.selectors[g]).collect();
+            assumptions.push(active);
+            self.nb_solver_calls += 1;
+            if !self.solver.solve_with_assumptions(&assumptions) { break falsified; }
+        };
+
+        // the clauses D are disabled for good (the model had to be read beforehand)
+        for active in activations {
+            let _ = self.solver.add_problem_clause(&mut vec![(!active).to_isize() as iint]);
+        }
+        mcs
+    }
+
+    /// Gives a selector literal to each group created since the last call, and guards each clause
+    /// added since then with the selector `s` of its group: `s -> clause` is added to the solver.
+    fn select_groups(&mut self) {
+        while self.selectors.len() < self.groups.len() {
+            let selector = Literal::positive(self.solver.new_var());
+            self.selectors.push(selector);
+        }
+
+        for i in self.nb_guarded..self.clauses.len() {
+            let selector = self.selectors[self.group_of[i]];
+            let mut guarded: Vec<iint> = self.clauses[i].iter().map(|l| l.to_isize() as iint).collect();
+            guarded.push((!selector).to_isize() as iint);
+            if self.solver.add_problem_clause(&mut guarded).is_err() {
+                self.is_unsat = true;
+            }
+        }
+        self.nb_guarded = self.clauses.len();
+    }
+
+    /// Leaves out all the groups (which might belong to the MUS) that do not belong to the final
+    /// conflict of the last (unsuccessful) search: they are disabled for good.
+    fn refine(&mut self, status: &mut [Status]) {
+        let core: HashSet<Literal> = self.solver.failed_assumptions().iter().cloned().collect();
+
+        for (c, status) in status.iter_mut().enumerate() {
+            if *status == Status::Unknown && !core.contains(&self.selectors[c]) {
+                *status = Status::Removed;
+                let mut unit = vec![(!self.selectors[c]).to_isize() as iint];
+                let _ = self.solver.add_problem_clause(&mut unit);
+            }
+        }
+    }
+
+    /// Marks the group `c` as necessary: it is enforced for good.
+    fn keep(&mut self, c: usize, status: &mut [Status]) {
+        status[c] = Status::Necessary;
+        let mut unit = vec![self.selectors[c].to_isize() as iint];
+        let _ = self.solver.add_problem_clause(&mut unit);
+    }
+
+    /// Performs the recursive model rotation from the given `model`, which satisfies all the hard
+    /// clauses and all the groups which might belong to the MUS but `c`.
+    fn rotate(&mut self, c: usize, model: Vec<bool>, occurrences: &[Vec<usize>], status: &mut [Status]) {
+        let n = self.clauses.len();
+        let mut pending = vec![(c, model)];
+        while let Some((c, model)) = pending.pop() {
+            // the variables of the clauses of the group which are falsified by the model
+            let mut vars: Vec<usize> = self.groups[c].iter()
+                .filter(|&&i| self.is_falsified(&self.clauses[i], &model))
+                .flat_map(|&i| self.clauses[i].iter().map(|l| usize::from(l.var())))
+                .collect();
+            vars.sort_unstable();
+            vars.dedup();
+
+            for v in vars {
+                let mut rotated = model.clone();
+                rotated[v - 1] = !rotated[v - 1];
+                if self.groups[c].iter().any(|&i| self.is_falsified(&self.clauses[i], &rotated)) {
+                    continue;
+                }
+
+                // only the clauses of the flipped variable may be falsified by the rotation
+                // (hard clauses have the indices n and beyond)
+                let mut falsified = None;
+                let mut acceptable = true;
+                for &i in occurrences[v].iter() {
+                    let clause = if i < n { &self.clauses[i] } else { &self.hard[i - n] };
+                    if i < n && status[self.group_of[i]] == Status::Removed { continue; }
+                    if !self.is_falsified(clause, &rotated) { continue; }
+
+                    if i >= n || falsified.is_some_and(|d| d != self.group_of[i]) {
+                        acceptable = false;
+                        break;
+                    }
+                    falsified = Some(self.group_of[i]);
+                }
+
+                if let (true, Some(d)) = (acceptable, falsified) {
+                    if status[d] == Status::Unknown {
+                        self.nb_rotated += 1;
+                        self.keep(d, status);
+                        pending.push((d, rotated));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the indices of the clauses (the hard ones come after the others) in which each
+    /// variable occurs
+    fn occurrences(&self) -> Vec<Vec<usize>> {
+        let mut occurrences = vec![vec![]; 1 + self.nb_vars];
+        for (i, clause) in self.clauses.iter().chain(self.hard.iter()).enumerate() {
+            for l in clause.iter() {
+                let v = usize::from(l.var());
+                if occurrences[v].last() != Some(&i) {
+                    occurrences[v].push(i);
+                }
+            }
+        }
+        occurrences
+    }
+
+    /// Returns the values of the variables of the problem in the current model of the solver
+    fn model(&self) -> Vec<bool> {
+        (1..=self.nb_vars).map(|v| self.solver.get_value(lit(v as iint)) == Bool::True).collect()
+    }
+
+    /// Tells whether all the clauses of the group `g` are satisfied by the given `model`
+    fn satisfies(&self, model: &[bool], g: usize) -> bool {
+        self.groups[g].iter().all(|&i| !self.is_falsified(&self.clauses[i], model))
+    }
+
+    /// Tells whether the given clause is falsified by the given `model`
+    #[inline]
+    fn is_falsified(&self, clause: &[Literal], model: &[bool]) -> bool {
+        !clause.iter().any(|&l| Mus::value(model, l))
+    }
+
+    /// Tells whether the literal `l` holds in the given `model`
+    #[inline]
+    fn value(model: &[bool], l: Literal) -> bool {
+        let value = model[usize::from(l.var()) - 1];
+        match l.sign() {
+            Sign::Positive =>  value,
+            Sign::Negative => !value
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Unit Tests
+// -----------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fixtures::mus_problem as problem;
+
+    /// Tells whether the given subset of groups is satisfiable
+    fn satisfiable(mus: &Mus, subset: &[usize]) -> bool {
+        let mut solver = Solver::new(mus.nb_vars());
+        for &c in subset.iter() {
+            for clause in mus.group(c) {
+                let mut clause: Vec<iint> = clause.iter().map(|l| l.to_isize() as iint).collect();
+                let _ = solver.add_problem_clause(&mut clause);
+            }
+        }
+        solver.solve()
+    }
+
+    /// Asserts that the given subset of groups is a MUS
+    fn assert_minimal_unsat(mus: &Mus, subset: &[usize]) {
+        assert!(!satisfiable(mus, subset), "{:?} is satisfiable", subset);
+        for &c in subset.iter() {
+            let rest: Vec<usize> = subset.iter().cloned().filter(|&d| d != c).collect();
+            assert!(satisfiable(mus, &rest), "{:?} is not minimal", subset);
+        }
+    }
+
+    /// Asserts that the given set of groups is an MCS (of a problem having no hard constraint)
+    fn assert_minimal_correction(mus: &Mus, mcs: &[usize]) {
+        let rest: Vec<usize> = (0..mus.nb_groups()).filter(|g| !mcs.contains(g)).collect();
+        assert!(satisfiable(mus, &rest), "{:?} does not correct the problem", mcs);
+        for &g in mcs.iter() {
+            let mut extended = rest.clone();
+            extended.push(g);
+            assert!(!satisfiable(mus, &extended), "{:?} is not minimal", mcs);
+        }
+    }
+
+    /// Returns all the MCSes of the given problem, sorted
+    fn all_mcses(mus: &mut Mus) -> Vec<Vec<usize>> {
+        let mut mcses = vec![];
+        let nb_mcses = mus.enumerate_mcses(None, |mcs| mcses.push(mcs.to_vec()));
+        assert_eq!(nb_mcses, mcses.len());
+        mcses.sort();
+        mcses
+    }
+
+    #[test]
+    fn a_satisfiable_problem_has_no_mus() {
+        let mut mus = problem(3, &[&[1, 2], &[-1, 3], &[-3]]);
+        assert_eq!(mus.extract(), None);
+    }
+
+    #[test]
+    fn the_irrelevant_clauses_are_left_out() {
+        let mut mus = problem(4, &[&[3], &[1], &[-1, 2], &[1, 2, 3], &[-3, 4], &[-2]]);
+        assert_eq!(mus.extract(), Some(vec![1, 2, 5]));
+    }
+
+    #[test]
+    fn the_mus_is_minimal() {
+        // the pigeon hole problem with 4 pigeons and 3 holes (pigeon p is in hole h: 3p + h - 3)
+        let mut clauses: Vec<Vec<iint>> = vec![];
+        for h in 1..=3 {
+            for p in 1..=4 {
+                for q in p + 1..=4 {
+                    clauses.push(vec![-(3 * p + h - 3), -(3 * q + h - 3)]);
+                }
+            }
+        }
+        for p in 1..=4 {
+            clauses.push((1..=3).map(|h| 3 * p + h - 3).collect());
+        }
+        // plus some noise
+        clauses.push(vec![1, -5, 9]);
+        clauses.push(vec![-2, 12]);
+        clauses.push(vec![13, 14]);
+
+        let clauses: Vec<&[iint]> = clauses.iter().map(|c| c.as_slice()).collect();
+        let mut mus = problem(14, &clauses);
+        let subset = mus.extract().unwrap();
+        assert_minimal_unsat(&mus, &subset);
+    }
+
+    #[test]
+    fn model_rotation_finds_the_necessary_clauses() {
+        // a chain of implications: every clause is necessary
+        let mut clauses: Vec<Vec<iint>> = vec![vec![1]];
+        for v in 1..10 {
+            clauses.push(vec![-v, v + 1]);
+        }
+        clauses.push(vec![-10]);
+
+        let clauses: Vec<&[iint]> = clauses.iter().map(|c| c.as_slice()).collect();
+        let mut mus = problem(10, &clauses);
+        assert_eq!(mus.extract(), Some((0..11).collect()));
+        assert!(mus.nb_rotated > 0);
+        assert!(mus.nb_solver_calls < 11);
+    }
+
+    #[test]
+    fn the_hard_clauses_are_not_part_of_the_mus() {
+        let mut mus = problem(2, &[&[2], &[1], &[-2, 1]]);
+        mus.add_hard_clause(&mut vec![-1]);
+        assert_eq!(mus.extract(), Some(vec![1]));
+
+        let mut mus = problem(2, &[&[1, 2]]);
+        mus.add_hard_clause(&mut vec![-1]);
+        mus.add_hard_clause(&mut vec![1]);
+        assert_eq!(mus.extract(), Some(vec![]));
+
+        let mut mus = problem(2, &[&[1, 2], &[-1]]);
+        mus.add_hard_xor(&[-1, 2]);
+        assert_eq!(mus.extract(), Some(vec![0, 1]));
+    }
+
+    #[test]
+    fn the_mus_is_made_of_groups() {
+        let mut mus = Mus::new(5);
+        let groups: Vec<usize> = (0..4).map(|_| mus.new_group()).collect();
+        // the clauses of a group are not necessarily consecutive
+        mus.add_group_clause(groups[0], &[1]);
+        mus.add_group_clause(groups[1], &[-1, 2]);
+        mus.add_group_clause(groups[0], &[3]);
+        mus.add_group_clause(groups[2], &[-3, -2]);
+        mus.add_group_clause(groups[2], &[4]);
+        mus.add_group_clause(groups[3], &[-4, 5]);
+        mus.add_hard_clause(&mut vec![-5, 3]);
+
+        // -3 or -2 is the only clause which can be falsified: it needs 3 and 1 (hence 2)
+        let subset = mus.extract().unwrap();
+        assert_eq!(subset, vec![0, 1, 2]);
+        assert_eq!(mus.group(0), vec![&[lit(1)][..], &[lit(3)][..]]);
+        assert_minimal_unsat(&mus, &subset);
+    }
+
+    #[test]
+    fn model_rotation_finds_the_necessary_groups() {
+        // a chain of implications split in groups of two clauses: every group is necessary
+        let mut mus = Mus::new(20);
+        let first = mus.new_group();
+        mus.add_group_clause(first, &[1]);
+        for v in 1..20 {
+            if v % 2 == 1 { mus.new_group(); }
+            let group = mus.nb_groups() - 1;
+            mus.add_group_clause(group, &[-v, v + 1]);
+        }
+        mus.add_clause(&[-20]);
+
+        let n = mus.nb_groups();
+        let subset = mus.extract().unwrap();
+        assert_eq!(subset, (0..n).collect::<Vec<usize>>());
+        assert!(mus.nb_rotated > 0);
+    }
+
+    #[test]
+    fn an_empty_clause_is_a_mus_on_its_own() {
+        let mut mus = problem(2, &[&[1, 2], &[], &[-1]]);
+        assert_eq!(mus.extract(), Some(vec![1]));
+    }
+
+    #[test]
+    fn enumerate_mcses_finds_all_the_minimal_correction_subsets() {
+        let mut mus = problem(2, &[&[1], &[2], &[-1, -2], &[-1]]);
+        assert_eq!(all_mcses(&mut mus), vec![vec![0], vec![1, 3], vec![2, 3]]);
+    }
+
+    #[test]
+    fn the_only_mcs_of_a_satisfiable_problem_is_empty() {
+        let mut mus = problem(3, &[&[1, 2], &[-1, 3], &[-3]]);
+        assert_eq!(all_mcses(&mut mus), vec![Vec::<usize>::new()]);
+    }
+
+    #[test]
+    fn there_is_no_mcs_when_the_hard_clauses_are_unsatisfiable() {
+        let mut mus = problem(1, &[&[1]]);
+        mus.add_hard_clause(&mut vec![-1]);
+        mus.add_hard_clause(&mut vec![1]);
+        assert_eq!(all_mcses(&mut mus), Vec::<Vec<usize>>::new());
+    }
+
+    #[test]
+    fn enumerate_mcses_stops_at_the_limit() {
+        let mut mus = problem(2, &[&[1], &[-1], &[2], &[-2]]);
+        let mut mcses = vec![];
+        assert_eq!(mus.enumerate_mcses(Some(3), |mcs| mcses.push(mcs.to_vec())), 3);
+        assert_eq!(mcses.len(), 3);
+        assert_eq!(mus.enumerate_mcses(Some(0), |_| panic!("no MCS expected")), 0);
+    }
+
+    #[test]
+    fn the_mcses_respect_the_hard_clauses() {
+        let mut mus = problem(2, &[&[1], &[2], &[-1, -2]]);
+        // the third clause can no longer be corrected
+        mus.add_hard_clause(&mut vec![-1, -2]);
+        assert_eq!(all_mcses(&mut mus), vec![vec![0], vec![1]]);
+    }
+
+    #[test]
+    fn the_mcses_are_made_of_groups() {
+        let mut mus = Mus::new(3);
+        let groups: Vec<usize> = (0..3).map(|_| mus.new_group()).collect();
+        mus.add_group_clause(groups[0], &[1]);
+        mus.add_group_clause(groups[0], &[2]);
+        mus.add_group_clause(groups[1], &[-1, -2]);
+        mus.add_group_clause(groups[2], &[-2, 3]);
+        mus.add_group_clause(groups[2], &[-3]);
+        assert_eq!(all_mcses(&mut mus), vec![vec![0], vec![1, 2]]);
+    }
+
+    #[test]
+    fn every_mcs_of_the_pigeon_hole_problem_is_minimal() {
+        // the pigeon hole problem with 3 pigeons and 2 holes (pigeon p is in hole h: 2p + h - 2)
+        let mut clauses: Vec<Vec<iint>> = vec![];
+        for h in 1..=2 {
+            for p in 1..=3 {
+                for q in p + 1..=3 {
+                    clauses.push(vec![-(2 * p + h - 2), -(2 * q + h - 2)]);
+                }
+            }
+        }
+        for p in 1..=3 {
+            clauses.push((1..=2).map(|h| 2 * p + h - 2).collect());
+        }
+        let clauses: Vec<&[iint]> = clauses.iter().map(|c| c.as_slice()).collect();
+
+        let mut mus = problem(6, &clauses);
+        let mcses = all_mcses(&mut mus);
+        // leaving out any one pigeon makes the problem satisfiable
+        for p in 0..3 {
+            assert!(mcses.contains(&vec![6 + p]));
+        }
+        for mcs in mcses.iter() {
+            assert_minimal_correction(&mus, mcs);
+        }
+    }
+}
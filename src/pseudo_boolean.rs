@@ -0,0 +1,340 @@
+use core::*;
+use solving::*;
+use encodings::*;
+
+/// The (integer) coefficient of a literal in a pseudo-Boolean constraint or objective
+pub type Coefficient = i64;
+
+/// The comparison between the left and right hand sides of a pseudo-Boolean constraint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    /// `sum of c_i * l_i >= rhs`
+    AtLeast,
+    /// `sum of c_i * l_i = rhs`
+    Equal,
+    /// `sum of c_i * l_i <= rhs`
+    AtMost
+}
+
+/// A linear constraint `sum of c_i * l_i <relation> rhs` over literals
+#[derive(Debug, Clone)]
+struct Constraint {
+    terms   : Vec<(Coefficient, Literal)>,
+    relation: Relation,
+    rhs     : Coefficient
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # PseudoBoolean
+/// A pseudo-Boolean solver. Given a set of linear constraints over literals having integer
+/// coefficients (and optionally, a linear objective function to minimize), it finds an (optimal)
+/// assignment of the variables satisfying all the constraints.
+///
+/// The constraints are translated into clauses with one of the `PbEncoding`s and handed over to
+/// the `Solver`. The objective is minimized by linear search: every time a solution is found, the
+/// constraint that the objective must be strictly better than that solution is added to the
+/// solver (through its incremental interface) until the problem becomes unsatisfiable. The last
+/// solution found is then an optimal one.
+// -----------------------------------------------------------------------------------------------
+#[derive(Debug)]
+pub struct PseudoBoolean {
+    /// The solver deciding the translated constraints
+    pub solver  : Solver,
+    /// The encoding used to translate the constraints into clauses
+    pub encoding: PbEncoding,
+    /// The number of variables of the problem (the solver comprises auxiliary variables too)
+    nb_vars     : usize,
+    /// The constraints of the problem
+    constraints : Vec<Constraint>,
+    /// The number of constraints which were translated into clauses already
+    nb_encoded  : usize,
+    /// The objective function to minimize (if any)
+    objective   : Option<Vec<(Coefficient, Literal)>>,
+    /// The value of the variables of the problem in the best assignment found so far
+    model       : Vec<bool>
+}
+
+impl PseudoBoolean {
+    /// Creates a pseudo-Boolean problem with `nb_vars` variables, no constraint and no objective.
+    /// The variables occurring in the constraints added afterwards are added as needed.
+    pub fn new(nb_vars: usize) -> PseudoBoolean {
+        PseudoBoolean {
+            solver     : Solver::new(nb_vars),
+            encoding   : PbEncoding::default(),
+            nb_vars,
+            constraints: vec![],
+            nb_encoded : 0,
+            objective  : None,
+            model      : vec![]
+        }
+    }
+
+    /// Returns the number of variables of the problem (auxiliary variables excluded)
+    #[inline]
+    pub fn nb_vars(&self) -> usize { self.nb_vars }
+
+    /// Returns true iff an objective function was set
+    #[inline]
+    pub fn has_objective(&self) -> bool { self.objective.is_some() }
+
+    /// Adds the constraint `sum of c_i * l_i <relation> rhs` to the problem (where `terms` lists
+    /// the couples (c_i, l_i)).
+    ///
+    /// # Note
+    /// The constraint is only translated into clauses when the problem is solved: its literals
+    /// must not mention the auxiliary variables of the encodings.
+    pub fn add_constraint(&mut self, terms: &[(Coefficient, iint)], relation: Relation, rhs: Coefficient) {
+        let terms = self.declare_terms(terms);
+        self.constraints.push(Constraint { terms, relation, rhs });
+    }
+
+    /// Sets the objective function `sum of c_i * l_i` to minimize (where `terms` lists the
+    /// couples (c_i, l_i)).
+    pub fn set_objective(&mut self, terms: &[(Coefficient, iint)]) {
+        let terms = self.declare_terms(terms);
+        self.objective = Some(terms);
+    }
+
+    /// Adds the variables of the given `terms` the problem does not know of yet, and returns the
+    /// terms of the corresponding literals.
+    fn declare_terms(&mut self, terms: &[(Coefficient, iint)]) -> Vec<(Coefficient, Literal)> {
+        let max = terms.iter().map(|&(_, l)| l.unsigned_abs() as usize).max().unwrap_or(0);
+        while self.nb_vars < max {
+            self.solver.new_var();
+            self.nb_vars += 1;
+        }
+        terms.iter().map(|&(c, l)| (c, lit(l))).collect()
+    }
+
+    /// Finds an assignment satisfying all the constraints (regardless of the objective).
+    ///
+    /// # Return Value
+    /// True iff the constraints are satisfiable, in which case the assignment is available
+    /// through `model`.
+    pub fn solve(&mut self) -> bool {
+        self.encode_constraints();
+
+        let satisfiable = self.solver.solve();
+        if satisfiable {
+            self.model = (1..=self.nb_vars)
+                .map(|v| self.solver.get_value(lit(v as iint)) == Bool::True)
+                .collect();
+        }
+        satisfiable
+    }
+
+    /// Finds an assignment satisfying all the constraints which minimizes the objective function.
+    /// The callback `on_solution` is given the value of every (improving) solution found along
+    /// the way.
+    ///
+    /// # Return Value
+    /// The value of an optimal assignment (which is then available through `model`) or None when
+    /// the constraints are unsatisfiable. Without objective, the value of any solution is 0.
+    pub fn minimize<F>(&mut self, mut on_solution: F) -> Option<Coefficient>
+        where F: FnMut(Coefficient) {
+
+        if !self.solve() { return None; }
+
+        let (offset, terms) = match self.objective {
+            None            => return Some(0),
+            Some(ref terms) => normalize(terms)
+        };
+
+        loop {
+            let value = self.value();
+            on_solution(value);
+
+            // the objective cannot be any lower
+            if value == offset { return Some(value); }
+
+            // the next solution must be strictly better than this one
+            let bound = (value - offset - 1) as u64;
+            pb_at_most(&mut self.solver, &terms, bound, self.encoding);
+
+            let model = self.model.clone();
+            if !self.solve() {
+                self.model = model;
+                return Some(value);
+            }
+        }
+    }
+
+    /// Returns the value of each variable of the problem in the last assignment found (the value
+    /// of variable `v` is at index `v-1`). This is only meaningful after a solution was found.
+    #[inline]
+    pub fn model(&self) -> &[bool] { &self.model }
+
+    /// Returns the value of the objective function for the last assignment found (or 0 when there
+    /// is no objective).
+    pub fn value(&self) -> Coefficient {
+        match self.objective {
+            None            => 0,
+            Some(ref terms) => terms.iter()
+                .filter(|&&(_, l)| self.model_value(l))
+                .map(|&(c, _)| c)
+                .sum()
+        }
+    }
+
+    /// Returns the value of the given literal in the last assignment found
+    fn model_value(&self, l: Literal) -> bool {
+        let value = self.model[usize::from(l.var()) - 1];
+        match l.sign() {
+            Sign::Positive => value,
+            Sign::Negative => !value
+        }
+    }
+
+    /// Translates the constraints which were not translated yet into clauses
+    fn encode_constraints(&mut self) {
+        for constraint in self.constraints[self.nb_encoded..].iter() {
+            encode(&mut self.solver, constraint, self.encoding);
+        }
+        self.nb_encoded = self.constraints.len();
+    }
+}
+
+/// Translates the given `constraint` into clauses of the solver with the given `encoding`
+fn encode(solver: &mut Solver, constraint: &Constraint, encoding: PbEncoding) {
+    // sum of c_i * l_i = offset + sum of w_i * l'_i where all w_i > 0
+    let (offset, terms) = normalize(&constraint.terms);
+    let rhs = constraint.rhs - offset;
+
+    if constraint.relation != Relation::AtLeast {
+        if rhs < 0 {
+            // the constraint cannot be satisfied
+            let _ = solver.add_problem_clause(&mut vec![]);
+        } else {
+            pb_at_most(solver, &terms, rhs as u64, encoding);
+        }
+    }
+    // (at least a negative rhs is trivially satisfied)
+    if constraint.relation != Relation::AtMost && rhs > 0 {
+        pb_at_least(solver, &terms, rhs as u64, encoding);
+    }
+}
+
+/// Rewrites the sum of the given terms into an equivalent constant `offset` plus a sum of terms
+/// having positive weights. This is achieved by replacing every `c.l` having a negative
+/// coefficient c with `c + |c|.¬l`.
+fn normalize(terms: &[(Coefficient, Literal)]) -> (Coefficient, Vec<(u64, Literal)>) {
+    let mut offset = 0;
+    let mut normalized = Vec::with_capacity(terms.len());
+    for &(c, l) in terms.iter() {
+        if c < 0 {
+            offset += c;
+            normalized.push((c.unsigned_abs(), !l));
+        } else if c > 0 {
+            normalized.push((c as u64, l));
+        }
+    }
+    (offset, normalized)
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Unit Tests
+// -----------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENCODINGS: [PbEncoding; 3] = [PbEncoding::Bdd, PbEncoding::Adder, PbEncoding::Sorter];
+
+    #[test]
+    fn no_constraint_is_satisfiable() {
+        let mut tested = PseudoBoolean::new(3);
+        assert!(tested.solve());
+        assert_eq!(tested.model().len(), 3);
+        assert_eq!(tested.minimize(|_| {}), Some(0));
+    }
+
+    #[test]
+    fn the_constraints_are_enforced() {
+        for &encoding in ENCODINGS.iter() {
+            let mut tested = PseudoBoolean::new(3);
+            tested.encoding = encoding;
+            // 2x1 + 3x2 + 4x3 >= 6 ; x1 + x2 + x3 <= 2 ; x3 = 0
+            tested.add_constraint(&[(2, 1), (3, 2), (4, 3)], Relation::AtLeast, 6);
+            tested.add_constraint(&[(1, 1), (1, 2), (1, 3)], Relation::AtMost,  2);
+            tested.add_constraint(&[(1, 3)], Relation::Equal, 0);
+
+            assert!(!tested.solve(), "{:?}", encoding);
+        }
+    }
+
+    #[test]
+    fn negative_coefficients_are_supported() {
+        for &encoding in ENCODINGS.iter() {
+            let mut tested = PseudoBoolean::new(3);
+            tested.encoding = encoding;
+            // x1 - x2 - 2x3 >= 0 ; -x1 >= -1 ; x2 + x3 >= 1
+            tested.add_constraint(&[(1, 1), (-1, 2), (-2, 3)], Relation::AtLeast, 0);
+            tested.add_constraint(&[(-1, 1)], Relation::AtLeast, -1);
+            tested.add_constraint(&[(1, 2), (1, 3)], Relation::AtLeast, 1);
+
+            assert!(tested.solve(), "{:?}", encoding);
+            assert_eq!(tested.model(), &[true, true, false]);
+        }
+    }
+
+    #[test]
+    fn negated_literals_are_supported() {
+        let mut tested = PseudoBoolean::new(2);
+        // 3~x1 + 2x2 = 5
+        tested.add_constraint(&[(3, -1), (2, 2)], Relation::Equal, 5);
+
+        assert!(tested.solve());
+        assert_eq!(tested.model(), &[false, true]);
+    }
+
+    #[test]
+    fn minimize_finds_the_optimum() {
+        for &encoding in ENCODINGS.iter() {
+            let mut tested = PseudoBoolean::new(4);
+            tested.encoding = encoding;
+            // min: 5x1 + 4x2 + 3x3 + 2x4 ; x1 + x2 >= 1 ; x2 + x3 + x4 >= 2 ; 2x1 + x4 >= 1
+            tested.set_objective(&[(5, 1), (4, 2), (3, 3), (2, 4)]);
+            tested.add_constraint(&[(1, 1), (1, 2)], Relation::AtLeast, 1);
+            tested.add_constraint(&[(1, 2), (1, 3), (1, 4)], Relation::AtLeast, 2);
+            tested.add_constraint(&[(2, 1), (1, 4)], Relation::AtLeast, 1);
+
+            let mut values = vec![];
+            assert_eq!(tested.minimize(|v| values.push(v)), Some(6), "{:?}", encoding);
+            assert_eq!(tested.model(), &[false, true, false, true]);
+            assert_eq!(tested.value(), 6);
+            // every solution is an improvement over the previous one
+            assert!(values.windows(2).all(|w| w[0] > w[1]));
+            assert_eq!(values.last(), Some(&6));
+        }
+    }
+
+    #[test]
+    fn minimize_supports_negative_objective_coefficients() {
+        let mut tested = PseudoBoolean::new(3);
+        // min: -x1 - x2 - x3 ; x1 + x2 + x3 <= 2
+        tested.set_objective(&[(-1, 1), (-1, 2), (-1, 3)]);
+        tested.add_constraint(&[(1, 1), (1, 2), (1, 3)], Relation::AtMost, 2);
+
+        assert_eq!(tested.minimize(|_| {}), Some(-2));
+        assert_eq!(tested.model().iter().filter(|&&v| v).count(), 2);
+    }
+
+    #[test]
+    fn unsatisfiable_constraints_have_no_optimum() {
+        let mut tested = PseudoBoolean::new(2);
+        tested.set_objective(&[(1, 1)]);
+        tested.add_constraint(&[(1, 1), (1, 2)], Relation::AtLeast, 3);
+
+        assert_eq!(tested.minimize(|_| {}), None);
+    }
+
+    #[test]
+    fn variables_are_declared_as_they_show_up() {
+        let mut tested = PseudoBoolean::new(0);
+        tested.add_constraint(&[(1, 2), (1, -3)], Relation::Equal, 2);
+
+        assert_eq!(tested.nb_vars(), 3);
+        assert!(tested.solve());
+        assert_eq!(&tested.model()[1..], &[true, false]);
+    }
+}
@@ -0,0 +1,248 @@
+use core::*;
+use solving::*;
+
+/// The strategies to enumerate all the models of a problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Enumeration {
+    /// Every model is excluded by a blocking clause (the negation of the model) before the search
+    /// resumes from where it stopped. Simple and fast, but there is one more clause per model.
+    #[default]
+    Blocking,
+    /// The (projected) models are enumerated depth first, the values of the variables being
+    /// decided in a fixed order under assumptions. No clause is added to the problem whatever the
+    /// number of models.
+    Decisions
+}
+
+impl Enumeration {
+    /// Returns the strategy having the given `name` (one of blocking or decisions) or None when
+    /// no strategy bears that name.
+    pub fn from_name(name: &str) -> Option<Enumeration> {
+        match name {
+            "blocking"  => Some(Enumeration::Blocking),
+            "decisions" => Some(Enumeration::Decisions),
+            _           => None
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # AllSat
+/// Enumerates the models of the problem of a solver, optionally projected onto a subset of its
+/// variables: in that case, the models which only differ on the other variables are reported once.
+///
+/// With the `Blocking` strategy, the solver keeps the blocking clauses of the models found: the
+/// problem it decides is modified by the enumeration. The `Decisions` strategy leaves the problem
+/// untouched: the search space of the projection variables is explored depth first, every node
+/// being a search under the assumption of the values decided so far. When the search fails, the
+/// exploration backtracks to the deepest value responsible for the failure (as told by the failed
+/// assumptions) and tries its other polarity, unless it was tried already.
+///
+/// See `Implementing Efficient All Solutions SAT Solvers` -- Toda, Soh (JEA 2016).
+// -----------------------------------------------------------------------------------------------
+#[derive(Debug)]
+pub struct AllSat<'a> {
+    /// The solver whose models are enumerated
+    solver      : &'a mut Solver,
+    /// The variables the models are projected onto
+    projection  : Vec<Variable>,
+    /// The strategy of the enumeration
+    pub strategy: Enumeration
+}
+
+impl<'a> AllSat<'a> {
+    /// Creates an enumerator of the models of the given solver, projected onto the variables of
+    /// the given `projection` (or onto all the variables of the solver when it is empty).
+    pub fn new(solver: &'a mut Solver, projection: &[Variable]) -> AllSat<'a> {
+        let mut projection: Vec<Variable> = if projection.is_empty() {
+            (1..=solver.nb_vars()).map(Variable::from).collect()
+        } else {
+            projection.to_vec()
+        };
+        projection.sort_by_key(|&v| usize::from(v));
+        projection.dedup();
+
+        AllSat { solver, projection, strategy: Enumeration::default() }
+    }
+
+    /// Returns the variables the models are projected onto
+    #[inline]
+    pub fn projection(&self) -> &[Variable] { &self.projection }
+
+    /// Enumerates the (projected) models of the problem, but no more than `limit` of them (when
+    /// a limit is given). Every model is handed over to `on_model` as the literals of the
+    /// projection variables that hold in the model, in the order of these variables.
+    ///
+    /// # Return Value
+    /// The number of models that were enumerated.
+    pub fn enumerate<F>(&mut self, limit: Option<usize>, on_model: F) -> usize
+        where F: FnMut(&[Literal]) {
+
+        if limit == Some(0) { return 0; }
+
+        match self.strategy {
+            Enumeration::Blocking  => self.enumerate_blocking(limit, on_model),
+            Enumeration::Decisions => self.enumerate_decisions(limit, on_model)
+        }
+    }
+
+    /// Enumerates the models, each of which is blocked (by a clause) once found
+    fn enumerate_blocking<F>(&mut self, limit: Option<usize>, mut on_model: F) -> usize
+        where F: FnMut(&[Literal]) {
+
+        let mut nb_models   = 0;
+        let mut satisfiable = self.solver.solve();
+        while satisfiable {
+            let model = self.model();
+            on_model(&model);
+            nb_models += 1;
+            if Some(nb_models) == limit { break; }
+
+            let blocking: Vec<Literal> = model.iter().map(|&l| !l).collect();
+            satisfiable = self.solver.solve_next(&blocking);
+        }
+        nb_models
+    }
+
+    /// Enumerates the models depth first, under the assumption of the values decided so far
+    fn enumerate_decisions<F>(&mut self, limit: Option<usize>, mut on_model: F) -> usize
+        where F: FnMut(&[Literal]) {
+
+        let mut nb_models = 0;
+        // the values decided so far, each of which tells whether its other polarity was tried
+        let mut decided: Vec<(Literal, bool)> = vec![];
+        loop {
+            let assumptions: Vec<Literal> = decided.iter().map(|&(l, _)| l).collect();
+            if self.solver.solve_with_assumptions(&assumptions) {
+                let model = self.model();
+                on_model(&model);
+                nb_models += 1;
+                if Some(nb_models) == limit { break; }
+
+                for &l in model[decided.len()..].iter() {
+                    decided.push((l, false));
+                }
+            } else {
+                // the values decided after the deepest failed assumption are of no concern
+                let failed = self.solver.failed_assumptions();
+                let deepest = decided.iter().rposition(|&(l, _)| failed.contains(&l));
+                decided.truncate(deepest.map_or(0, |d| d + 1));
+            }
+
+            // try the other polarity of the deepest value for which it was not tried yet
+            while let Some(&(_, true)) = decided.last() {
+                decided.pop();
+            }
+            match decided.pop() {
+                None         => break,
+                Some((l, _)) => decided.push((!l, true))
+            }
+        }
+        nb_models
+    }
+
+    /// Returns the literals of the projection variables that hold in the last model found
+    fn model(&self) -> Vec<Literal> {
+        self.projection.iter()
+            .map(|&v| {
+                let positive = Literal::positive(v);
+                if self.solver.get_value(positive) == Bool::True { positive } else { !positive }
+            })
+            .collect()
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Unit Tests
+// -----------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fixtures::problem;
+
+    const STRATEGIES: [Enumeration; 2] = [Enumeration::Blocking, Enumeration::Decisions];
+
+    /// Enumerates the models and returns them as sorted lists of (signed) integers
+    fn models(solver: &mut Solver, projection: &[iint], strategy: Enumeration, limit: Option<usize>) -> Vec<Vec<iint>> {
+        let projection: Vec<Variable> = projection.iter().map(|&v| var(v as uint)).collect();
+        let mut tested = AllSat::new(solver, &projection);
+        tested.strategy = strategy;
+
+        let mut models = vec![];
+        let nb_models = tested.enumerate(limit, |m| models.push(m.iter().map(|l| l.to_isize() as iint).collect::<Vec<iint>>()));
+        assert_eq!(nb_models, models.len());
+        models.sort();
+        models
+    }
+
+    #[test]
+    fn all_the_models_are_enumerated_once() {
+        for &strategy in STRATEGIES.iter() {
+            // exactly one of 1, 2, 3 ; 4 -> 1
+            let mut solver = problem(4, &[&[1, 2, 3], &[-1, -2], &[-1, -3], &[-2, -3], &[-4, 1]]);
+            assert_eq!(models(&mut solver, &[], strategy, None), vec![
+                vec![-1, -2,  3, -4],
+                vec![-1,  2, -3, -4],
+                vec![ 1, -2, -3, -4],
+                vec![ 1, -2, -3,  4]
+            ], "{:?}", strategy);
+        }
+    }
+
+    #[test]
+    fn the_models_can_be_projected() {
+        for &strategy in STRATEGIES.iter() {
+            // 1 <-> (2 | 3)
+            let mut solver = problem(3, &[&[-1, 2, 3], &[1, -2], &[1, -3]]);
+            assert_eq!(models(&mut solver, &[1], strategy, None), vec![vec![-1], vec![1]], "{:?}", strategy);
+
+            let mut solver = problem(3, &[&[-1, 2, 3], &[1, -2], &[1, -3]]);
+            assert_eq!(models(&mut solver, &[3, 1, 3], strategy, None).len(), 3, "{:?}", strategy);
+        }
+    }
+
+    #[test]
+    fn an_unsatisfiable_problem_has_no_model() {
+        for &strategy in STRATEGIES.iter() {
+            let mut solver = problem(2, &[&[1, 2], &[-1], &[-2]]);
+            assert!(models(&mut solver, &[], strategy, None).is_empty());
+        }
+    }
+
+    #[test]
+    fn the_enumeration_stops_at_the_limit() {
+        for &strategy in STRATEGIES.iter() {
+            let mut solver = Solver::new(10);
+            assert_eq!(models(&mut solver, &[], strategy, Some(5)).len(), 5);
+            assert_eq!(models(&mut solver, &[], strategy, Some(0)).len(), 0);
+        }
+    }
+
+    #[test]
+    fn the_number_of_models_is_right_with_every_configuration() {
+        // at most one of 1..6 (7 models) times 7 | 8 (3 models) times the free 9 (2 models)
+        let mut clauses: Vec<Vec<iint>> = vec![vec![7, 8]];
+        for a in 1..=6 {
+            for b in a+1..=6 {
+                clauses.push(vec![-a, -b]);
+            }
+        }
+        let clauses: Vec<&[iint]> = clauses.iter().map(|c| c.as_slice()).collect();
+
+        for &strategy in STRATEGIES.iter() {
+            for &chrono in [false, true].iter() {
+                let mut solver = problem(9, &clauses);
+                solver.chrono_backtrack = chrono;
+                solver.chrono_threshold = 0;
+                assert_eq!(models(&mut solver, &[], strategy, None).len(), 42);
+            }
+        }
+    }
+
+    #[test]
+    fn enumeration_from_name() {
+        assert_eq!(Enumeration::from_name("blocking"),  Some(Enumeration::Blocking));
+        assert_eq!(Enumeration::from_name("decisions"), Some(Enumeration::Decisions));
+        assert_eq!(Enumeration::from_name("all"),       None);
+    }
+}
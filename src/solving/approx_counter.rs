@@ -0,0 +1,320 @@
+extern crate rand;
+
+use std::collections::HashMap;
+
+use self::rand::{Rng, SeedableRng};
+use self::rand::rngs::StdRng;
+
+use core::*;
+use solving::*;
+use encodings::guarded_xor_literal;
+
+// -----------------------------------------------------------------------------------------------
+/// # ApproxCounter
+/// An approximate model counter (projected onto a sampling set) giving (ε, δ) guarantees: with a
+/// probability of at least 1 - δ, the estimated number of models is within a factor 1 + ε of the
+/// actual one.
+///
+/// The models are partitioned in (roughly equal) cells by a random hash function made of m XOR
+/// constraints over the variables of the sampling set: each constraint halves the number of models
+/// of the cell. The number of models of the cell is counted up to a threshold by enumerating them
+/// (with blocking clauses), and the number m of constraints is searched (from the one of the
+/// previous iteration) such that the cell is small but not empty. The number of models of the
+/// cell times 2^m then estimates the number of models. The median of several such estimates is
+/// returned.
+///
+/// All of this happens incrementally: each XOR constraint defines a literal which is assumed to
+/// select a cell, and the blocking clauses of an enumeration are guarded by an activation literal
+/// which is eventually disabled. The definitions of the XOR constraints of a hash function are
+/// propagated by Gauss-Jordan elimination and removed (or, when `native_xors` is off, encoded
+/// into clauses by `guarded_xor_literal` and guarded as well): once the hash function is done
+/// with, all the variables it has introduced are fixed so that they no longer cost anything.
+///
+/// See `Algorithmic Improvements in Approximate Counting for Probabilistic Inference: From Linear
+///     to Logarithmic SAT Calls` -- Chakraborty, Meel, Vardi (IJCAI 2016).
+// -----------------------------------------------------------------------------------------------
+#[derive(Debug)]
+pub struct ApproxCounter<'a> {
+    /// The solver whose models are counted
+    solver     : &'a mut Solver,
+    /// The variables the models are projected onto
+    sampling   : Vec<Variable>,
+    /// The tolerance of the estimate
+    pub epsilon: f64,
+    /// The confidence of the estimate (the probability that it is out of tolerance)
+    pub delta  : f64,
+    /// Tells whether the XOR constraints are handed over to the Gauss-Jordan elimination engine
+    /// of the solver (the default) rather than encoded into clauses
+    pub native_xors: bool,
+    /// The source of the random hash functions
+    rng        : StdRng,
+    /// The number of searches the counting took
+    pub nb_solver_calls: usize
+}
+
+impl<'a> ApproxCounter<'a> {
+    /// Creates a counter of the models of the given solver, projected onto the variables of the
+    /// given `sampling` set (or onto all the variables of the solver when it is empty). The
+    /// random hash functions are drawn from a generator initialized with the given `seed`.
+    pub fn new(solver: &'a mut Solver, sampling: &[Variable], seed: u64) -> ApproxCounter<'a> {
+        let mut sampling: Vec<Variable> = if sampling.is_empty() {
+            (1..=solver.nb_vars()).map(Variable::from).collect()
+        } else {
+            sampling.to_vec()
+        };
+        sampling.sort_by_key(|&v| usize::from(v));
+        sampling.dedup();
+
+        ApproxCounter {
+            solver,
+            sampling,
+            epsilon        : 0.8,
+            delta          : 0.2,
+            native_xors    : true,
+            rng            : StdRng::seed_from_u64(seed),
+            nb_solver_calls: 0
+        }
+    }
+
+    /// Returns the number of models a cell must have (at most) to be deemed small
+    pub fn threshold(&self) -> usize {
+        let eps = self.epsilon;
+        (1.0 + 9.84 * (1.0 + eps / (1.0 + eps)) * (1.0 + 1.0 / eps).powi(2)).ceil() as usize
+    }
+
+    /// Returns the number of estimates whose median is returned
+    pub fn nb_iterations(&self) -> usize {
+        (17.0 * (3.0 / self.delta).log2()).ceil() as usize
+    }
+
+    /// Returns an estimate of the number of (projected) models of the problem. When the problem
+    /// has fewer models than the threshold, their exact number is returned.
+    pub fn count(&mut self) -> Natural {
+        let threshold = self.threshold();
+        let exact = self.bounded_count(&[], threshold);
+        if exact < threshold {
+            return Natural::from(exact as u64);
+        }
+
+        let mut estimates = vec![];
+        let mut previous  = None;
+        for _ in 0..self.nb_iterations() {
+            let (nb_models, m) = self.estimate(threshold, previous);
+            estimates.push(&Natural::from(nb_models as u64) << m);
+            previous = Some(m);
+        }
+
+        estimates.sort();
+        estimates.swap_remove(estimates.len() / 2)
+    }
+
+    /// Draws a new hash function and searches the smallest number m of its XOR constraints which
+    /// yields a cell having fewer models than the `threshold` (knowing that the whole problem has
+    /// more). The search gallops from the `previous` m (if any) before narrowing down.
+    ///
+    /// # Return Value
+    /// The number of models of the cell along with m.
+    fn estimate(&mut self, threshold: usize, previous: Option<usize>) -> (usize, usize) {
+        let n      = self.sampling.len();
+        let first  = self.solver.nb_vars() + 1;
+        let guard  = Literal::positive(self.solver.new_var());
+        let mut cell   = vec![guard];
+        let mut counts = HashMap::new();
+        counts.insert(0, threshold);
+
+        // count(lo) >= threshold and count(hi) < threshold (which is assumed for n)
+        let (mut lo, mut hi) = (0, n);
+        if let Some(previous) = previous.map(|p| p.max(1).min(n)) {
+            let mut step = 1;
+            if self.cell_count(&mut cell, &mut counts, previous, threshold) >= threshold {
+                lo = previous;
+                while lo + step < n {
+                    if self.cell_count(&mut cell, &mut counts, lo + step, threshold) < threshold {
+                        hi = lo + step;
+                        break;
+                    }
+                    lo   += step;
+                    step *= 2;
+                }
+            } else {
+                hi = previous;
+                while hi > step {
+                    if self.cell_count(&mut cell, &mut counts, hi - step, threshold) >= threshold {
+                        lo = hi - step;
+                        break;
+                    }
+                    hi   -= step;
+                    step *= 2;
+                }
+            }
+        }
+
+        while hi - lo > 1 {
+            let mid = (lo + hi) / 2;
+            if self.cell_count(&mut cell, &mut counts, mid, threshold) >= threshold {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        let count = self.cell_count(&mut cell, &mut counts, hi, threshold);
+
+        // the hash function is disabled for good (a native constraint is removed) and all the
+        // variables it has introduced are fixed
+        let _ = self.solver.add_problem_clause(&mut vec![(!guard).to_isize() as iint]);
+        if self.native_xors {
+            for l in cell[1..].iter() {
+                self.solver.remove_xors(l.var());
+            }
+        }
+        for v in first..=self.solver.nb_vars() {
+            let _ = self.solver.add_problem_clause(&mut vec![-(v as iint)]);
+        }
+        self.solver.remove_satisfied();
+        (count, hi)
+    }
+
+    /// Returns the number of models (up to the `threshold`) of the cell selected by the first `m`
+    /// XOR constraints of the hash function, whose constraints are drawn as they are needed. The
+    /// `cell` starts with the guard of the hash function, and the `counts` memoize the numbers of
+    /// models of the cells already counted.
+    fn cell_count(&mut self, cell: &mut Vec<Literal>, counts: &mut HashMap<usize, usize>, m: usize, threshold: usize) -> usize {
+        if let Some(&count) = counts.get(&m) {
+            return count;
+        }
+
+        while cell.len() <= m {
+            let constraint = self.random_xor(cell[0]);
+            cell.push(constraint);
+        }
+        let count = self.bounded_count(&cell[..=m], threshold);
+        counts.insert(m, count);
+        count
+    }
+
+    /// Draws a random XOR constraint over the sampling set (each variable occurs with probability
+    /// 1/2 and the parity is chosen at random) and returns the literal which holds iff the
+    /// constraint is satisfied (as long as the given `guard` holds).
+    fn random_xor(&mut self, guard: Literal) -> Literal {
+        let rng = &mut self.rng;
+        let literals: Vec<Literal> = self.sampling.iter()
+            .filter(|_| rng.gen())
+            .map(|&v| Literal::positive(v))
+            .collect();
+        let parity: bool = self.rng.gen();
+
+        let xor = if self.native_xors {
+            // x1 ^ ... ^ xk ^ !t holds iff t <-> x1 ^ ... ^ xk
+            let defined = Literal::positive(self.solver.new_var());
+            let mut xor: Vec<iint> = literals.iter().map(|l| l.to_isize() as iint).collect();
+            xor.push((!defined).to_isize() as iint);
+            self.solver.add_xor(&xor);
+            defined
+        } else {
+            guarded_xor_literal(self.solver, guard, &literals)
+        };
+
+        if parity { xor } else { !xor }
+    }
+
+    /// Enumerates the (projected) models satisfying the given `cell` assumptions, but no more
+    /// than `threshold` of them, and returns their number. The blocking clauses are guarded by a
+    /// fresh activation literal which is disabled for good afterwards.
+    fn bounded_count(&mut self, cell: &[Literal], threshold: usize) -> usize {
+        let active = Literal::positive(self.solver.new_var());
+        let mut assumptions = cell.to_vec();
+        assumptions.push(active);
+
+        self.nb_solver_calls += 1;
+        let mut satisfiable = self.solver.solve_with_assumptions(&assumptions);
+        let mut nb_models   = 0;
+        while satisfiable {
+            nb_models += 1;
+            if nb_models >= threshold { break; }
+
+            // the search resumes from where the model was found
+            let mut blocking = vec![!active];
+            for &v in self.sampling.iter() {
+                let positive = Literal::positive(v);
+                blocking.push(if self.solver.get_value(positive) == Bool::True { !positive } else { positive });
+            }
+            self.nb_solver_calls += 1;
+            satisfiable = self.solver.solve_next(&blocking);
+        }
+
+        let _ = self.solver.add_problem_clause(&mut vec![(!active).to_isize() as iint]);
+        nb_models
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Unit Tests
+// -----------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fixtures::problem;
+
+    #[test]
+    fn the_guarantees_set_the_threshold_and_the_iterations() {
+        let mut solver  = Solver::new(1);
+        let mut counter = ApproxCounter::new(&mut solver, &[], 1);
+        assert_eq!(counter.threshold(), 73);
+        assert_eq!(counter.nb_iterations(), 67);
+
+        counter.epsilon = 0.5;
+        counter.delta   = 0.05;
+        assert_eq!(counter.threshold(), 120);
+        assert_eq!(counter.nb_iterations(), 101);
+    }
+
+    #[test]
+    fn the_count_is_exact_below_the_threshold() {
+        // exactly one of 1, 2, 3 ; 4 -> 1
+        let mut solver = problem(4, &[&[1, 2, 3], &[-1, -2], &[-1, -3], &[-2, -3], &[-4, 1]]);
+        assert_eq!(ApproxCounter::new(&mut solver, &[], 1).count(), Natural::from(4));
+
+        let mut solver = problem(4, &[&[1, 2, 3], &[-1, -2], &[-1, -3], &[-2, -3], &[-4, 1]]);
+        assert_eq!(ApproxCounter::new(&mut solver, &[var(2), var(3)], 1).count(), Natural::from(3));
+
+        let mut solver = problem(2, &[&[1], &[-1]]);
+        assert_eq!(ApproxCounter::new(&mut solver, &[], 1).count(), Natural::zero());
+    }
+
+    #[test]
+    fn the_estimate_is_within_the_tolerance() {
+        // 1 | 2 over 10 variables: 3 * 2^8 = 768 models
+        for &native in [false, true].iter() {
+            let mut solver  = problem(10, &[&[1, 2]]);
+            let mut counter = ApproxCounter::new(&mut solver, &[], 7);
+            counter.native_xors = native;
+            counter.epsilon = 1.0;
+            counter.delta   = 0.8;
+
+            let estimate = counter.count();
+            assert!(estimate >= Natural::from(768 / 2), "{} (native = {})", estimate, native);
+            assert!(estimate <= Natural::from(768 * 2), "{} (native = {})", estimate, native);
+        }
+    }
+
+    #[test]
+    fn the_estimate_is_projected_onto_the_sampling_set() {
+        // 11..14 are functionally defined by 1..4: 2^10 projected models
+        let mut clauses: Vec<Vec<iint>> = vec![];
+        for v in 1..=4 {
+            clauses.push(vec![-v, v + 10]);
+            clauses.push(vec![v, -(v + 10)]);
+        }
+        let clauses: Vec<&[iint]> = clauses.iter().map(|c| c.as_slice()).collect();
+        let sampling: Vec<Variable> = (1..=10).map(var).collect();
+
+        let mut solver  = problem(14, &clauses);
+        let mut counter = ApproxCounter::new(&mut solver, &sampling, 3);
+        counter.epsilon = 1.0;
+        counter.delta   = 0.8;
+
+        let estimate = counter.count();
+        assert!(estimate >= Natural::from(1024 / 2), "{}", estimate);
+        assert!(estimate <= Natural::from(1024 * 2), "{}", estimate);
+    }
+}
@@ -0,0 +1,565 @@
+use std::fmt;
+use std::slice;
+use std::ops::{Index, IndexMut};
+
+use core::*;
+use solving::ClauseId;
+
+// Layout of the header preceding the literals of each clause in the arena
+const SIZE    : usize = 0;
+const FLAGS   : usize = 1;
+const LBD     : usize = 2;
+const ACTIVITY: usize = 3;
+const HEADER  : usize = 4;
+
+// Meaning of the bits of the FLAGS word
+const LEARNED   : u32 = 1;
+const DELETED   : u32 = 2;
+const USED      : u32 = 4;
+const PROTECTED : u32 = 8;
+const TIER_SHIFT: u32 = 4;
+const TIER_MASK : u32 = 3 << TIER_SHIFT;
+const XOR_REASON: u32 = 64;
+
+// -----------------------------------------------------------------------------------------------
+/// # Tier
+/// The tier to which a learned clause belongs. It determines how the clause is managed upon
+/// database reduction.
+/// See `Between SAT and UNSAT: The Fundamental Difference in CDCL SAT` -- Chanseok Oh (SAT 2015).
+// -----------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Tier {
+    /// The glue clauses (LBD <= 2). These are kept forever.
+    Core  = 0,
+    /// The clauses that are of good quality (LBD <= 6). These are kept as long as they are used.
+    Tier2 = 1,
+    /// All the other clauses. Half of these is forgotten at each reduction of the database.
+    Local = 2
+}
+
+impl Tier {
+    /// Returns the tier to which a clause having the given `lbd` belongs
+    pub fn of(lbd: u32) -> Tier {
+        if lbd <= 2 {
+            Tier::Core
+        } else if lbd <= 6 {
+            Tier::Tier2
+        } else {
+            Tier::Local
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Clause Arena
+/// The clause arena stores all the clauses of the problem in one single contiguous block of
+/// memory. Each clause is made of a header (size, flags, lbd and activity) immediately followed by
+/// its literals. A clause is identified by the offset of its header in the arena. This identifier
+/// is stable: it does not change when some other clause is removed from the database.
+///
+/// _Note:_
+/// Removing a clause only marks it as deleted. The memory it used to occupy is only reclaimed
+/// when the arena is compacted (`collect_garbage`). Because compaction relocates the clauses, it
+/// returns a `Relocation` which the caller must use to update all the clause ids it holds.
+// -----------------------------------------------------------------------------------------------
+pub struct ClauseArena {
+    /// The actual memory where headers and literals are stored
+    memory: Vec<u32>,
+    /// The number of clauses which are alive (not deleted)
+    nb_clauses: usize,
+    /// The number of words which are occupied by deleted clauses or literals
+    wasted: usize
+}
+
+impl ClauseArena {
+    /// Creates an empty arena capable of holding `words` words without reallocation
+    pub fn with_capacity(words: usize) -> ClauseArena {
+        ClauseArena {
+            memory: Vec::with_capacity(words),
+            nb_clauses: 0,
+            wasted: 0
+        }
+    }
+
+    /// Appends a new clause made of the given `literals` to the arena and returns its id.
+    /// The clause initially belongs to the local tier, has an unknown lbd and no activity.
+    pub fn alloc(&mut self, literals: &[Literal], is_learned: bool) -> ClauseId {
+        let c_id  = self.memory.len();
+        let flags = if is_learned { LEARNED } else { 0 };
+
+        self.memory.push(literals.len() as u32);
+        self.memory.push(flags | (Tier::Local as u32) << TIER_SHIFT);
+        self.memory.push(u32::MAX);
+        self.memory.push(0f32.to_bits());
+        for l in literals.iter() {
+            self.memory.push(l.to_isize() as iint as u32);
+        }
+
+        self.nb_clauses += 1;
+        c_id
+    }
+
+    /// Returns the number of clauses (not deleted) in the arena
+    #[inline]
+    pub fn len(&self) -> usize { self.nb_clauses }
+
+    /// Returns true iff the arena contains no clause
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.nb_clauses == 0 }
+
+    /// Returns an iterator over the ids of all the clauses which have not been deleted
+    pub fn ids(&self) -> ClauseIds<'_> {
+        ClauseIds { arena: self, cursor: 0 }
+    }
+
+    /// Marks the given clause as deleted. Its memory is going to be reclaimed upon the next
+    /// garbage collection.
+    pub fn delete(&mut self, c_id: ClauseId) {
+        debug_assert!(!self.is_deleted(c_id));
+        self.memory[c_id + FLAGS] |= DELETED;
+        self.wasted     += HEADER + self.memory[c_id + SIZE] as usize;
+        self.nb_clauses -= 1;
+    }
+
+    /// Removes the literal at position `pos` from the given clause. Just like `Vec::swap_remove`,
+    /// the removed literal is replaced by the last literal of the clause.
+    pub fn swap_remove_literal(&mut self, c_id: ClauseId, pos: usize) {
+        let size = self.memory[c_id + SIZE] as usize;
+        assert!(pos < size);
+        self.memory.swap(c_id + HEADER + pos, c_id + HEADER + size - 1);
+        self.truncate(c_id, size - 1);
+    }
+
+    /// Shortens the given clause, keeping only the first `size` literals.
+    pub fn truncate(&mut self, c_id: ClauseId, size: usize) {
+        let old_size = self.memory[c_id + SIZE] as usize;
+        if size < old_size {
+            self.memory[c_id + SIZE] = size as u32;
+            for i in size..old_size {
+                self.memory[c_id + HEADER + i] = PADDING;
+            }
+            self.wasted += old_size - size;
+        }
+    }
+
+    #[inline]
+    fn is_set(&self, c_id: ClauseId, flag: u32) -> bool {
+        self.memory[c_id + FLAGS] & flag != 0
+    }
+
+    #[inline]
+    fn set(&mut self, c_id: ClauseId, flag: u32, value: bool) {
+        if value {
+            self.memory[c_id + FLAGS] |=  flag;
+        } else {
+            self.memory[c_id + FLAGS] &= !flag;
+        }
+    }
+
+    /// Tells whether the clause was learned during search or originates from the problem
+    #[inline]
+    pub fn is_learned(&self, c_id: ClauseId) -> bool { self.is_set(c_id, LEARNED) }
+
+    /// Tells whether the clause was deleted
+    #[inline]
+    pub fn is_deleted(&self, c_id: ClauseId) -> bool { self.is_set(c_id, DELETED) }
+
+    /// Tells whether the clause was used during conflict analysis since the last reduction
+    #[inline]
+    pub fn is_used(&self, c_id: ClauseId) -> bool { self.is_set(c_id, USED) }
+
+    /// Sets the 'used since last reduction' indication of the clause
+    #[inline]
+    pub fn set_used(&mut self, c_id: ClauseId, used: bool) { self.set(c_id, USED, used) }
+
+    /// Tells whether the lbd of the clause was improved since the last reduction
+    #[inline]
+    pub fn is_protected(&self, c_id: ClauseId) -> bool { self.is_set(c_id, PROTECTED) }
+
+    /// Sets the 'lbd recently updated' indication of the clause
+    #[inline]
+    pub fn set_protected(&mut self, c_id: ClauseId, protected: bool) {
+        self.set(c_id, PROTECTED, protected)
+    }
+
+    /// Tells whether the clause was built to explain an implication (or conflict) of the XOR
+    /// constraints rather than learned from a conflict
+    #[inline]
+    pub fn is_xor_reason(&self, c_id: ClauseId) -> bool { self.is_set(c_id, XOR_REASON) }
+
+    /// Sets the 'xor reason' indication of the clause
+    #[inline]
+    pub fn set_xor_reason(&mut self, c_id: ClauseId, xor_reason: bool) {
+        self.set(c_id, XOR_REASON, xor_reason)
+    }
+
+    /// Returns the tier of the clause
+    #[inline]
+    pub fn tier(&self, c_id: ClauseId) -> Tier {
+        match (self.memory[c_id + FLAGS] & TIER_MASK) >> TIER_SHIFT {
+            0 => Tier::Core,
+            1 => Tier::Tier2,
+            _ => Tier::Local
+        }
+    }
+
+    /// Sets the tier of the clause
+    #[inline]
+    pub fn set_tier(&mut self, c_id: ClauseId, tier: Tier) {
+        let flags = self.memory[c_id + FLAGS] & !TIER_MASK;
+        self.memory[c_id + FLAGS] = flags | (tier as u32) << TIER_SHIFT;
+    }
+
+    /// Returns the literal block distance of the clause
+    #[inline]
+    pub fn lbd(&self, c_id: ClauseId) -> u32 { self.memory[c_id + LBD] }
+
+    /// Sets the literal block distance of the clause
+    #[inline]
+    pub fn set_lbd(&mut self, c_id: ClauseId, lbd: u32) { self.memory[c_id + LBD] = lbd }
+
+    /// Returns the activity of the clause
+    #[inline]
+    pub fn activity(&self, c_id: ClauseId) -> f32 { f32::from_bits(self.memory[c_id + ACTIVITY]) }
+
+    /// Sets the activity of the clause
+    #[inline]
+    pub fn set_activity(&mut self, c_id: ClauseId, activity: f32) {
+        self.memory[c_id + ACTIVITY] = activity.to_bits()
+    }
+
+    /// Returns a DIMACS string representation of the given clause
+    pub fn to_dimacs(&self, c_id: ClauseId) -> String {
+        let mut out = String::new();
+
+        for l in self[c_id].iter() {
+            out.push_str(&format!("{} ", l.to_isize()));
+        }
+        out.push('0');
+        out
+    }
+
+    /// Tells whether enough memory is wasted to make a garbage collection worthwhile
+    #[inline]
+    pub fn should_collect_garbage(&self) -> bool {
+        self.wasted * 5 > self.memory.len()
+    }
+
+    /// Compacts the arena: the deleted clauses are dropped and the remaining ones are moved
+    /// towards the beginning of the arena (their relative order is preserved).
+    ///
+    /// # Return Value
+    /// The relocation telling the new id of each of the clauses which were moved.
+    pub fn collect_garbage(&mut self) -> Relocation {
+        let mut moves = Vec::with_capacity(self.nb_clauses);
+        let len       = self.memory.len();
+
+        let mut from = 0;
+        let mut into = 0;
+        while from < len {
+            let size = HEADER + self.memory[from + SIZE] as usize;
+            let next = from + HEADER + self.capacity(from);
+
+            if self.memory[from + FLAGS] & DELETED == 0 {
+                self.memory.copy_within(from..from + size, into);
+                moves.push((from, into));
+                into += size;
+            }
+            from = next;
+        }
+
+        self.memory.truncate(into);
+        self.wasted = 0;
+        Relocation { moves }
+    }
+
+    /// Returns the number of words that were originally allocated to store the literals of the
+    /// given clause. This is at least its current size (clauses can only shrink).
+    fn capacity(&self, c_id: ClauseId) -> usize {
+        // The words that were freed when shortening a clause are filled with PADDING. Hence, the
+        // next header is the first word past the literals of the clause which is not PADDING.
+        let mut cap = self.memory[c_id + SIZE] as usize;
+        while c_id + HEADER + cap < self.memory.len() && self.memory[c_id + HEADER + cap] == PADDING {
+            cap += 1;
+        }
+        cap
+    }
+}
+
+/// The value used to fill the words freed by the shortening of some clause. It can not be
+/// confused with the size of a header since no clause can ever be that long.
+const PADDING: u32 = u32::MAX;
+
+// Allow the use of clause ids to access the literals of a clause
+impl Index<ClauseId> for ClauseArena {
+    type Output = [Literal];
+    #[inline]
+    fn index(&self, c_id: ClauseId) -> &[Literal] {
+        let size  = self.memory[c_id + SIZE] as usize;
+        let words = &self.memory[c_id + HEADER..c_id + HEADER + size];
+        // This is safe: Literal is a transparent wrapper around a 32 bits integer
+        unsafe { slice::from_raw_parts(words.as_ptr() as *const Literal, size) }
+    }
+}
+impl IndexMut<ClauseId> for ClauseArena {
+    #[inline]
+    fn index_mut(&mut self, c_id: ClauseId) -> &mut [Literal] {
+        let size  = self.memory[c_id + SIZE] as usize;
+        let words = &mut self.memory[c_id + HEADER..c_id + HEADER + size];
+        // This is safe: Literal is a transparent wrapper around a 32 bits integer
+        unsafe { slice::from_raw_parts_mut(words.as_mut_ptr() as *mut Literal, size) }
+    }
+}
+
+impl fmt::Debug for ClauseArena {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list()
+            .entries(self.ids().map(|c| ClauseDebug(&self[c])))
+            .finish()
+    }
+}
+
+/// Formats the literals of a clause the same way as a `Clause`
+struct ClauseDebug<'a>(&'a [Literal]);
+impl<'a> fmt::Debug for ClauseDebug<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Clause({:?})", self.0)
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Clause Ids
+/// An iterator over the ids of the clauses which are alive in some arena
+// -----------------------------------------------------------------------------------------------
+pub struct ClauseIds<'a> {
+    arena : &'a ClauseArena,
+    cursor: usize
+}
+
+impl<'a> Iterator for ClauseIds<'a> {
+    type Item = ClauseId;
+
+    fn next(&mut self) -> Option<ClauseId> {
+        while self.cursor < self.arena.memory.len() {
+            let c_id = self.cursor;
+            self.cursor += HEADER + self.arena.capacity(c_id);
+
+            if !self.arena.is_deleted(c_id) {
+                return Some(c_id);
+            }
+        }
+        None
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Relocation
+/// The outcome of a garbage collection: it maps the old id of each clause that survived the
+/// collection onto its new id.
+// -----------------------------------------------------------------------------------------------
+#[derive(Debug)]
+pub struct Relocation {
+    /// The pairs (old id, new id) sorted by increasing old id
+    moves: Vec<(ClauseId, ClauseId)>
+}
+
+impl Relocation {
+    /// Returns the new id of the clause formerly identified by `old`.
+    ///
+    /// # Panics
+    /// When `old` did not identify a clause surviving the collection.
+    pub fn get(&self, old: ClauseId) -> ClauseId {
+        match self.moves.binary_search_by_key(&old, |&(from, _)| from) {
+            Ok(pos) => self.moves[pos].1,
+            Err(_)  => panic!("clause {} did not survive the garbage collection", old)
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Unit Tests
+// -----------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alloc_must_return_the_offset_of_the_header() {
+        let mut arena = ClauseArena::with_capacity(0);
+        let c0 = arena.alloc(&[lit(1), lit(-2)], false);
+        let c1 = arena.alloc(&[lit(3)], true);
+
+        assert_eq!(0, c0);
+        assert_eq!(HEADER + 2, c1);
+        assert_eq!(2, arena.len());
+    }
+
+    #[test]
+    fn index_must_yield_the_literals_of_the_clause() {
+        let mut arena = ClauseArena::with_capacity(0);
+        let c0 = arena.alloc(&[lit(1), lit(-2)], false);
+        let c1 = arena.alloc(&[lit(3), lit(-4), lit(5)], true);
+
+        assert_eq!(&[lit(1), lit(-2)], &arena[c0]);
+        assert_eq!(&[lit(3), lit(-4), lit(5)], &arena[c1]);
+
+        arena[c1].swap(0, 2);
+        assert_eq!(&[lit(5), lit(-4), lit(3)], &arena[c1]);
+        assert_eq!(&[lit(1), lit(-2)], &arena[c0]);
+    }
+
+    #[test]
+    fn alloc_must_initialize_the_header() {
+        let mut arena = ClauseArena::with_capacity(0);
+        let c0 = arena.alloc(&[lit(1), lit(-2)], false);
+        let c1 = arena.alloc(&[lit(3)], true);
+
+        assert!(!arena.is_learned(c0));
+        assert!( arena.is_learned(c1));
+        assert!(!arena.is_deleted(c1));
+        assert!(!arena.is_used(c1));
+        assert!(!arena.is_protected(c1));
+        assert!(!arena.is_xor_reason(c1));
+        assert_eq!(Tier::Local, arena.tier(c1));
+        assert_eq!(0.0, arena.activity(c1));
+    }
+
+    #[test]
+    fn setters_must_not_interfere_with_one_another() {
+        let mut arena = ClauseArena::with_capacity(0);
+        let c0 = arena.alloc(&[lit(1), lit(-2)], true);
+
+        arena.set_used(c0, true);
+        arena.set_tier(c0, Tier::Tier2);
+        arena.set_lbd(c0, 4);
+        arena.set_activity(c0, 1.5);
+
+        assert!(arena.is_learned(c0));
+        assert!(arena.is_used(c0));
+        assert!(!arena.is_protected(c0));
+        assert_eq!(Tier::Tier2, arena.tier(c0));
+        assert_eq!(4, arena.lbd(c0));
+        assert_eq!(1.5, arena.activity(c0));
+
+        arena.set_used(c0, false);
+        arena.set_protected(c0, true);
+        arena.set_xor_reason(c0, true);
+        arena.set_tier(c0, Tier::Core);
+
+        assert!(!arena.is_used(c0));
+        assert!(arena.is_protected(c0));
+        assert!(arena.is_xor_reason(c0));
+        assert_eq!(Tier::Core, arena.tier(c0));
+        assert_eq!(&[lit(1), lit(-2)], &arena[c0]);
+    }
+
+    #[test]
+    fn ids_must_skip_the_deleted_clauses() {
+        let mut arena = ClauseArena::with_capacity(0);
+        let c0 = arena.alloc(&[lit(1), lit(-2)], false);
+        let c1 = arena.alloc(&[lit(3)], true);
+        let c2 = arena.alloc(&[lit(4), lit(5), lit(6)], true);
+
+        arena.delete(c1);
+
+        assert!(arena.is_deleted(c1));
+        assert_eq!(2, arena.len());
+        assert_eq!(vec![c0, c2], arena.ids().collect::<Vec<ClauseId>>());
+    }
+
+    #[test]
+    fn ids_must_skip_the_padding_of_shortened_clauses() {
+        let mut arena = ClauseArena::with_capacity(0);
+        let c0 = arena.alloc(&[lit(1), lit(-2), lit(3), lit(4)], false);
+        let c1 = arena.alloc(&[lit(5)], true);
+
+        arena.truncate(c0, 1);
+
+        assert_eq!(&[lit(1)], &arena[c0]);
+        assert_eq!(vec![c0, c1], arena.ids().collect::<Vec<ClauseId>>());
+    }
+
+    #[test]
+    fn swap_remove_literal_must_replace_the_literal_by_the_last_one() {
+        let mut arena = ClauseArena::with_capacity(0);
+        let c0 = arena.alloc(&[lit(1), lit(-2), lit(3), lit(4)], false);
+        let c1 = arena.alloc(&[lit(5)], true);
+
+        arena.swap_remove_literal(c0, 1);
+
+        assert_eq!(&[lit(1), lit(4), lit(3)], &arena[c0]);
+        assert_eq!(&[lit(5)], &arena[c1]);
+    }
+
+    #[test]
+    fn should_collect_garbage_must_be_false_when_nothing_is_wasted() {
+        let mut arena = ClauseArena::with_capacity(0);
+        arena.alloc(&[lit(1), lit(-2)], false);
+        arena.alloc(&[lit(3)], true);
+
+        assert!(!arena.should_collect_garbage());
+    }
+
+    #[test]
+    fn should_collect_garbage_must_be_true_when_enough_is_wasted() {
+        let mut arena = ClauseArena::with_capacity(0);
+        let c0 = arena.alloc(&[lit(1), lit(-2)], false);
+        arena.alloc(&[lit(3)], true);
+
+        arena.delete(c0);
+        assert!(arena.should_collect_garbage());
+    }
+
+    #[test]
+    fn collect_garbage_must_compact_the_arena() {
+        let mut arena = ClauseArena::with_capacity(0);
+        let c0 = arena.alloc(&[lit(1), lit(-2)], false);
+        let c1 = arena.alloc(&[lit(3), lit(7), lit(8)], true);
+        let c2 = arena.alloc(&[lit(4), lit(5), lit(6)], true);
+
+        arena.set_lbd(c2, 3);
+        arena.truncate(c1, 1);
+        arena.delete(c0);
+
+        let relocation = arena.collect_garbage();
+        let n1 = relocation.get(c1);
+        let n2 = relocation.get(c2);
+
+        assert_eq!(0, n1);
+        assert_eq!(HEADER + 1, n2);
+        assert_eq!(vec![n1, n2], arena.ids().collect::<Vec<ClauseId>>());
+        assert_eq!(&[lit(3)], &arena[n1]);
+        assert_eq!(&[lit(4), lit(5), lit(6)], &arena[n2]);
+        assert_eq!(3, arena.lbd(n2));
+        assert!(!arena.should_collect_garbage());
+    }
+
+    #[test]
+    #[should_panic]
+    fn relocation_must_panic_for_a_clause_that_did_not_survive() {
+        let mut arena = ClauseArena::with_capacity(0);
+        let c0 = arena.alloc(&[lit(1), lit(-2)], false);
+        arena.alloc(&[lit(3)], true);
+
+        arena.delete(c0);
+        arena.collect_garbage().get(c0);
+    }
+
+    #[test]
+    fn debug_must_only_show_the_live_clauses() {
+        let mut arena = ClauseArena::with_capacity(0);
+        let c0 = arena.alloc(&[lit(1), lit(-2)], false);
+        arena.alloc(&[lit(3)], true);
+
+        arena.delete(c0);
+        assert_eq!("[Clause([Literal(3)])]", format!("{:?}", arena));
+    }
+
+    #[test]
+    fn to_dimacs_must_end_with_zero() {
+        let mut arena = ClauseArena::with_capacity(0);
+        let c0 = arena.alloc(&[lit(1), lit(-2)], false);
+
+        assert_eq!("1 -2 0", arena.to_dimacs(c0));
+    }
+}
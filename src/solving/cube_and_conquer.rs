@@ -0,0 +1,391 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+
+use core::*;
+use collections::*;
+use solving::*;
+
+/// A cube is a conjunction of literals. The cubes produced by the lookahead split the problem:
+/// the problem is satisfiable iff it is satisfiable under (at least) one of the cubes.
+pub type Cube = Vec<Literal>;
+
+/// The weight of a clause which got reduced to k unassigned literals is `REDUCED_WEIGHT^(k-2)`
+const REDUCED_WEIGHT: f64 = 0.2;
+
+/// The outcome of the selection of a branching variable
+enum Selection {
+    /// Branch on this variable
+    Branch(Variable),
+    /// This literal is implied (its negation is a failed literal)
+    Implied(Literal),
+    /// Both polarities of some variable are failed literals: the current node is refuted
+    Refuted,
+    /// There is no free variable left: the current node is a model
+    Complete
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Lookahead
+/// A lookahead cube generator. It splits the problem into cubes by building a (bounded) decision
+/// tree in which every branching variable is chosen by looking ahead at the consequences of both
+/// its polarities. The leaves of that tree (save the refuted ones) are the cubes.
+///
+/// Every candidate variable x is scored with the march heuristic
+/// `1024 * diff(x) * diff(-x) + diff(x) + diff(-x)` where `diff(l)` weighs the clauses that were
+/// reduced (but not satisfied) when propagating `l`. Failed literals are detected on the fly:
+/// their negation is asserted at the current node.
+///
+/// See `Cube and Conquer: Guiding CDCL SAT Solvers by Lookaheads` -- Heule, Kullmann, Wieringa,
+/// Biere (HVC 2011).
+// -----------------------------------------------------------------------------------------------
+#[derive(Debug)]
+pub struct Lookahead<'a> {
+    /// The solver used to propagate the consequences of the literals
+    solver     : &'a mut Solver,
+    /// The maximum number of literals in a cube (the cutoff depth of the decision tree)
+    depth      : usize,
+    /// The maximum number of variables that are looked ahead upon to select a branching variable
+    candidates : usize,
+    /// The (problem) clauses of the solver
+    clauses    : Vec<ClauseId>,
+    /// The index (in `clauses`) of the clauses containing each literal
+    occurs     : LitIdxVec<Vec<usize>>,
+    /// Remembers the last time each clause was weighed (avoids weighing a clause twice)
+    stamps     : Vec<usize>,
+    /// The current time stamp
+    stamp      : usize,
+    /// The cubes produced so far
+    cubes      : Vec<Cube>
+}
+
+impl<'a> Lookahead<'a> {
+    /// Creates a cube generator splitting the problem loaded in `solver` into cubes having (at
+    /// most) `depth` literals.
+    pub fn new(solver: &'a mut Solver, depth: usize) -> Lookahead<'a> {
+        let clauses: Vec<ClauseId> = solver.clauses.ids()
+            .filter(|&c| !solver.clauses.is_learned(c))
+            .collect();
+
+        let mut occurs = LitIdxVec::with_capacity(solver.nb_vars());
+        for _ in 0..solver.nb_vars() {
+            occurs.push_values(vec![], vec![]);
+        }
+        for (i, &c) in clauses.iter().enumerate() {
+            for &l in solver.clauses[c].iter() {
+                occurs[l].push(i);
+            }
+        }
+
+        let stamps = vec![0; clauses.len()];
+        Lookahead { solver, depth, candidates: 50, clauses, occurs, stamps, stamp: 0, cubes: vec![] }
+    }
+
+    /// Splits the problem and returns the cubes. When the problem is refuted by the lookahead
+    /// alone, no cube at all is returned.
+    pub fn cubes(mut self) -> Vec<Cube> {
+        if self.solver.propagate_at_root() {
+            let mut cube = vec![];
+            self.split(&mut cube);
+        }
+        self.solver.backtrack_to(0);
+        self.cubes
+    }
+
+    /// Splits the node of the decision tree identified by `cube`. All the literals of the cube
+    /// have been decided and propagated (without conflict) when this method is called.
+    fn split(&mut self, cube: &mut Cube) {
+        let level = self.solver.decision_level();
+
+        if cube.len() >= self.depth {
+            self.cubes.push(cube.clone());
+            return;
+        }
+
+        let var = loop {
+            match self.select() {
+                Selection::Branch(var)   => break Some(var),
+                Selection::Complete      => { self.cubes.push(cube.clone()); break None; },
+                Selection::Refuted       => break None,
+                Selection::Implied(lit)  => {
+                    if !self.solver.probe(lit) { break None; }
+                }
+            }
+        };
+
+        if let Some(var) = var {
+            let base = self.solver.decision_level();
+            for &sign in [Sign::Positive, Sign::Negative].iter() {
+                let lit = Literal::from_var(var, sign);
+                cube.push(lit);
+                if self.solver.probe(lit) {
+                    self.split(cube);
+                }
+                self.solver.backtrack_to(base);
+                cube.pop();
+            }
+        }
+
+        self.solver.backtrack_to(level);
+    }
+
+    /// Looks ahead upon the most promising free variables and selects the one to branch on.
+    fn select(&mut self) -> Selection {
+        let mut free: Vec<(usize, Variable)> = (1..(self.solver.nb_vars()+1))
+            .map(Variable::from)
+            .filter(|&v| self.solver.get_value(Literal::from_var(v, Sign::Positive)) == Bool::Undef)
+            .map(|v| (self.nb_occurrences(v), v))
+            .collect();
+
+        if free.is_empty() { return Selection::Complete; }
+
+        // the variables occurring in more clauses are more likely to be good branching variables
+        free.sort_by_key(|&(count, _)| ::std::cmp::Reverse(count));
+        free.truncate(self.candidates);
+
+        let mut best = None;
+        for (_, var) in free {
+            let positive = Literal::from_var(var, Sign::Positive);
+            let pos = self.diff(positive);
+            let neg = self.diff(!positive);
+
+            let (pos, neg) = match (pos, neg) {
+                (None, None)         => return Selection::Refuted,
+                (None, Some(_))      => return Selection::Implied(!positive),
+                (Some(_), None)      => return Selection::Implied(positive),
+                (Some(p), Some(n))   => (p, n)
+            };
+
+            let score = 1024.0 * pos * neg + pos + neg;
+            match best {
+                Some((s, _)) if s >= score => { /* keep the best one */ },
+                _ => best = Some((score, var))
+            }
+        }
+
+        Selection::Branch(best.unwrap().1)
+    }
+
+    /// Returns the number of (problem) clauses in which the given variable occurs
+    fn nb_occurrences(&self, var: Variable) -> usize {
+        let positive = Literal::from_var(var, Sign::Positive);
+        self.occurs[positive].len() + self.occurs[!positive].len()
+    }
+
+    /// Propagates `lit` and weighs the clauses which have been reduced (but not satisfied) in the
+    /// process. None is returned when `lit` is a failed literal (its propagation yields a
+    /// conflict).
+    fn diff(&mut self, lit: Literal) -> Option<f64> {
+        let level = self.solver.decision_level();
+        let start = self.solver.trail_len();
+
+        if !self.solver.probe(lit) {
+            self.solver.backtrack_to(level);
+            return None;
+        }
+
+        self.stamp += 1;
+        let mut diff = 0.0;
+        for i in start..self.solver.trail_len() {
+            let falsified = !self.solver.trail_literal(i);
+
+            for &c in self.occurs[falsified].iter() {
+                if self.stamps[c] == self.stamp { continue; }
+                self.stamps[c] = self.stamp;
+
+                let clause = &self.solver.clauses[self.clauses[c]];
+                if clause.iter().any(|&l| self.solver.get_value(l) == Bool::True) { continue; }
+
+                let free = clause.iter().filter(|&&l| self.solver.get_value(l) == Bool::Undef).count();
+                if free >= 2 {
+                    diff += REDUCED_WEIGHT.powi(free as i32 - 2);
+                }
+            }
+        }
+
+        self.solver.backtrack_to(level);
+        Some(diff)
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Cube and Conquer
+/// Solves a problem which has been split into cubes: the cubes are solved one after the other
+/// (under assumptions) by a pool of incremental solvers running in parallel. The solvers keep
+/// their learned clauses from one cube to the next, and they share the best of them with one
+/// another. The search stops as soon as one cube is found to be satisfiable.
+// -----------------------------------------------------------------------------------------------
+#[derive(Debug)]
+pub struct CubeAndConquer {
+    /// The solvers of the pool (all of them are loaded with the same problem)
+    solvers: Vec<Solver>,
+    /// The cubes to solve
+    cubes  : Vec<Cube>
+}
+
+impl CubeAndConquer {
+    /// Creates a cube and conquer search solving the given `cubes` with the given `solvers`
+    /// which must all have been loaded with the very same problem.
+    pub fn new(mut solvers: Vec<Solver>, cubes: Vec<Cube>) -> CubeAndConquer {
+        let exchanges = ClauseExchange::connect(solvers.len());
+        for (solver, exchange) in solvers.iter_mut().zip(exchanges) {
+            solver.exchange = Some(exchange);
+        }
+
+        CubeAndConquer { solvers, cubes }
+    }
+
+    /// Solves the cubes and returns the answer along with the solver which found it (ie: to
+    /// retrieve a model or statistics).
+    ///
+    /// # Return Value
+    /// true if there exist an assignment satisfying the problem and one of the cubes.
+    /// false if there exists no such assignment.
+    pub fn solve(self) -> (bool, Solver) {
+        let CubeAndConquer { solvers, cubes } = self;
+
+        let cubes     = Arc::new(cubes);
+        let next      = Arc::new(AtomicUsize::new(0));
+        let terminate = Arc::new(AtomicBool::new(false));
+
+        let handles: Vec<_> = solvers.into_iter()
+            .map(|mut solver| {
+                let cubes     = Arc::clone(&cubes);
+                let next      = Arc::clone(&next);
+                let terminate = Arc::clone(&terminate);
+                solver.terminate = Some(Arc::clone(&terminate));
+
+                thread::spawn(move || {
+                    loop {
+                        let i = next.fetch_add(1, Ordering::SeqCst);
+                        if i >= cubes.len() { return (false, solver); }
+
+                        match solver.try_solve_with_assumptions(&cubes[i]) {
+                            // some other solver found the answer
+                            None        => return (false, solver),
+                            // the answer is found: the problem is satisfiable
+                            Some(true)  => {
+                                let winner = !terminate.swap(true, Ordering::SeqCst);
+                                return (winner, solver);
+                            },
+                            // the problem is unsat regardless of the cube: stop everything
+                            Some(false) if solver.failed_assumptions().is_empty() => {
+                                terminate.store(true, Ordering::SeqCst);
+                                return (false, solver);
+                            },
+                            Some(false) => { /* on to the next cube */ }
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let mut answer = None;
+        for handle in handles {
+            let (winner, solver) = handle.join().expect("a solver of the pool panicked");
+            // keep the first solver unless an other one found a model
+            if winner || answer.is_none() {
+                answer = Some((winner, solver));
+            }
+        }
+
+        answer.expect("the pool comprises at least one solver")
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Unit Tests
+// -----------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fixtures::pigeon_hole;
+
+    #[test]
+    fn lookahead_produces_cubes_of_bounded_depth() {
+        let mut solver = pigeon_hole(5, 4);
+        let cubes = Lookahead::new(&mut solver, 3).cubes();
+
+        assert!(!cubes.is_empty());
+        assert!(cubes.iter().all(|c| c.len() <= 3));
+        // the solver is left at the root level
+        assert_eq!(0, solver.decision_level());
+    }
+
+    #[test]
+    fn lookahead_with_depth_zero_produces_the_empty_cube() {
+        let mut solver = pigeon_hole(3, 3);
+        let cubes = Lookahead::new(&mut solver, 0).cubes();
+
+        assert_eq!(cubes, vec![vec![]]);
+    }
+
+    #[test]
+    fn lookahead_cubes_partition_the_search_space() {
+        let mut solver = pigeon_hole(4, 4);
+        let cubes = Lookahead::new(&mut solver, 4).cubes();
+
+        // no two cubes can hold together: each of them disagrees with the others on some literal
+        for (i, c1) in cubes.iter().enumerate() {
+            for c2 in cubes.iter().skip(i+1) {
+                assert!(c1.iter().any(|l| c2.contains(&!*l)));
+            }
+        }
+    }
+
+    #[test]
+    fn lookahead_refutes_trivially_unsat_problems() {
+        let mut solver = Solver::new(2);
+        solver.add_problem_clause(&mut vec![ 1, 2]).unwrap();
+        solver.add_problem_clause(&mut vec![ 1,-2]).unwrap();
+        solver.add_problem_clause(&mut vec![-1, 2]).unwrap();
+        solver.add_problem_clause(&mut vec![-1,-2]).unwrap();
+
+        let cubes = Lookahead::new(&mut solver, 5).cubes();
+        assert!(cubes.is_empty());
+    }
+
+    #[test]
+    fn lookahead_detects_failed_literals() {
+        let mut solver = Solver::new(3);
+        // 1 is a failed literal
+        solver.add_problem_clause(&mut vec![-1, 2]).unwrap();
+        solver.add_problem_clause(&mut vec![-1,-2]).unwrap();
+        solver.add_problem_clause(&mut vec![ 1, 3]).unwrap();
+
+        let cubes = Lookahead::new(&mut solver, 1).cubes();
+        for cube in cubes {
+            assert!(!cube.contains(&lit(1)));
+        }
+    }
+
+    #[test]
+    fn conquer_finds_a_model() {
+        let mut solver = pigeon_hole(5, 5);
+        let cubes = Lookahead::new(&mut solver, 3).cubes();
+        let solvers = vec![solver, pigeon_hole(5, 5)];
+
+        let (satisfiable, solver) = CubeAndConquer::new(solvers, cubes).solve();
+        assert!(satisfiable);
+        for p in 0..5 {
+            let holes = (0..5).filter(|h| solver.get_value(lit(1 + p * 5 + h)) == Bool::True).count();
+            assert_eq!(holes, 1);
+        }
+    }
+
+    #[test]
+    fn conquer_proves_unsatisfiability() {
+        let mut solver = pigeon_hole(6, 5);
+        let cubes = Lookahead::new(&mut solver, 3).cubes();
+        let solvers = vec![solver, pigeon_hole(6, 5), pigeon_hole(6, 5)];
+
+        let (satisfiable, _) = CubeAndConquer::new(solvers, cubes).solve();
+        assert!(!satisfiable);
+    }
+
+    #[test]
+    fn conquer_without_cube_is_unsat() {
+        let (satisfiable, _) = CubeAndConquer::new(vec![pigeon_hole(2, 2)], vec![]).solve();
+        assert!(!satisfiable);
+    }
+}
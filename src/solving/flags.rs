@@ -11,7 +11,8 @@ pub enum Flag {
     IsMarked           =  2, //--> used during conflict analysis
     IsImplied          =  4, //--> forced by the ongoing conflict clause
     IsNotImplied       =  8, //--> not forced but effectively analysed
-    IsInConflictClause = 16  //--> makes it easy to retrieve the backjump point
+    IsInConflictClause = 16, //--> makes it easy to retrieve the backjump point
+    IsInBlock          = 32  //--> used while shrinking the learned clause
 }
 
 /// The `Flags` newtype, as its name suggests, serves the point of collecting the various flags that
@@ -128,6 +129,9 @@ impl Debug for Flags {
         if self.is_set(Flag::IsInConflictClause) {
             s = format!("{} IsInConflictClause", s);
         }
+        if self.is_set(Flag::IsInBlock) {
+            s = format!("{} IsInBlock", s);
+        }
 
         return write!(f, "Flags({} )", s);
     }
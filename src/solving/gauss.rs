@@ -0,0 +1,666 @@
+extern crate fixedbitset;
+
+use std::collections::HashMap;
+
+use core::*;
+
+use self::fixedbitset::FixedBitSet;
+
+/// The largest XOR constraints looked for among the clauses (an XOR over k variables is encoded
+/// with 2^(k-1) clauses).
+const MAX_DETECTED_XOR_SIZE: usize = 6;
+
+// -----------------------------------------------------------------------------------------------
+/// # Xor
+/// An XOR constraint: the exclusive or of its variables must be equal to its `parity`.
+// -----------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Xor {
+    /// The variables of the constraint (sorted, without duplicates)
+    pub vars  : Vec<Variable>,
+    /// The value of the exclusive or of the variables
+    pub parity: bool
+}
+
+impl Xor {
+    /// Creates the XOR constraint requiring the exclusive or of the given `literals` to hold (as
+    /// the `x` lines of the CryptoMiniSat DIMACS dialect). Every negative literal flips the parity
+    /// and the variables occurring twice cancel out.
+    pub fn new(literals: &[Literal]) -> Xor {
+        let mut parity = true;
+        let mut vars: Vec<Variable> = literals.iter()
+            .map(|l| {
+                if let Sign::Negative = l.sign() { parity = !parity; }
+                l.var()
+            })
+            .collect();
+
+        vars.sort_unstable_by_key(|&v| usize::from(v));
+        let mut distinct: Vec<Variable> = Vec::with_capacity(vars.len());
+        for v in vars {
+            if distinct.last() == Some(&v) { distinct.pop(); } else { distinct.push(v); }
+        }
+
+        Xor { vars: distinct, parity }
+    }
+
+    /// Returns the clauses encoding this constraint: one clause forbidding each assignment of
+    /// the variables whose parity is wrong. There are 2^(k-1) of them for k variables.
+    pub fn clauses(&self) -> Vec<Vec<Literal>> {
+        let n = self.vars.len();
+        (0_u64..1 << n)
+            .filter(|bits| (bits.count_ones() % 2 == 1) != self.parity)
+            .map(|bits| self.vars.iter().enumerate()
+                .map(|(i, &v)| {
+                    let sign = if bits & (1 << i) != 0 { Sign::Negative } else { Sign::Positive };
+                    Literal::from_var(v, sign)
+                })
+                .collect())
+            .collect()
+    }
+}
+
+/// Finds the XOR constraints which are encoded (with one clause per forbidden assignment) among
+/// the given `clauses`. Only the constraints having 3 to `MAX_DETECTED_XOR_SIZE` variables are
+/// looked for.
+pub fn find_xors(clauses: &[Vec<Literal>]) -> Vec<Xor> {
+    // the sign patterns (bit i is set when the i-th variable is negative) found for each set of
+    // variables
+    let mut patterns: HashMap<Vec<usize>, u64> = HashMap::new();
+
+    for clause in clauses.iter() {
+        if clause.len() < 3 || clause.len() > MAX_DETECTED_XOR_SIZE { continue; }
+
+        let mut literals = clause.clone();
+        literals.sort_unstable_by_key(|l| usize::from(l.var()));
+        if literals.windows(2).any(|w| w[0].var() == w[1].var()) { continue; }
+
+        let vars: Vec<usize> = literals.iter().map(|l| usize::from(l.var())).collect();
+        let pattern = literals.iter().enumerate()
+            .filter(|&(_, l)| matches!(l.sign(), Sign::Negative))
+            .fold(0_u64, |p, (i, _)| p | (1 << i));
+
+        *patterns.entry(vars).or_insert(0) |= 1 << pattern;
+    }
+
+    let mut xors = vec![];
+    for (vars, found) in patterns.iter() {
+        let n = vars.len();
+        // the clause having the sign pattern p forbids the assignment in which exactly the
+        // negative variables are true: all the clauses having an even number of negative
+        // literals encode an odd parity, and the other ones encode an even parity
+        for &parity in [true, false].iter() {
+            let complete = (0_u64..1 << n)
+                .filter(|p| (p.count_ones() % 2 == 0) == parity)
+                .all(|p| found & (1 << p) != 0);
+
+            if complete {
+                let vars = vars.iter().map(|&v| Variable::from(v)).collect();
+                xors.push(Xor { vars, parity });
+            }
+        }
+    }
+
+    // (for the result not to depend on the iteration order of the map)
+    xors.sort_by_key(|x| x.vars.iter().map(|&v| usize::from(v)).collect::<Vec<usize>>());
+    xors
+}
+
+/// A row of the matrix: an XOR constraint over the columns of the matrix
+#[derive(Debug, Clone, Default)]
+struct Row {
+    /// The columns of the variables of the constraint
+    bits   : FixedBitSet,
+    /// The value of the exclusive or of the variables
+    parity : bool
+}
+
+impl Row {
+    /// Adds the `other` row to this one (modulo 2)
+    fn add(&mut self, other: &Row) {
+        for (a, b) in self.bits.as_mut_slice().iter_mut().zip(other.bits.as_slice().iter()) {
+            *a ^= *b;
+        }
+        self.parity ^= other.parity;
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Gauss
+/// A Gauss-Jordan elimination engine reasoning on a system of XOR constraints. Every constraint
+/// is a row of a matrix over GF(2) whose columns are the variables of the constraints.
+///
+/// The matrix is kept in reduced row echelon form: each row has a basic column which occurs in
+/// no other row. As in the two watched literals scheme, each row watches two unassigned columns:
+/// its basic column and one other column. When the basic column of a row gets assigned, another
+/// unassigned column of the row becomes basic (it is eliminated from the other rows). When the
+/// other watched column gets assigned, another one is looked for. When there is none, the basic
+/// variable is implied by the row, or the row is a conflict when the basic variable is assigned
+/// as well and the parity is wrong. Both are explained by the clause forbidding the current
+/// assignment of the variables of the row (which is a linear combination of the constraints).
+///
+/// The echelon form holds whatever the assignment: nothing but the watches needs repairing when
+/// the solver backtracks. The matrix is only eliminated from scratch when constraints are added
+/// or removed.
+///
+/// See `Gaussian Elimination with Boolean Constraint Propagation` -- Han, Jiang (CAV 2012).
+// -----------------------------------------------------------------------------------------------
+#[derive(Debug, Clone, Default)]
+pub struct Gauss {
+    /// The column of each variable (if it occurs in some constraint)
+    columns     : Vec<Option<usize>>,
+    /// The variable of each column
+    vars        : Vec<Variable>,
+    /// The constraints of the system, along with whether each of them is also encoded with
+    /// clauses of the problem
+    constraints : Vec<(Xor, bool)>,
+    /// The rows of the matrix (a basis of the constraints, in reduced row echelon form)
+    rows        : Vec<Row>,
+    /// Whether the rows are up to date with the constraints
+    eliminated  : bool,
+    /// Whether the constraints are inconsistent (some linear combination of them is 0 = 1)
+    inconsistent: bool,
+    /// The basic column of each row
+    basic       : Vec<usize>,
+    /// The row whose basic column is the given column (if any)
+    basic_row   : Vec<Option<usize>>,
+    /// The other column watched by each row (if any)
+    watched     : Vec<Option<usize>>,
+    /// The rows watching each column. This list is lazily cleaned: a row is only watching the
+    /// column if `watched` still tells so.
+    watchers    : Vec<Vec<usize>>,
+    /// The columns which were assigned (as notified by `assign`)
+    assigned    : FixedBitSet,
+    /// The columns which were assigned true
+    truth       : FixedBitSet,
+    /// The rows whose watches must be updated
+    pending     : Vec<usize>,
+    /// Whether the assignment was partially undone since the last propagation
+    backtracked : bool,
+    /// The clause explaining the last implication of the variable of each column. The implied
+    /// literal comes first, the other literals are false.
+    explanations: Vec<Vec<Literal>>
+}
+
+impl Gauss {
+    /// Creates an engine having no constraint
+    pub fn new() -> Gauss { Gauss::default() }
+
+    /// Returns the number of XOR constraints of the system
+    #[inline]
+    pub fn len(&self) -> usize { self.constraints.len() }
+
+    /// Returns true iff the system has no constraint
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.constraints.is_empty() }
+
+    /// Adds the given constraint to the system. The constraint is `encoded` when the problem
+    /// comprises the clauses encoding it as well (ie: when it was found by `find_xors`).
+    pub fn add(&mut self, xor: &Xor, encoded: bool) {
+        for &v in xor.vars.iter() {
+            self.column(v);
+        }
+        self.constraints.push((xor.clone(), encoded));
+        self.eliminated = false;
+    }
+
+    /// Removes the constraints in which the given variable occurs from the system and returns
+    /// their number. The columns of the variables which no longer occur in any constraint are
+    /// dropped along with them.
+    pub fn remove(&mut self, v: Variable) -> usize {
+        let index = usize::from(v);
+        if self.columns.get(index).is_none_or(|c| c.is_none()) { return 0; }
+
+        let old = ::std::mem::take(self);
+        let mut removed = 0;
+        for (xor, encoded) in old.constraints.iter() {
+            if xor.vars.contains(&v) {
+                removed += 1;
+            } else {
+                self.add(xor, *encoded);
+            }
+        }
+        removed
+    }
+
+    /// Returns the XOR constraints of the system which are not encoded with clauses
+    pub fn xors(&self) -> Vec<Xor> {
+        self.constraints.iter()
+            .filter(|&&(_, encoded)| !encoded)
+            .map(|(xor, _)| xor.clone())
+            .collect()
+    }
+
+    /// Returns the column of the given variable (which is created when needed)
+    fn column(&mut self, v: Variable) -> usize {
+        let index = usize::from(v);
+        if self.columns.len() <= index {
+            self.columns.resize(index + 1, None);
+        }
+        match self.columns[index] {
+            Some(column) => column,
+            None         => {
+                let column = self.vars.len();
+                self.columns[index] = Some(column);
+                self.vars.push(v);
+                self.explanations.push(vec![]);
+                column
+            }
+        }
+    }
+
+    /// Notifies the engine that the given literal was assigned true. The assignments must be
+    /// notified in the order of the trail, and before `propagate` is called.
+    pub fn assign(&mut self, literal: Literal) {
+        // (the assignment is read anew after an elimination or a backtrack)
+        if !self.eliminated || self.backtracked { return; }
+
+        let column = match self.columns.get(usize::from(literal.var())) {
+            Some(&Some(column)) => column,
+            _                   => return
+        };
+        self.assigned.insert(column);
+        self.truth.set(column, matches!(literal.sign(), Sign::Positive));
+
+        if let Some(r) = self.basic_row[column] {
+            self.pending.push(r);
+        }
+        let watched = &self.watched;
+        self.pending.extend(self.watchers[column].drain(..).filter(|&r| watched[r] == Some(column)));
+    }
+
+    /// Notifies the engine that some assignments were undone. The watches are repaired upon the
+    /// next propagation.
+    pub fn backtrack(&mut self) {
+        self.backtracked = true;
+        // (the pending rows may no longer be in the watchers list of the column they watch)
+        for r in self.pending.drain(..) {
+            self.watched[r] = None;
+        }
+    }
+
+    /// Propagates the assignments notified so far. The `value` of each variable is only read
+    /// when the assignment was partially undone since the last propagation.
+    ///
+    /// # Return Value
+    /// Ok with the next literal implied by the system (whose explanation is then available
+    /// through `explanation`) or None when there is none, or Err with a clause whose literals are
+    /// all false when the system is violated by the assignment. The implied literal must be
+    /// assigned (and notified) before the propagation resumes.
+    pub fn propagate<F>(&mut self, value: F) -> Result<Option<Literal>, Vec<Literal>>
+        where F: Fn(Variable) -> Bool {
+
+        if !self.eliminated {
+            self.eliminate();
+        }
+        if self.inconsistent {
+            return Err(vec![]);
+        }
+        if self.backtracked {
+            self.reset(value);
+        }
+
+        while let Some(r) = self.pending.pop() {
+            let implied = self.update(r)?;
+            if implied.is_some() {
+                return Ok(implied);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the clause explaining the last implication of the given variable (the implied
+    /// literal first, followed by the false literals implying it).
+    ///
+    /// # Panics
+    /// - when `v` does not occur in any constraint
+    pub fn explanation(&self, v: Variable) -> &[Literal] {
+        let column = self.columns[usize::from(v)].expect("the variable occurs in no XOR constraint");
+        &self.explanations[column]
+    }
+
+    /// Brings the rows made of the constraints to their reduced row echelon form (the redundant
+    /// rows are dropped).
+    fn eliminate(&mut self) {
+        let width = self.vars.len();
+        self.rows.clear();
+        self.basic.clear();
+        self.inconsistent = false;
+        for (xor, _) in self.constraints.iter() {
+            let mut bits = FixedBitSet::with_capacity(width);
+            for &v in xor.vars.iter() {
+                bits.insert(self.columns[usize::from(v)].unwrap());
+            }
+            self.rows.push(Row { bits, parity: xor.parity });
+        }
+
+        let mut r = 0;
+        while r < self.rows.len() {
+            match self.rows[r].bits.ones().next() {
+                None => {
+                    self.inconsistent |= self.rows[r].parity;
+                    self.rows.swap_remove(r);
+                },
+                Some(column) => {
+                    self.basic.push(column);
+                    self.eliminate_column(r, column);
+                    r += 1;
+                }
+            }
+        }
+
+        self.basic_row = vec![None; width];
+        for (r, &column) in self.basic.iter().enumerate() {
+            self.basic_row[column] = Some(r);
+        }
+        self.assigned = FixedBitSet::with_capacity(width);
+        self.truth    = FixedBitSet::with_capacity(width);
+        self.watched  = vec![None; self.rows.len()];
+        self.watchers = vec![vec![]; width];
+        self.pending.clear();
+        self.eliminated  = true;
+        self.backtracked = true;
+    }
+
+    /// Reads the assignment anew and schedules the update of the rows whose watches are no longer
+    /// both unassigned.
+    fn reset<F>(&mut self, value: F) where F: Fn(Variable) -> Bool {
+        self.assigned.clear();
+        self.truth.clear();
+        for (c, &v) in self.vars.iter().enumerate() {
+            match value(v) {
+                Bool::Undef => {},
+                Bool::True  => { self.assigned.insert(c); self.truth.insert(c); },
+                Bool::False => { self.assigned.insert(c); }
+            }
+        }
+
+        for r in (0..self.rows.len()).rev() {
+            let watching = self.watched[r].is_some_and(|c| !self.assigned.contains(c));
+            if !watching || self.assigned.contains(self.basic[r]) {
+                self.pending.push(r);
+            }
+        }
+        self.backtracked = false;
+    }
+
+    /// Adds the row `r` to all the other rows comprising the given column. The watches of these
+    /// rows must then be updated.
+    fn eliminate_column(&mut self, r: usize, column: usize) {
+        // (the pivot row is left empty meanwhile)
+        let pivot = ::std::mem::take(&mut self.rows[r]);
+        for (i, row) in self.rows.iter_mut().enumerate() {
+            if row.bits.contains(column) {
+                row.add(&pivot);
+                self.pending.push(i);
+            }
+        }
+        self.rows[r] = pivot;
+    }
+
+    /// Restores the watches of the row `r` after one of them was assigned (or the row changed).
+    ///
+    /// # Return Value
+    /// Ok with the basic literal of the row when the row implies it, or Err with the conflicting
+    /// clause when the row is violated.
+    fn update(&mut self, r: usize) -> Result<Option<Literal>, Vec<Literal>> {
+        if self.assigned.contains(self.basic[r]) {
+            match self.unassigned_column(r, None) {
+                Some(column) => self.pivot(r, column),
+                None         => {
+                    self.watched[r] = None;
+                    return if self.parity_of(r) != self.rows[r].parity {
+                        Err(self.falsified(r, None))
+                    } else {
+                        Ok(None)
+                    };
+                }
+            }
+        }
+
+        let basic = self.basic[r];
+        if let Some(column) = self.watched[r] {
+            if column != basic && self.rows[r].bits.contains(column) && !self.assigned.contains(column) {
+                return Ok(None);
+            }
+        }
+
+        match self.unassigned_column(r, Some(basic)) {
+            Some(column) => {
+                self.watched[r] = Some(column);
+                self.watchers[column].push(r);
+                Ok(None)
+            },
+            None => {
+                self.watched[r] = None;
+                let sign = if self.parity_of(r) != self.rows[r].parity { Sign::Positive } else { Sign::Negative };
+                let literal = Literal::from_var(self.vars[basic], sign);
+                self.explanations[basic] = self.falsified(r, Some(literal));
+                Ok(Some(literal))
+            }
+        }
+    }
+
+    /// Makes `column` the basic column of the row `r`: it is eliminated from the other rows,
+    /// whose watches must then be updated.
+    fn pivot(&mut self, r: usize, column: usize) {
+        self.basic_row[self.basic[r]] = None;
+        self.basic[r] = column;
+        self.basic_row[column] = Some(r);
+
+        self.eliminate_column(r, column);
+    }
+
+    /// Returns an unassigned column of the row `r` other than `except` (if any)
+    fn unassigned_column(&self, r: usize, except: Option<usize>) -> Option<usize> {
+        let bits = self.rows[r].bits.as_slice();
+        for (i, (&b, &a)) in bits.iter().zip(self.assigned.as_slice().iter()).enumerate() {
+            let mut word = b & !a;
+            while word != 0 {
+                let column = 32 * i + word.trailing_zeros() as usize;
+                if Some(column) != except { return Some(column); }
+                word &= word - 1;
+            }
+        }
+        None
+    }
+
+    /// Returns the parity of the number of true (assigned) variables of the row `r`
+    fn parity_of(&self, r: usize) -> bool {
+        let bits = self.rows[r].bits.as_slice();
+        let ones: u32 = bits.iter().zip(self.truth.as_slice().iter()).map(|(&b, &t)| (b & t).count_ones()).sum();
+        ones % 2 == 1
+    }
+
+    /// Returns the clause forbidding the current assignment of the variables of the row `r`:
+    /// the `implied` literal (when given) followed by the (false) literals of all the other
+    /// variables of the row.
+    fn falsified(&self, r: usize, implied: Option<Literal>) -> Vec<Literal> {
+        let mut clause: Vec<Literal> = implied.into_iter().collect();
+        for c in self.rows[r].bits.ones() {
+            let v = self.vars[c];
+            if implied.is_some_and(|l| l.var() == v) { continue; }
+
+            let sign = if self.truth.contains(c) { Sign::Negative } else { Sign::Positive };
+            clause.push(Literal::from_var(v, sign));
+        }
+        clause
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Unit Tests
+// -----------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literals(ls: &[iint]) -> Vec<Literal> {
+        ls.iter().map(|&l| lit(l)).collect()
+    }
+
+    /// Returns a valuation in which the given literals are true (and the others unassigned)
+    fn valuation(assigned: &[Literal]) -> impl Fn(Variable) -> Bool + '_ {
+        move |v| match assigned.iter().find(|l| l.var() == v) {
+            None    => Bool::Undef,
+            Some(l) => if let Sign::Positive = l.sign() { Bool::True } else { Bool::False }
+        }
+    }
+
+    /// Propagates the given assignment from scratch (as after a backtrack to the root) up to the
+    /// fixpoint, and returns the literals implied along the way.
+    fn propagate(gauss: &mut Gauss, ls: &[iint]) -> Result<Vec<Literal>, Vec<Literal>> {
+        gauss.backtrack();
+        let mut trail = literals(ls);
+        let mut implied = vec![];
+        while let Some(l) = gauss.propagate(valuation(&trail))? {
+            gauss.assign(l);
+            trail.push(l);
+            implied.push(l);
+        }
+        Ok(implied)
+    }
+
+    #[test]
+    fn negative_literals_flip_the_parity_and_duplicates_cancel_out() {
+        let xor = Xor::new(&literals(&[3, -1, 2, -3, 4, 4]));
+        assert_eq!(xor.vars, vec![var(1), var(2)]);
+        assert!(xor.parity);
+
+        assert!(!Xor::new(&literals(&[-1, 2])).parity);
+    }
+
+    #[test]
+    fn clauses_forbid_the_assignments_having_the_wrong_parity() {
+        let xor = Xor::new(&literals(&[1, 2]));
+        assert_eq!(xor.clauses(), vec![literals(&[1, 2]), literals(&[-1, -2])]);
+
+        let xor = Xor::new(&literals(&[1, 2, 3]));
+        assert_eq!(xor.clauses().len(), 4);
+        assert!(xor.clauses().iter().all(|c| c.iter().filter(|l| l.to_isize() < 0).count() % 2 == 0));
+    }
+
+    #[test]
+    fn find_xors_recognizes_the_clausal_encodings() {
+        let mut clauses = Xor::new(&literals(&[1, 2, 3])).clauses();
+        clauses.extend(Xor::new(&literals(&[-2, 4, 5, 6])).clauses());
+        // an incomplete encoding is not an xor
+        clauses.extend(Xor::new(&literals(&[7, 8, 9])).clauses().into_iter().skip(1));
+        clauses.push(literals(&[1, 4, 7]));
+
+        assert_eq!(find_xors(&clauses), vec![
+            Xor::new(&literals(&[1, 2, 3])),
+            Xor::new(&literals(&[-2, 4, 5, 6]))
+        ]);
+    }
+
+    #[test]
+    fn propagate_implies_the_last_unassigned_variable_of_a_row() {
+        let mut gauss = Gauss::new();
+        gauss.add(&Xor::new(&literals(&[1, 2, 3])), false);
+
+        assert_eq!(propagate(&mut gauss, &[1]), Ok(vec![]));
+        assert_eq!(propagate(&mut gauss, &[1, -3]), Ok(literals(&[-2])));
+        assert_eq!(gauss.explanation(var(2)), &literals(&[-2, -1, 3])[..]);
+    }
+
+    #[test]
+    fn propagate_combines_the_rows() {
+        let mut gauss = Gauss::new();
+        // x1 + x2 + x3 = 1 and x1 + x2 + x4 = 0 imply x3 + x4 = 1
+        gauss.add(&Xor::new(&literals(&[1, 2, 3])), false);
+        gauss.add(&Xor::new(&literals(&[1, 2, -4])), false);
+        assert_eq!(gauss.len(), 2);
+
+        assert_eq!(propagate(&mut gauss, &[3]), Ok(literals(&[-4])));
+        assert_eq!(gauss.explanation(var(4)), &literals(&[-4, -3])[..]);
+    }
+
+    #[test]
+    fn propagate_detects_the_conflicts() {
+        let mut gauss = Gauss::new();
+        gauss.add(&Xor::new(&literals(&[1, 2, 3])), false);
+        gauss.add(&Xor::new(&literals(&[2, 3, 4])), false);
+
+        // x1 + x4 = 0 is violated
+        let conflict = propagate(&mut gauss, &[1, -4]).unwrap_err();
+        assert_eq!(conflict, literals(&[-1, 4]));
+    }
+
+    #[test]
+    fn propagate_follows_the_notified_assignments() {
+        let mut gauss = Gauss::new();
+        gauss.add(&Xor::new(&literals(&[1, 2, 3])), false);
+        gauss.add(&Xor::new(&literals(&[3, 4, 5])), false);
+        gauss.add(&Xor::new(&literals(&[1, 5, -6])), false);
+        assert_eq!(propagate(&mut gauss, &[]), Ok(vec![]));
+
+        // the basic columns get assigned one after the other: the matrix is pivoted as it goes
+        let mut trail = vec![];
+        for &l in [1, 4].iter() {
+            trail.push(lit(l));
+            gauss.assign(lit(l));
+            assert_eq!(gauss.propagate(valuation(&trail)), Ok(None));
+        }
+        trail.push(lit(-2));
+        gauss.assign(lit(-2));
+        // x1 + x2 + x3 = 1 implies -3, then x3 + x4 + x5 = 1 implies -5 and x1 + x5 + x6 = 0 implies 6
+        let mut implied = vec![];
+        while let Some(l) = gauss.propagate(valuation(&trail)).unwrap() {
+            trail.push(l);
+            gauss.assign(l);
+            implied.push(l);
+        }
+        implied.sort_by_key(|l| usize::from(l.var()));
+        assert_eq!(implied, literals(&[-3, -5, 6]));
+
+        // the watches are repaired when the assignment is undone
+        trail.truncate(1);
+        gauss.backtrack();
+        assert_eq!(gauss.propagate(valuation(&trail)), Ok(None));
+        trail.push(lit(-6));
+        gauss.assign(lit(-6));
+        assert_eq!(gauss.propagate(valuation(&trail)), Ok(Some(lit(5))));
+        assert_eq!(gauss.explanation(var(5)), &literals(&[5, -1, 6])[..]);
+    }
+
+    #[test]
+    fn an_inconsistent_system_is_a_conflict_by_itself() {
+        let mut gauss = Gauss::new();
+        gauss.add(&Xor::new(&literals(&[1, 2, 3])), false);
+        gauss.add(&Xor::new(&literals(&[2, 3, 4])), false);
+        gauss.add(&Xor::new(&literals(&[1, 4])), false);
+        assert_eq!(propagate(&mut gauss, &[]), Err(vec![]));
+
+        // the system is eliminated anew when it changes
+        assert_eq!(gauss.remove(var(4)), 2);
+        assert_eq!(propagate(&mut gauss, &[1, 2]), Ok(literals(&[3])));
+    }
+
+    #[test]
+    fn xors_lists_the_constraints_which_are_not_encoded() {
+        let mut gauss = Gauss::new();
+        assert!(gauss.is_empty());
+        gauss.add(&Xor::new(&literals(&[4, -1])), false);
+        gauss.add(&Xor::new(&literals(&[2, 3, 4])), false);
+        gauss.add(&Xor::new(&literals(&[1, 2, 3])), true);
+
+        assert_eq!(gauss.len(), 3);
+        assert_eq!(gauss.xors(), vec![Xor::new(&literals(&[4, -1])), Xor::new(&literals(&[2, 3, 4]))]);
+    }
+
+    #[test]
+    fn remove_drops_the_constraints_of_a_variable() {
+        let mut gauss = Gauss::new();
+        gauss.add(&Xor::new(&literals(&[1, 2, 5])), false);
+        gauss.add(&Xor::new(&literals(&[2, 3, -5])), false);
+        gauss.add(&Xor::new(&literals(&[3, 4])), true);
+
+        assert_eq!(gauss.remove(var(6)), 0);
+        assert_eq!(gauss.remove(var(5)), 2);
+        assert_eq!(gauss.len(), 1);
+        assert_eq!(gauss.vars, vec![var(3), var(4)]);
+        assert_eq!(propagate(&mut gauss, &[3]), Ok(literals(&[-4])));
+        assert_eq!(gauss.xors(), vec![]);
+    }
+}
@@ -62,6 +62,23 @@ impl BranchingHeuristic for ACIDS {
     fn pop_top(&mut self) -> Variable { self.heap.pop_top() }
 }
 
+impl ACIDS {
+    /// Returns the element with highest score without removing it from the heap.
+    ///
+    /// # Panics
+    /// - when one tries to peek an empty heap.
+    #[inline]
+    pub fn peek_top(&self) -> Variable { self.heap.peek_top() }
+
+    /// Returns the score currently associated with the given variable
+    #[inline]
+    pub fn score(&self, var: Variable) -> f64 { self.heap.get_score(var) }
+
+    /// Makes room for one more variable (which is placed on the heap) and returns it.
+    #[inline]
+    pub fn grow(&mut self) -> Variable { self.heap.grow(0.0) }
+}
+
 // -----------------------------------------------------------------------------------------------
 /// # Unit Tests
 // -----------------------------------------------------------------------------------------------
@@ -123,6 +140,25 @@ mod tests {
         tested.bump(Variable::from(MAX+2));
     }
 
+    #[test]
+    /// peek_top tells what is going to be popped next
+    fn peek_top_must_yield_the_next_item_to_be_popped(){
+        let mut tested = ACIDS::new(MAX);
+        tested.bump(Variable::from(50_u32));
+        assert_eq!(tested.peek_top(), Variable::from(50_u32));
+        assert_eq!(tested.pop_top(),  Variable::from(50_u32));
+    }
+
+    #[test]
+    /// score reflects the bumps of the variables
+    fn score_must_reflect_the_bumps(){
+        let mut tested = ACIDS::new(MAX);
+        assert_eq!(tested.score(Variable::from(50_u32)), 0.0);
+        tested.decay();
+        tested.bump(Variable::from(50_u32));
+        assert_eq!(tested.score(Variable::from(50_u32)), 1.0);
+    }
+
     #[test]
     /// bump changes the score, and adapts the position
     fn bump_must_update_the_score_and_position(){
@@ -1,4 +1,5 @@
 use core::*;
+use collections::BoundedQueue;
 
 /// Abstraction of a variable selection heuristic.
 pub trait BranchingHeuristic {
@@ -32,8 +33,9 @@ pub trait BranchingHeuristic {
 
 /// Abstraction of a restart strategy.
 pub trait RestartHeuristic {
-    /// Tells whether the solver should restart given it has already encountered `nb_conflicts`
-    fn should_restart(&self, mean: f64, queue: &Vec<u32>) -> bool;
+    /// Tells whether the solver should restart given the global `mean` lbd and the `queue` of the
+    /// most recent lbds
+    fn should_restart(&self, mean: f64, queue: &BoundedQueue) -> bool;
 
     /// Sets the next conflict limit before the next restart
     fn set_next_limit(&mut self);
@@ -41,7 +43,7 @@ pub trait RestartHeuristic {
 
 pub trait RestartHeuristic2 {
     /// Tells whether the solver should restart given it has already encountered `nb_conflicts`
-    fn should_restart(&self, mean: usize, queue: &Vec<u32>) -> bool;
+    fn should_restart(&self, mean: usize, queue: &BoundedQueue) -> bool;
 
     /// Sets the next conflict limit before the next restart
     fn set_next_limit(&mut self);
@@ -0,0 +1,133 @@
+use collections::BoundedQueue;
+use solving::heuristics::RestartHeuristic2;
+
+/// This structure encapsulates the restart strategy of the solver.
+/// It is implemented using CaDiCaL's exponential moving averages of the lbd: a fast moving average
+/// tracks the quality of the most recently learned clauses while a slow one tracks the long term
+/// quality. A restart is triggered whenever the recent clauses become significantly worse than
+/// the long term average. Unlike `Glucose`, it does not need to remember a window of lbds.
+///
+/// See `Evaluating CDCL Restart Schemes` -- Biere, Froehlich (POS 2015).
+#[derive(Debug)]
+pub struct Ema {
+    /// The moving average of the lbd of the most recently learned clauses
+    fast  : MovingAverage,
+    /// The moving average of the lbd over the long run
+    slow  : MovingAverage,
+    /// How much the fast average must exceed the slow one for a restart to be triggered
+    margin: f64,
+    /// The minimum number of conflicts between two restarts
+    min_conflicts: usize
+}
+
+impl RestartHeuristic2 for Ema {
+    /// Tells whether the solver should restart given it has already encountered `nb_conflicts`
+    #[inline]
+    fn should_restart(&self, nb_conflict: usize, _queue: &BoundedQueue) -> bool {
+        nb_conflict >= self.min_conflicts && self.fast.value > self.margin * self.slow.value
+    }
+
+    /// Sets the next conflict limit before the next restart
+    #[inline]
+    fn set_next_limit(&mut self) {}
+}
+
+impl Ema {
+    /// Creates a new instance
+    pub fn new() -> Ema {
+        Ema {
+            fast  : MovingAverage::new(3e-2),
+            slow  : MovingAverage::new(1e-5),
+            margin: 1.1,
+            min_conflicts: 2
+        }
+    }
+
+    /// Updates both moving averages with the lbd of a newly learned clause
+    #[inline]
+    pub fn update(&mut self, lbd: u32) {
+        self.fast.update(f64::from(lbd));
+        self.slow.update(f64::from(lbd));
+    }
+}
+
+impl Default for Ema {
+    fn default() -> Ema { Ema::new() }
+}
+
+/// An exponential moving average whose initialization bias is corrected (otherwise, the average
+/// of a slow moving average would remain close to zero for a very long time).
+#[derive(Debug)]
+struct MovingAverage {
+    /// The bias corrected average
+    value : f64,
+    /// The actual exponential moving average (biased towards zero)
+    biased: f64,
+    /// The weight given to each new sample
+    alpha : f64,
+    /// (1 - alpha)^t where t is the number of samples seen so far
+    exp   : f64
+}
+
+impl MovingAverage {
+    fn new(alpha: f64) -> MovingAverage {
+        MovingAverage { value: 0.0, biased: 0.0, alpha, exp: 1.0 }
+    }
+
+    #[inline]
+    fn update(&mut self, sample: f64) {
+        self.biased += self.alpha * (sample - self.biased);
+        self.exp    *= 1.0 - self.alpha;
+        self.value   = self.biased / (1.0 - self.exp);
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Unit Tests
+// -----------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moving_average_is_not_biased_towards_zero() {
+        let mut tested = MovingAverage::new(1e-5);
+        tested.update(4.0);
+        assert!((tested.value - 4.0).abs() < 1e-9);
+        tested.update(4.0);
+        assert!((tested.value - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn no_restart_while_lbd_is_stable() {
+        let mut tested = Ema::new();
+        for _ in 0..1000 {
+            tested.update(5);
+        }
+        assert!(!tested.should_restart(1000, &BoundedQueue::new(0)));
+    }
+
+    #[test]
+    fn restart_when_recent_lbds_get_worse() {
+        let mut tested = Ema::new();
+        for _ in 0..1000 {
+            tested.update(5);
+        }
+        for _ in 0..50 {
+            tested.update(20);
+        }
+        assert!(tested.should_restart(50, &BoundedQueue::new(0)));
+    }
+
+    #[test]
+    fn no_restart_before_min_conflicts() {
+        let mut tested = Ema::new();
+        for _ in 0..1000 {
+            tested.update(5);
+        }
+        for _ in 0..50 {
+            tested.update(20);
+        }
+        assert!(!tested.should_restart(1, &BoundedQueue::new(0)));
+    }
+}
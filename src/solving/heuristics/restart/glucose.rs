@@ -1,3 +1,4 @@
+use collections::BoundedQueue;
 use solving::heuristics::RestartHeuristic;
 
 /// This structure encapsulates the restart strategy of the solver.
@@ -10,10 +11,9 @@ pub struct Glucose {
 
 impl RestartHeuristic for Glucose {
     #[inline]
-    fn should_restart(&self, avg_glob: f64, queue: &Vec<u32>) -> bool {
+    fn should_restart(&self, avg_glob: f64, queue: &BoundedQueue) -> bool {
         if queue.len() < self.x { return false }
-        let sum: f64 = queue.iter().map(|&v| v as f64).sum();
-        sum/queue.len() as f64 * self.k > avg_glob as f64
+        queue.average() * self.k > avg_glob
     }
 
     /// Sets the next conflict limit before the next restart
@@ -37,15 +37,23 @@ impl Glucose {
 mod tests {
     use super::*;
 
+    fn queue(values: &[u32]) -> BoundedQueue {
+        let mut queue = BoundedQueue::new(100);
+        for &v in values {
+            queue.push(v);
+        }
+        queue
+    }
+
     #[test]
     fn glucose_restart_fn() {
         let tested = Glucose::new();
-        let _ret = tested.should_restart(1.0,&vec![1]);
-        assert_eq!(tested.should_restart(1.0,&vec![1]), false);
-        assert_eq!(tested.should_restart(1.0,&vec![1,1,1,1,1,1,1,1,1,1,1,1,1,1]), false);
-        assert_eq!(tested.should_restart(1.0,&vec![2,2,2]), false);
-        assert_eq!(tested.should_restart(1.0,&vec![100]), false);
-        assert_eq!(tested.should_restart(1.0,&vec![101]), false);
+        let _ret = tested.should_restart(1.0,&queue(&[1]));
+        assert_eq!(tested.should_restart(1.0,&queue(&[1])), false);
+        assert_eq!(tested.should_restart(1.0,&queue(&[1,1,1,1,1,1,1,1,1,1,1,1,1,1])), false);
+        assert_eq!(tested.should_restart(1.0,&queue(&[2,2,2])), false);
+        assert_eq!(tested.should_restart(1.0,&queue(&[100])), false);
+        assert_eq!(tested.should_restart(1.0,&queue(&[101])), false);
         let mut vec1 = Vec::with_capacity(100);
         let mut vec2 = Vec::with_capacity(100);
         for i in 1..100 {
@@ -54,14 +62,14 @@ mod tests {
         }
         vec2.push(100);
 
-        assert_eq!(tested.should_restart(1.0,&vec1), false);
-        assert_eq!(tested.should_restart(1.0,&vec2), true);
-        assert_eq!(tested.should_restart(1.0,&vec2), true);
-        assert_eq!(tested.should_restart(100.0,&vec2), false);
-        assert_eq!(tested.should_restart(10.0,&vec2), true);
-        assert_eq!(tested.should_restart(50.0,&vec2), false);
-        assert_eq!(tested.should_restart(35.3,&vec2), true);
-        assert_eq!(tested.should_restart(35.4,&vec2), false);
+        assert_eq!(tested.should_restart(1.0,&queue(&vec1)), false);
+        assert_eq!(tested.should_restart(1.0,&queue(&vec2)), true);
+        assert_eq!(tested.should_restart(1.0,&queue(&vec2)), true);
+        assert_eq!(tested.should_restart(100.0,&queue(&vec2)), false);
+        assert_eq!(tested.should_restart(10.0,&queue(&vec2)), true);
+        assert_eq!(tested.should_restart(50.0,&queue(&vec2)), false);
+        assert_eq!(tested.should_restart(35.3,&queue(&vec2)), true);
+        assert_eq!(tested.should_restart(35.4,&queue(&vec2)), false);
 
     }
 
@@ -84,36 +92,18 @@ mod tests {
     #[test]
     fn remove_fifo() {
         let glucose_size = 100;
-        let mut lbd_vec: Vec<u32> = Vec::with_capacity(glucose_size);
-        let mut nb_learned = 0;
+        let mut lbd_queue = BoundedQueue::new(glucose_size);
         for i in 0..100 {
-            lbd_vec.push(i);
-            nb_learned += 1;
-            let mut get = 0;
-            match lbd_vec.get(i as usize) {
-                None => { /* nothing to do */ },
-                Some(r) => {
-                    get = *r;
-                }
-            }
-            assert_eq!(lbd_vec.len(), (i+1) as usize);
-            assert_eq!(get,i);
+            lbd_queue.push(i);
+            assert_eq!(lbd_queue.len(), (i+1) as usize);
+            assert_eq!(lbd_queue.iter().last(), Some(&i));
         }
-        for i in 0..49{
-            lbd_vec.push(50+i);
-            nb_learned += 1;
-            if lbd_vec.len() > glucose_size {
-                lbd_vec.swap_remove(nb_learned % (glucose_size+1));
-            }
-            let mut get = 0;
-            match lbd_vec.get(i as usize) {
-                None => { /* nothing to do */ },
-                Some(r) => {
-                    get = *r;
-                }
-            }
-            assert_eq!(lbd_vec.len(),100);
-            assert_eq!(get,50+i)
+        for i in 0..49 {
+            lbd_queue.push(100+i);
+            assert_eq!(lbd_queue.len(), 100);
+            // the oldest lbd is evicted first
+            assert_eq!(lbd_queue.iter().next(), Some(&(i+1)));
+            assert_eq!(lbd_queue.iter().last(), Some(&(100+i)));
         }
     }
 }
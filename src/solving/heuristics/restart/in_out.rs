@@ -1,3 +1,4 @@
+use collections::BoundedQueue;
 use solving::heuristics::RestartHeuristic2;
 
 /// This structure encapsulates the restart strategy of the solver.
@@ -14,7 +15,7 @@ pub struct InOut {
 impl RestartHeuristic2 for InOut {
     /// Tells whether the solver should restart given it has already encountered `nb_conflicts`
     #[inline]
-    fn should_restart(&self, nb_conflict: usize, _queue: &Vec<u32>) -> bool {
+    fn should_restart(&self, nb_conflict: usize, _queue: &BoundedQueue) -> bool {
         return nb_conflict == self.conflicts;
     }
 
@@ -59,33 +60,33 @@ mod tests {
     #[test]
     fn in_out_restart() {
         let mut tested = InOut::new();
-        assert_eq!(tested.should_restart(50, &vec![]), false);
-        assert_eq!(tested.should_restart(99, &vec![]), false);
-        assert_eq!(tested.should_restart(100, &vec![]), true);
-        assert_eq!(tested.should_restart(101, &vec![]), false);
+        assert_eq!(tested.should_restart(50, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(99, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(100, &BoundedQueue::new(0)), true);
+        assert_eq!(tested.should_restart(101, &BoundedQueue::new(0)), false);
 
         tested.set_next_limit();
 
-        assert_eq!(tested.should_restart(50, &vec![]), false);
-        assert_eq!(tested.should_restart(99, &vec![]), false);
-        assert_eq!(tested.should_restart(100, &vec![]), true);
-        assert_eq!(tested.should_restart(101, &vec![]), false);
+        assert_eq!(tested.should_restart(50, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(99, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(100, &BoundedQueue::new(0)), true);
+        assert_eq!(tested.should_restart(101, &BoundedQueue::new(0)), false);
 
         tested.set_next_limit();
 
-        assert_eq!(tested.should_restart(100, &vec![]), false);
-        assert_eq!(tested.should_restart(109, &vec![]), false);
-        assert_eq!(tested.should_restart(110, &vec![]), true);
-        assert_eq!(tested.should_restart(111, &vec![]), false);
+        assert_eq!(tested.should_restart(100, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(109, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(110, &BoundedQueue::new(0)), true);
+        assert_eq!(tested.should_restart(111, &BoundedQueue::new(0)), false);
 
         tested.set_next_limit();
         tested.set_next_limit();
         tested.set_next_limit();
 
-        assert_eq!(tested.should_restart(110, &vec![]), false);
-        assert_eq!(tested.should_restart(120, &vec![]), false);
-        assert_eq!(tested.should_restart(121, &vec![]), true);
-        assert_eq!(tested.should_restart(122, &vec![]), false);
+        assert_eq!(tested.should_restart(110, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(120, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(121, &BoundedQueue::new(0)), true);
+        assert_eq!(tested.should_restart(122, &BoundedQueue::new(0)), false);
 
     }
 
@@ -1,3 +1,4 @@
+use collections::BoundedQueue;
 use solving::heuristics::RestartHeuristic2;
 
 /// This structure encapsulates the restart strategy of the solver.
@@ -18,7 +19,7 @@ pub struct Luby {
 impl RestartHeuristic2 for Luby {
     /// Tells whether the solver should restart given it has already encountered `nb_conflicts`
     #[inline]
-    fn should_restart(&self, nb_conflict: usize, _queue: &Vec<u32>) -> bool {
+    fn should_restart(&self, nb_conflict: usize, _queue: &BoundedQueue) -> bool {
         nb_conflict > (self.unit << self.shift)
     }
 
@@ -89,90 +90,90 @@ mod tests {
         let mut tested = Luby::new(100);
 
         // 0
-        assert_eq!(tested.should_restart(  1, &vec![]), false);
-        assert_eq!(tested.should_restart( 10, &vec![]), false);
-        assert_eq!(tested.should_restart( 99, &vec![]), false);
-        assert_eq!(tested.should_restart(100, &vec![]), false);
-        assert_eq!(tested.should_restart(101, &vec![]), true);
+        assert_eq!(tested.should_restart(  1, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart( 10, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart( 99, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(100, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(101, &BoundedQueue::new(0)), true);
 
         // 1
         tested.set_next_limit();
 
-        assert_eq!(tested.should_restart(  1, &vec![]), false);
-        assert_eq!(tested.should_restart( 10, &vec![]), false);
-        assert_eq!(tested.should_restart( 99, &vec![]), false);
-        assert_eq!(tested.should_restart(100, &vec![]), false);
-        assert_eq!(tested.should_restart(200, &vec![]), false);
-        assert_eq!(tested.should_restart(201, &vec![]), true);
+        assert_eq!(tested.should_restart(  1, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart( 10, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart( 99, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(100, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(200, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(201, &BoundedQueue::new(0)), true);
 
         // 1
         tested.set_next_limit();
 
-        assert_eq!(tested.should_restart(  1, &vec![]), false);
-        assert_eq!(tested.should_restart( 10, &vec![]), false);
-        assert_eq!(tested.should_restart( 99, &vec![]), false);
-        assert_eq!(tested.should_restart(100, &vec![]), false);
-        assert_eq!(tested.should_restart(200, &vec![]), false);
-        assert_eq!(tested.should_restart(201, &vec![]), true);
+        assert_eq!(tested.should_restart(  1, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart( 10, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart( 99, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(100, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(200, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(201, &BoundedQueue::new(0)), true);
 
         // 2
         tested.set_next_limit();
 
-        assert_eq!(tested.should_restart(  1, &vec![]), false);
-        assert_eq!(tested.should_restart( 10, &vec![]), false);
-        assert_eq!(tested.should_restart( 99, &vec![]), false);
-        assert_eq!(tested.should_restart(100, &vec![]), false);
-        assert_eq!(tested.should_restart(200, &vec![]), false);
-        assert_eq!(tested.should_restart(300, &vec![]), false);
-        assert_eq!(tested.should_restart(400, &vec![]), false);
-        assert_eq!(tested.should_restart(401, &vec![]), true);
+        assert_eq!(tested.should_restart(  1, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart( 10, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart( 99, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(100, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(200, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(300, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(400, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(401, &BoundedQueue::new(0)), true);
 
         // 1
         tested.set_next_limit();
 
-        assert_eq!(tested.should_restart(  1, &vec![]), false);
-        assert_eq!(tested.should_restart( 10, &vec![]), false);
-        assert_eq!(tested.should_restart( 99, &vec![]), false);
-        assert_eq!(tested.should_restart(100, &vec![]), false);
-        assert_eq!(tested.should_restart(200, &vec![]), false);
-        assert_eq!(tested.should_restart(201, &vec![]), true);
+        assert_eq!(tested.should_restart(  1, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart( 10, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart( 99, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(100, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(200, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(201, &BoundedQueue::new(0)), true);
 
         // 1
         tested.set_next_limit();
 
-        assert_eq!(tested.should_restart(  1, &vec![]), false);
-        assert_eq!(tested.should_restart( 10, &vec![]), false);
-        assert_eq!(tested.should_restart( 99, &vec![]), false);
-        assert_eq!(tested.should_restart(100, &vec![]), false);
-        assert_eq!(tested.should_restart(200, &vec![]), false);
-        assert_eq!(tested.should_restart(201, &vec![]), true);
+        assert_eq!(tested.should_restart(  1, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart( 10, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart( 99, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(100, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(200, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(201, &BoundedQueue::new(0)), true);
 
         // 2
         tested.set_next_limit();
 
-        assert_eq!(tested.should_restart(  1, &vec![]), false);
-        assert_eq!(tested.should_restart( 10, &vec![]), false);
-        assert_eq!(tested.should_restart( 99, &vec![]), false);
-        assert_eq!(tested.should_restart(100, &vec![]), false);
-        assert_eq!(tested.should_restart(200, &vec![]), false);
-        assert_eq!(tested.should_restart(300, &vec![]), false);
-        assert_eq!(tested.should_restart(400, &vec![]), false);
-        assert_eq!(tested.should_restart(401, &vec![]), true);
+        assert_eq!(tested.should_restart(  1, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart( 10, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart( 99, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(100, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(200, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(300, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(400, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(401, &BoundedQueue::new(0)), true);
 
         // 4
         tested.set_next_limit();
 
-        assert_eq!(tested.should_restart(  1, &vec![]), false);
-        assert_eq!(tested.should_restart( 10, &vec![]), false);
-        assert_eq!(tested.should_restart( 99, &vec![]), false);
-        assert_eq!(tested.should_restart(100, &vec![]), false);
-        assert_eq!(tested.should_restart(200, &vec![]), false);
-        assert_eq!(tested.should_restart(300, &vec![]), false);
-        assert_eq!(tested.should_restart(400, &vec![]), false);
-        assert_eq!(tested.should_restart(500, &vec![]), false);
-        assert_eq!(tested.should_restart(600, &vec![]), false);
-        assert_eq!(tested.should_restart(700, &vec![]), false);
-        assert_eq!(tested.should_restart(800, &vec![]), false);
-        assert_eq!(tested.should_restart(801, &vec![]), false);
+        assert_eq!(tested.should_restart(  1, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart( 10, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart( 99, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(100, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(200, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(300, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(400, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(500, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(600, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(700, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(800, &BoundedQueue::new(0)), false);
+        assert_eq!(tested.should_restart(801, &BoundedQueue::new(0)), false);
     }
 }
\ No newline at end of file
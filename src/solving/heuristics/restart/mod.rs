@@ -1,7 +1,81 @@
 mod luby;
 mod in_out;
 mod glucose;
+mod ema;
 
 pub use self::luby::*;
 pub use self::in_out::*;
-pub use self::glucose::*;
\ No newline at end of file
+pub use self::glucose::*;
+pub use self::ema::*;
+
+use solving::heuristics::{RestartHeuristic, RestartHeuristic2};
+
+/// The restart strategies the solver can be configured with.
+#[derive(Debug)]
+pub enum RestartStrategy {
+    /// PicoSAT's in/out restarts (static)
+    InOut(InOut),
+    /// Luby restarts (static)
+    Luby(Luby),
+    /// Glucose's restarts comparing the recent lbds to the global average (dynamic)
+    Glucose(Glucose),
+    /// CaDiCaL's restarts comparing a fast and a slow moving average of the lbds (dynamic)
+    Ema(Ema)
+}
+
+impl RestartStrategy {
+    /// Returns the restart strategy having the given `name` (one of inout, luby, glucose or ema)
+    /// or None when no strategy bears that name.
+    pub fn from_name(name: &str) -> Option<RestartStrategy> {
+        match name {
+            "inout"   => Some(RestartStrategy::InOut(InOut::new())),
+            "luby"    => Some(RestartStrategy::Luby(Luby::new(100))),
+            "glucose" => Some(RestartStrategy::Glucose(Glucose::new())),
+            "ema"     => Some(RestartStrategy::Ema(Ema::new())),
+            _         => None
+        }
+    }
+
+    /// Returns true iff the strategy decides to restart based on the quality of the learned
+    /// clauses rather than on a predefined sequence. Only these strategies can be blocked.
+    #[inline]
+    pub fn is_dynamic(&self) -> bool {
+        matches!(*self, RestartStrategy::Glucose(_) | RestartStrategy::Ema(_))
+    }
+
+    /// Sets the next conflict limit before the next restart
+    #[inline]
+    pub fn set_next_limit(&mut self) {
+        match *self {
+            RestartStrategy::InOut(ref mut s)   => s.set_next_limit(),
+            RestartStrategy::Luby(ref mut s)    => s.set_next_limit(),
+            RestartStrategy::Glucose(ref mut s) => s.set_next_limit(),
+            RestartStrategy::Ema(ref mut s)     => s.set_next_limit(),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Unit Tests
+// -----------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_name_knows_all_strategies() {
+        assert!(RestartStrategy::from_name("inout").is_some());
+        assert!(RestartStrategy::from_name("luby").is_some());
+        assert!(RestartStrategy::from_name("glucose").is_some());
+        assert!(RestartStrategy::from_name("ema").is_some());
+        assert!(RestartStrategy::from_name("whatever").is_none());
+    }
+
+    #[test]
+    fn only_glucose_and_ema_are_dynamic() {
+        assert!(!RestartStrategy::from_name("inout").unwrap().is_dynamic());
+        assert!(!RestartStrategy::from_name("luby").unwrap().is_dynamic());
+        assert!(RestartStrategy::from_name("glucose").unwrap().is_dynamic());
+        assert!(RestartStrategy::from_name("ema").unwrap().is_dynamic());
+    }
+}
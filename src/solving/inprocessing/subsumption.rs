@@ -8,13 +8,13 @@ use self::fixedbitset::FixedBitSet;
 use self::time::*;
 */
 
-fn is_tautology(c: &Clause) -> bool{
+fn is_tautology(c: &[Literal]) -> bool{
     for lit in c.iter(){
         if c.contains(&-*lit) {return true; }
     }
     return false;
 }
-pub fn subsume(c1: &Clause, c2: &Clause) -> bool {
+pub fn subsume(c1: &[Literal], c2: &[Literal]) -> bool {
     if c1.len() >= c2.len() {return false; }
     for lit in c1.iter(){
         if !c2.contains(lit){
@@ -47,7 +47,7 @@ pub fn subsume2(c1: &Clause, c2: &Clause) -> bool {
     return cnt >= 0
 }*/
 
-pub fn subsume_without_lit(c1: &Clause, c2: &Clause, l: Literal) -> bool {
+pub fn subsume_without_lit(c1: &[Literal], c2: &[Literal], l: Literal) -> bool {
     if c1.len() > c2.len()+1 {return false; }
     for lit in c1.iter(){
         if *lit != l &&!c2.contains(lit){
@@ -83,7 +83,7 @@ fn subsume2(c1: &Clause2, c2: &Clause2) -> bool {
 }
 
 
-fn is_tautology(c: &Clause) -> bool{
+fn is_tautology(c: &[Literal]) -> bool{
     for lit in c.iter(){
         if lit.to_isize() > 0 && c.negative.contains(lit.to_isize() as usize){ return true; }
             else if lit.to_isize() < 0 && c.positive.contains((-lit.to_isize()) as usize){ return true; }
@@ -94,7 +94,7 @@ fn is_tautology(c: &Clause) -> bool{
 
 
 // Lit must be in c1 and -Lit in c2 !!!
-pub fn subsume_without_lit(c1: &Clause, c2: &Clause, l: Literal) -> bool {
+pub fn subsume_without_lit(c1: &[Literal], c2: &[Literal], l: Literal) -> bool {
     if c1.len() > c2.len()+1 {return false; }
     let zero = FixedBitSet::with_capacity((*c1).positive.len());
     let mut pos_bitset = (*c1).positive.bitand(&(*c2).positive);
@@ -2,7 +2,6 @@ use std::usize;
 
 pub type ClauseId = usize;
 
-pub type Watcher  = ClauseId;
 pub type Conflict = ClauseId;
 pub type Reason   = ClauseId;
 
@@ -10,11 +9,27 @@ pub const CLAUSE_ELIDED: ClauseId = usize::MAX;
 
 mod heuristics;
 mod flags;
+mod clause_arena;
+mod watcher;
 mod solver;
+mod portfolio;
+mod cube_and_conquer;
+mod gauss;
+mod all_sat;
+mod model_counter;
+mod approx_counter;
 pub mod inprocessing;
 
 pub use self::heuristics::*;
 pub use self::inprocessing::*;
 
 pub use self::flags::{Flag, Flags};
-pub use self::solver::Solver;
\ No newline at end of file
+pub use self::clause_arena::{ClauseArena, ClauseIds, Relocation, Tier};
+pub use self::watcher::Watcher;
+pub use self::solver::Solver;
+pub use self::portfolio::{ClauseExchange, Portfolio, SharedClause};
+pub use self::cube_and_conquer::{Cube, CubeAndConquer, Lookahead};
+pub use self::gauss::{find_xors, Gauss, Xor};
+pub use self::all_sat::{AllSat, Enumeration};
+pub use self::model_counter::ModelCounter;
+pub use self::approx_counter::ApproxCounter;
\ No newline at end of file
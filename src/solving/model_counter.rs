@@ -0,0 +1,428 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+
+use core::*;
+use solving::*;
+
+/// A connected component of the residual problem: the (unassigned) variables it is made of, and
+/// the indices of the problem clauses (not satisfied yet) which connect them.
+#[derive(Debug)]
+struct Component {
+    vars   : Vec<usize>,
+    clauses: Vec<usize>
+}
+
+impl Component {
+    /// Returns the key identifying this component in the cache: its variables followed by its
+    /// clauses. Both are sorted, hence two components having the same clause set and the same
+    /// variables have the same key (and the same number of models).
+    fn key(&self) -> Vec<u32> {
+        let mut key = Vec::with_capacity(self.vars.len() + self.clauses.len() + 1);
+        key.extend(self.vars.iter().map(|&v| v as u32));
+        key.push(u32::MAX);
+        key.extend(self.clauses.iter().map(|&c| c as u32));
+        key
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # ModelCounter
+/// An exact model counter (#SAT), optionally counting the models projected onto a subset of the
+/// variables: in that case, the models which only differ on the other variables count as one.
+///
+/// The counter is a DPLL search which branches on the trail of the solver (and hence shares its
+/// propagation): the number of models of the residual problem is the sum of the numbers of models
+/// of both branches. Before branching, the residual problem is split into connected components
+/// (ie: sets of variables connected by the problem clauses that are not satisfied yet), whose
+/// numbers of models multiply. The number of models of each component is cached, keyed by the
+/// component clause set, so that it is never counted twice.
+///
+/// The conflicts are analyzed by the solver which learns a clause out of each of them. Because
+/// both branches of every decision must be explored, the solver never backjumps: it only goes
+/// back to the previous decision level, where the learned clause is asserting.
+///
+/// The learned clauses follow from the whole problem, not from the component being counted: a
+/// component might lose some of its models because of a sibling component being unsat. Hence,
+/// the counts cached while counting a product of components are removed from the cache as soon
+/// as one of these components turns out to be unsat (the counts of the other components may be
+/// polluted). Besides, the oldest counts are evicted when the cache grows too large.
+///
+/// See `Implementing a Competitive #SAT Solver: sharpSAT` -- Thurley (SAT 2006) and
+///     `Combining Component Caching and Clause Learning for Effective Model Counting` -- Sang,
+///     Bacchus, Beame, Kautz, Pitassi (SAT 2004).
+// -----------------------------------------------------------------------------------------------
+#[derive(Debug)]
+pub struct ModelCounter<'a> {
+    /// The solver whose models are counted
+    solver      : &'a mut Solver,
+    /// Tells whether each variable belongs to the projection
+    projected   : Vec<bool>,
+    /// The clauses of the problem (which are never forgotten, unlike those of the solver)
+    clauses     : Vec<Vec<Literal>>,
+    /// The indices of the clauses each variable occurs in
+    occurrences : Vec<Vec<usize>>,
+    /// The number of models of the components counted so far
+    cache       : HashMap<Vec<u32>, Natural>,
+    /// The keys in the order they were added to the cache (so that they can be removed)
+    cached      : VecDeque<Vec<u32>>,
+    /// The number of keys that were evicted from the front of `cached`
+    nb_evicted  : usize,
+    /// The total length of the keys held in the cache
+    cache_words : usize,
+    /// The maximum total length of the keys held in the cache (the oldest counts are evicted
+    /// beyond that limit)
+    pub cache_limit: usize,
+    /// The stamp of the last components decomposition having visited each variable (resp. clause)
+    var_stamps  : Vec<usize>,
+    clause_stamps: Vec<usize>,
+    stamp       : usize,
+    /// The number of components whose number of models was found in the cache
+    pub nb_cache_hits: usize
+}
+
+impl<'a> ModelCounter<'a> {
+    /// Creates a counter of the models of the given solver, projected onto the variables of the
+    /// given `projection` (or counting all the models when it is empty).
+    pub fn new(solver: &'a mut Solver, projection: &[Variable]) -> ModelCounter<'a> {
+        // the counter backtracks chronologically by itself
+        solver.chrono_backtrack = false;
+
+        let nb_vars = solver.nb_vars();
+        let mut projected = vec![projection.is_empty(); nb_vars + 1];
+        for &v in projection.iter() {
+            projected[usize::from(v)] = true;
+        }
+
+        let clauses = solver.problem_clauses();
+        let mut occurrences = vec![vec![]; nb_vars + 1];
+        for (c, clause) in clauses.iter().enumerate() {
+            for l in clause.iter() {
+                occurrences[usize::from(l.var())].push(c);
+            }
+        }
+
+        ModelCounter {
+            solver,
+            projected,
+            clause_stamps: vec![0; clauses.len()],
+            clauses,
+            occurrences,
+            cache        : HashMap::new(),
+            cached       : VecDeque::new(),
+            nb_evicted   : 0,
+            cache_words  : 0,
+            cache_limit  : 1 << 24,
+            var_stamps   : vec![0; nb_vars + 1],
+            stamp        : 0,
+            nb_cache_hits: 0
+        }
+    }
+
+    /// Returns the number of (projected) models of the problem
+    pub fn count(&mut self) -> Natural {
+        // (backtracking at the root level would skip the propagation of the root literals)
+        if self.solver.decision_level() > 0 {
+            self.solver.backtrack_to(0);
+        }
+        if !self.solver.propagate_at_root() {
+            return Natural::zero();
+        }
+
+        let vars: Vec<usize> = (1..=self.solver.nb_vars()).collect();
+        let count = match self.count_vars(&vars) {
+            Ok(count)     => count,
+            Err(conflict) => {
+                // the problem is unsat
+                let _ = self.solver.learn(conflict);
+                Natural::zero()
+            }
+        };
+
+        self.solver.backtrack_to(0);
+        count
+    }
+
+    /// Returns the number of components held in the cache
+    #[inline]
+    pub fn cache_size(&self) -> usize { self.cache.len() }
+
+    /// Counts the models of the residual problem over the given variables (under the current
+    /// assignment) as the product of the models of its components.
+    ///
+    /// # Return Value
+    /// The number of models or the conflict met at the current decision level (in which case,
+    /// the current level has no model at all).
+    fn count_vars(&mut self, vars: &[usize]) -> Result<Natural, Conflict> {
+        let mark = self.nb_evicted + self.cached.len();
+        let (components, nb_free) = self.components(vars);
+
+        let mut count = &Natural::one() << nb_free;
+        for component in components.iter() {
+            match self.count_component(component) {
+                Err(conflict) => {
+                    self.forget(mark);
+                    return Err(conflict);
+                },
+                Ok(n) => {
+                    count *= &n;
+                    if count.is_zero() {
+                        self.forget(mark);
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(count)
+    }
+
+    /// Counts the models of the given component: that is, the sum of the models of both branches
+    /// of one of its variables. When none of its variables is projected, the component either
+    /// has one model or none.
+    fn count_component(&mut self, component: &Component) -> Result<Natural, Conflict> {
+        let key = component.key();
+        if let Some(count) = self.cache.get(&key) {
+            self.nb_cache_hits += 1;
+            return Ok(count.clone());
+        }
+
+        let existential = !component.vars.iter().any(|&v| self.projected[v]);
+        let level    = self.solver.decision_level();
+        let variable = Variable::from(self.branching_var(component, existential));
+        let positive = Literal::positive(variable);
+
+        let mut count = Natural::zero();
+        for &lit in [positive, !positive].iter() {
+            match self.solver.get_value(lit) {
+                // the other branch has taught the solver that this one is implied
+                Bool::True  => count += &self.count_vars(&component.vars)?,
+                Bool::False => continue,
+                Bool::Undef => {
+                    let mut conflict = self.solver.branch(lit);
+                    if conflict.is_none() {
+                        match self.count_vars(&component.vars) {
+                            Ok(n) => {
+                                count += &n;
+                                self.solver.backtrack_to(level);
+                            },
+                            Err(c) => conflict = Some(c)
+                        }
+                    }
+                    if let Some(conflict) = conflict {
+                        if let Some(deeper) = self.solver.learn(conflict) {
+                            return Err(deeper);
+                        }
+                    }
+                }
+            }
+
+            if existential && !count.is_zero() { break; }
+        }
+
+        self.remember(key, count.clone());
+        Ok(count)
+    }
+
+    /// Adds the count of the component having the given `key` to the cache, after having evicted
+    /// the oldest counts if the cache is full.
+    fn remember(&mut self, key: Vec<u32>, count: Natural) {
+        while self.cache_words + key.len() > self.cache_limit {
+            match self.cached.pop_front() {
+                None      => break,
+                Some(old) => {
+                    self.cache_words -= old.len();
+                    self.cache.remove(&old);
+                    self.nb_evicted += 1;
+                }
+            }
+        }
+
+        self.cache_words += key.len();
+        self.cached.push_back(key.clone());
+        self.cache.insert(key, count);
+    }
+
+    /// Removes from the cache all the counts which were cached past the given `mark` (the number
+    /// of counts that had been cached when the mark was taken).
+    fn forget(&mut self, mark: usize) {
+        let start = mark.saturating_sub(self.nb_evicted);
+        for key in self.cached.drain(start..) {
+            self.cache_words -= key.len();
+            self.cache.remove(&key);
+        }
+    }
+
+    /// Splits the residual problem over the given variables into connected components.
+    ///
+    /// # Return Value
+    /// The components along with the number of projected variables that occur in no residual
+    /// clause (each of which doubles the number of models).
+    fn components(&mut self, vars: &[usize]) -> (Vec<Component>, usize) {
+        self.stamp += 1;
+        let stamp = self.stamp;
+
+        let mut components = vec![];
+        let mut nb_free    = 0;
+        for &v in vars.iter() {
+            if self.var_stamps[v] == stamp || !self.is_unassigned(v) { continue; }
+
+            self.var_stamps[v] = stamp;
+            let mut component = Component { vars: vec![], clauses: vec![] };
+            let mut stack = vec![v];
+            while let Some(u) = stack.pop() {
+                component.vars.push(u);
+                for &c in self.occurrences[u].iter() {
+                    if self.clause_stamps[c] == stamp { continue; }
+                    self.clause_stamps[c] = stamp;
+
+                    let clause = &self.clauses[c];
+                    if clause.iter().any(|&l| self.solver.get_value(l) == Bool::True) { continue; }
+
+                    component.clauses.push(c);
+                    for l in clause.iter() {
+                        let w = usize::from(l.var());
+                        if self.var_stamps[w] != stamp && self.solver.get_value(*l) == Bool::Undef {
+                            self.var_stamps[w] = stamp;
+                            stack.push(w);
+                        }
+                    }
+                }
+            }
+
+            if component.clauses.is_empty() {
+                if self.projected[v] { nb_free += 1; }
+            } else {
+                component.vars.sort_unstable();
+                component.clauses.sort_unstable();
+                components.push(component);
+            }
+        }
+        (components, nb_free)
+    }
+
+    /// Returns the variable of the component to branch on: the one occurring in the most clauses
+    /// of the component. Unless the component is `existential`, only the projected variables are
+    /// eligible (all the projected variables must be decided before the other ones).
+    fn branching_var(&self, component: &Component, existential: bool) -> usize {
+        let mut scores = HashMap::new();
+        for &c in component.clauses.iter() {
+            for l in self.clauses[c].iter() {
+                let v = usize::from(l.var());
+                if self.solver.get_value(*l) == Bool::Undef && (existential || self.projected[v]) {
+                    *scores.entry(v).or_insert(0) += 1;
+                }
+            }
+        }
+
+        component.vars.iter()
+            .cloned()
+            .filter(|&v| existential || self.projected[v])
+            .max_by_key(|v| (scores.get(v).cloned().unwrap_or(0), Reverse(*v)))
+            .unwrap()
+    }
+
+    /// Tells whether the given variable is unassigned
+    #[inline]
+    fn is_unassigned(&self, v: usize) -> bool {
+        self.solver.get_value(Literal::positive(Variable::from(v))) == Bool::Undef
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Unit Tests
+// -----------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fixtures::problem;
+
+    fn count(solver: &mut Solver, projection: &[uint]) -> String {
+        let projection: Vec<Variable> = projection.iter().map(|&v| var(v)).collect();
+        ModelCounter::new(solver, &projection).count().to_string()
+    }
+
+    #[test]
+    fn the_models_are_counted_exactly() {
+        // exactly one of 1, 2, 3 ; 4 -> 1
+        let mut solver = problem(4, &[&[1, 2, 3], &[-1, -2], &[-1, -3], &[-2, -3], &[-4, 1]]);
+        assert_eq!(count(&mut solver, &[]), "4");
+    }
+
+    #[test]
+    fn an_unsatisfiable_problem_has_no_model() {
+        let mut solver = problem(2, &[&[1, 2], &[-1, 2], &[1, -2], &[-1, -2]]);
+        assert_eq!(count(&mut solver, &[]), "0");
+
+        let mut solver = problem(2, &[&[1], &[-1]]);
+        assert_eq!(count(&mut solver, &[]), "0");
+
+        // the units are not propagated yet when the counter starts
+        let mut solver = problem(2, &[&[1, 2], &[-2], &[-1]]);
+        assert_eq!(count(&mut solver, &[]), "0");
+    }
+
+    #[test]
+    fn the_counts_are_arbitrarily_large() {
+        // 100 free variables and one binary clause
+        let mut solver = problem(101, &[&[1, 101]]);
+        assert_eq!(count(&mut solver, &[]), (&Natural::from(3) << 99).to_string());
+    }
+
+    #[test]
+    fn the_components_multiply() {
+        // (1 | 2) & (3 | 4) & (5 | 6): 3 * 3 * 3 models, each component being counted once
+        let mut solver = problem(6, &[&[1, 2], &[3, 4], &[5, 6]]);
+        assert_eq!(count(&mut solver, &[]), "27");
+    }
+
+    #[test]
+    fn the_models_can_be_projected() {
+        // 1 <-> (2 | 3)
+        let mut solver = problem(3, &[&[-1, 2, 3], &[1, -2], &[1, -3]]);
+        assert_eq!(count(&mut solver, &[1]), "2");
+
+        let mut solver = problem(3, &[&[-1, 2, 3], &[1, -2], &[1, -3]]);
+        assert_eq!(count(&mut solver, &[2, 3]), "4");
+
+        // 1 is forced: only one projected model
+        let mut solver = problem(3, &[&[1], &[2, 3]]);
+        assert_eq!(count(&mut solver, &[1]), "1");
+    }
+
+    #[test]
+    fn the_cache_is_used() {
+        // 2 and 7 hold whatever the value of 1: both branches leave the same component
+        let mut solver = problem(7, &[&[1, 2], &[-1, 2], &[1, 7], &[-1, 7], &[2, 3, 4], &[3, 4, 5], &[-3, -5]]);
+        let mut counter = ModelCounter::new(&mut solver, &[]);
+        assert_eq!(counter.count().to_string(), "20");
+        assert!(counter.nb_cache_hits > 0);
+        assert!(counter.cache_size() > 0);
+    }
+
+    #[test]
+    fn the_count_is_right_when_the_cache_is_full() {
+        let clauses: &[&[iint]] = &[&[1, 2], &[-1, 2], &[1, 7], &[-1, 7], &[2, 3, 4], &[3, 4, 5], &[-3, -5], &[6, 8], &[-6, -8, 9]];
+        for &limit in [0, 8, 1 << 24].iter() {
+            let mut solver = problem(9, clauses);
+            let mut counter = ModelCounter::new(&mut solver, &[]);
+            counter.cache_limit = limit;
+            assert_eq!(counter.count().to_string(), "50");
+            assert!(counter.cache_size() <= limit.max(1));
+        }
+    }
+
+    #[test]
+    fn the_count_is_right_with_learned_clauses() {
+        // pigeon hole (3 pigeons, 2 holes) next to a free component: no model at all
+        let mut clauses: Vec<Vec<iint>> = vec![vec![1, 2], vec![3, 4], vec![5, 6], vec![7, 8]];
+        for hole in 0..2 {
+            for a in 0..3 {
+                for b in a+1..3 {
+                    clauses.push(vec![-(1 + 2 * a + hole), -(1 + 2 * b + hole)]);
+                }
+            }
+        }
+        let clauses: Vec<&[iint]> = clauses.iter().map(|c| c.as_slice()).collect();
+        let mut solver = problem(8, &clauses);
+        assert_eq!(count(&mut solver, &[]), "0");
+    }
+}
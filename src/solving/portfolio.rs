@@ -0,0 +1,248 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::thread;
+
+use core::*;
+use solving::*;
+
+/// The learned clauses longer than this are only shared when their lbd is low enough
+const SHARE_MAX_SIZE: usize = 4;
+/// The learned clauses whose lbd is higher than this are only shared when they are short enough
+const SHARE_MAX_LBD : u32   = 2;
+/// The number of clauses an inbox holds at most: the clauses exported beyond are dropped
+const INBOX_CAPACITY: usize = 10_000;
+
+/// A clause exchanged between the solvers of a portfolio: its lbd and its literals
+pub type SharedClause = (u32, Vec<Literal>);
+
+// -----------------------------------------------------------------------------------------------
+/// # Clause Exchange
+/// The endpoint through which one solver of a portfolio exchanges its short or low-lbd learned
+/// clauses with all the other solvers of that portfolio.
+///
+/// Each solver owns one bounded (`std::sync::mpsc::sync_channel`) inbox through which it receives
+/// the clauses exported by the others. The received clauses are only imported when the solver
+/// restarts, that is to say when it is back at the root level and adding new clauses is trivially
+/// safe. Sharing is only a hint: a clause exported while the inbox of a solver is full is simply
+/// dropped for that solver, so that a solver which rarely restarts does not pile up everything its
+/// peers have learned.
+// -----------------------------------------------------------------------------------------------
+#[derive(Debug)]
+pub struct ClauseExchange {
+    /// The channels through which the clauses are sent to the other solvers
+    outbox: Vec<SyncSender<SharedClause>>,
+    /// The channel through which the clauses exported by the other solvers are received
+    inbox : Receiver<SharedClause>
+}
+
+impl ClauseExchange {
+    /// Creates `n` fully connected endpoints: every clause exported through one of them is
+    /// received by all the other ones.
+    pub fn connect(n: usize) -> Vec<ClauseExchange> {
+        ClauseExchange::connect_with_capacity(n, INBOX_CAPACITY)
+    }
+
+    /// Creates `n` fully connected endpoints whose inboxes hold at most `capacity` clauses.
+    fn connect_with_capacity(n: usize, capacity: usize) -> Vec<ClauseExchange> {
+        let (senders, receivers): (Vec<_>, Vec<_>) = (0..n).map(|_| sync_channel(capacity)).unzip();
+
+        receivers.into_iter().enumerate()
+            .map(|(i, inbox)| {
+                let outbox = senders.iter().enumerate()
+                    .filter(|&(j, _)| i != j)
+                    .map(|(_, s)| s.clone())
+                    .collect();
+
+                ClauseExchange { outbox, inbox }
+            })
+            .collect()
+    }
+
+    /// Tells whether a learned clause having `size` literals and the given `lbd` is worth
+    /// sharing with the other solvers.
+    #[inline]
+    pub fn should_export(&self, size: usize, lbd: u32) -> bool {
+        size <= SHARE_MAX_SIZE || lbd <= SHARE_MAX_LBD
+    }
+
+    /// Sends the given clause to all the other solvers of the portfolio (except those whose inbox
+    /// is full).
+    pub fn export(&self, lbd: u32, literals: &[Literal]) {
+        for sender in self.outbox.iter() {
+            // the inbox is full when its solver has not restarted for a while: the clause is lost
+            // for that one. And the receiver is gone when its solver is done: there is nothing to
+            // share anymore. Either way, the exporting solver must never block.
+            let _ = sender.try_send((lbd, literals.to_vec()));
+        }
+    }
+
+    /// Returns all the clauses that have been exported by the other solvers since the last call.
+    pub fn import(&self) -> Vec<SharedClause> {
+        self.inbox.try_iter().collect()
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Portfolio
+/// A portfolio runs several solvers on the very same problem, each in its own thread. The solvers
+/// use diversified heuristics (restart strategy, initial phases and variable ordering, ...) so
+/// that they explore different parts of the search space, and they share their most valuable
+/// learned clauses with one another. The first solver to find the answer wins: all the others are
+/// told to stop.
+// -----------------------------------------------------------------------------------------------
+#[derive(Debug)]
+pub struct Portfolio {
+    /// The solvers (all of them are loaded with the same problem)
+    solvers  : Vec<Solver>,
+    /// The flag raised by the winning solver to stop all the others
+    terminate: Arc<AtomicBool>
+}
+
+impl Portfolio {
+    /// Creates a portfolio out of the given `solvers` which must all have been loaded with the
+    /// very same problem. The first solver keeps the configuration it was given while the others
+    /// are diversified.
+    pub fn new(mut solvers: Vec<Solver>) -> Portfolio {
+        let terminate = Arc::new(AtomicBool::new(false));
+        let exchanges = ClauseExchange::connect(solvers.len());
+
+        for (id, (solver, exchange)) in solvers.iter_mut().zip(exchanges).enumerate() {
+            Portfolio::diversify(solver, id);
+            solver.exchange  = Some(exchange);
+            solver.terminate = Some(Arc::clone(&terminate));
+        }
+
+        Portfolio { solvers, terminate }
+    }
+
+    /// Gives the solver with the given `id` a configuration of its own.
+    fn diversify(solver: &mut Solver, id: usize) {
+        if id == 0 { return; }
+
+        solver.restart_strat = match id % 4 {
+            1 => RestartStrategy::Glucose(Glucose::new()),
+            2 => RestartStrategy::Ema(Ema::new()),
+            3 => RestartStrategy::Luby(Luby::new(100)),
+            _ => RestartStrategy::InOut(InOut::new())
+        };
+        solver.shrink = id % 3 == 1;
+        solver.randomize(id as u64);
+    }
+
+    /// Runs all the solvers in parallel and returns the answer of the first one to complete,
+    /// along with that solver (ie: to retrieve a model or statistics).
+    ///
+    /// # Return Value
+    /// true if there exist an assignment satisfying the given cnf problem.
+    /// false if there exists no such assignment.
+    pub fn solve(self) -> (bool, Solver) {
+        let Portfolio { solvers, terminate } = self;
+
+        let handles: Vec<_> = solvers.into_iter()
+            .map(|mut solver| {
+                let terminate = Arc::clone(&terminate);
+
+                thread::spawn(move || {
+                    let result = solver.try_solve();
+                    // only the first solver to raise the flag wins
+                    let winner = result.is_some() && !terminate.swap(true, Ordering::SeqCst);
+                    (winner, result, solver)
+                })
+            })
+            .collect();
+
+        let mut answer = None;
+        for handle in handles {
+            let (winner, result, solver) = handle.join().expect("a solver of the portfolio panicked");
+            if winner {
+                answer = Some((result.unwrap(), solver));
+            }
+        }
+
+        answer.expect("no solver of the portfolio completed its search")
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Unit Tests
+// -----------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fixtures::pigeon_hole;
+
+    #[test]
+    fn exported_clauses_are_received_by_all_the_others() {
+        let exchanges = ClauseExchange::connect(3);
+
+        exchanges[0].export(2, &[lit(1), lit(-2)]);
+
+        assert!(exchanges[0].import().is_empty());
+        assert_eq!(exchanges[1].import(), vec![(2, vec![lit(1), lit(-2)])]);
+        assert_eq!(exchanges[2].import(), vec![(2, vec![lit(1), lit(-2)])]);
+        // the clauses are only received once
+        assert!(exchanges[1].import().is_empty());
+    }
+
+    #[test]
+    fn export_ignores_the_solvers_that_are_gone() {
+        let mut exchanges = ClauseExchange::connect(2);
+        exchanges.pop();
+
+        exchanges[0].export(1, &[lit(1)]);
+    }
+
+    #[test]
+    fn export_drops_the_clauses_that_overflow_an_inbox() {
+        let exchanges = ClauseExchange::connect_with_capacity(2, 2);
+
+        exchanges[0].export(1, &[lit(1)]);
+        exchanges[0].export(1, &[lit(2)]);
+        exchanges[0].export(1, &[lit(3)]);
+
+        assert_eq!(exchanges[1].import(), vec![(1, vec![lit(1)]), (1, vec![lit(2)])]);
+        // once emptied, the inbox receives the clauses again
+        exchanges[0].export(1, &[lit(4)]);
+        assert_eq!(exchanges[1].import(), vec![(1, vec![lit(4)])]);
+    }
+
+    #[test]
+    fn only_short_or_low_lbd_clauses_are_exported() {
+        let exchanges = ClauseExchange::connect(2);
+
+        assert!( exchanges[0].should_export(1, 1));
+        assert!( exchanges[0].should_export(4, 4));
+        assert!( exchanges[0].should_export(9, 2));
+        assert!(!exchanges[0].should_export(9, 3));
+    }
+
+    #[test]
+    fn portfolio_proves_satisfiability() {
+        let solvers = (0..4).map(|_| pigeon_hole(5, 5)).collect();
+        let (satisfiable, solver) = Portfolio::new(solvers).solve();
+
+        assert!(satisfiable);
+        // every pigeon is in exactly one hole
+        for p in 0..5 {
+            let holes = (0..5).filter(|h| solver.get_value(lit(1 + p * 5 + h)) == Bool::True).count();
+            assert_eq!(holes, 1);
+        }
+    }
+
+    #[test]
+    fn portfolio_proves_unsatisfiability() {
+        let solvers = (0..4).map(|_| pigeon_hole(6, 5)).collect();
+        let (satisfiable, _) = Portfolio::new(solvers).solve();
+
+        assert!(!satisfiable);
+    }
+
+    #[test]
+    fn portfolio_with_a_single_solver() {
+        let solvers = vec![pigeon_hole(4, 3)];
+        let (satisfiable, _) = Portfolio::new(solvers).solve();
+
+        assert!(!satisfiable);
+    }
+}
@@ -1,7 +1,12 @@
 extern crate fixedbitset;
+extern crate rand;
 extern crate time;
 
+use std::cmp::Reverse;
+use std::mem;
 use std::usize;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 //use std::ops::{BitOr,BitAnd};
 
 use core::*;
@@ -10,9 +15,18 @@ use solving::*;
 //use self::time::*;
 
 use self::fixedbitset::FixedBitSet;
+use self::rand::{Rng, SeedableRng};
+use self::rand::rngs::StdRng;
 
 type  ClauseId = usize;
 const CLAUSE_ELIDED: ClauseId = usize::MAX;
+/// The reason of the literals implied by the XOR constraints. The actual reason clause is only
+/// generated (from the explanation kept by the Gauss-Jordan engine) when it is needed.
+const XOR_IMPLIED: ClauseId = usize::MAX - 1;
+/// The number of conflicts before which the restarts are never blocked
+const RESTART_BLOCKING_MIN_CONFLICTS: usize = 10_000;
+/// How much larger than its recent average the trail must be for the restarts to be blocked
+const RESTART_BLOCKING_MARGIN: f64 = 1.4;
 //const CLAUSE_SUB: ClauseId = usize::MAX - 1;
 
 type Conflict = ClauseId;
@@ -44,7 +58,7 @@ pub struct Solver {
     /// The current assignment of boolean values to variables
     valuation: VarIdxVec<Bool>,
     /// All the clauses that make the problem
-    pub clauses : Vec<Clause>,
+    pub clauses : ClauseArena,
 
     /// A flag telling whether or not the solver was detected to be unsat.
     /// This flag must be set while adding clauses to the problem and during conflict resolution
@@ -59,37 +73,47 @@ pub struct Solver {
     phase_saving : FixedBitSet,
     /// The number of clauses that can be learned before we start to try cleaning up the database
     max_learned  : usize,
-
-    /// Glucose specific
-    //restart_strat: Glucose, // HERE
-    //restart_strat: Luby,
-    restart_strat: InOut,
-
-    glucose_wind : Vec<u32>,
-
+    /// A flag telling whether or not restarts should reuse the part of the trail that would
+    /// anyhow be re-built by the decisions following a complete restart.
+    /// See `Reusing the Assignment Trail in CDCL Solvers` -- van der Tak, Ramos, Heule (JSAT 2011).
+    pub reuse_trail : bool,
+
+    /// The restart strategy (defaults to PicoSAT's in/out restarts)
+    pub restart_strat: RestartStrategy,
+    /// The lbds of the most recently learned clauses (Glucose specific)
+    glucose_wind : BoundedQueue,
+    /// The average lbd of the clauses learned since the very beginning (Glucose specific)
     glucose_avg_global : f64,
-
-    glucose_size : usize,
+    /// A flag telling whether or not the dynamic restarts are postponed when the trail becomes
+    /// much larger than its recent average (which suggests the search is approaching a model).
+    /// See `Refining Restarts Strategies for SAT and UNSAT` -- Audemard, Simon (CP 2012).
+    pub restart_blocking: bool,
+    /// The sizes of the trail when the most recent conflicts occurred (restart blocking)
+    trail_wind : BoundedQueue,
+    /// The number of conflicts until which the restarts are blocked (restart blocking)
+    restarts_blocked_until: usize,
 
 
     /// The last level at which some variable was assigned (intervenes in the LBD computation)
     level        : VarIdxVec<u32>,
-    /// This is an heuristic 'quality' score associated with each of the clauses which is used
-    /// by the solver's clause management (removal) strategy. It measures the number of propagation
-    /// blocks that were necessary for this clause to become falsified.
-    /// See `Predicting Learnt Clauses Quality in Modern SAT Solvers.` Audemard, Simon in aaai2009
-    /// for the full details about literal block distance.
-    lbd: Vec<u32>,
-    /// This flag indicates whether or not the LBD of this clause has 'recently' been updated. That
-    /// is to say, it tells whether or not the LBD of this clause has been improved since the last
-    /// round of database reduction. This indication is helpful in the sense that it helps protecting
-    /// against deletion the clauses that have recently been of interest.
-    lbd_recently_updated: FixedBitSet,
+    // Note: the quality scores associated with each clause (literal block distance, tier,
+    //       activity, ...) are stored in the header of the clause in the arena.
+    //       See `Predicting Learnt Clauses Quality in Modern SAT Solvers.` Audemard, Simon in
+    //       aaai2009 for the full details about literal block distance.
+    /// The amount by which the activity of a clause is increased when it is bumped
+    clause_inc: f32,
+    /// The factor by which the activity of all clauses decays after each conflict
+    clause_decay: f32,
 
     // ~~~ # Propagation ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-    /// Watchers: vectors of watchers associated with each literal.
+    /// Watchers: vectors of watchers associated with each literal. Each watcher carries a blocker
+    /// literal which lets propagation skip the satisfied clauses without accessing their memory.
     /// _Important Notice_ : A clause should watch a literal it owns, not its negation !
     watchers     : LitIdxVec<Vec<Watcher>>,
+    /// Binary watchers: the binary clauses are not watched in `watchers` but in these lists. The
+    /// blocker of a binary watcher is the other literal of the clause. This way, binary clauses
+    /// are propagated without ever accessing the clause memory (unless they imply something).
+    binaries     : LitIdxVec<Vec<Watcher>>,
     /// The trail of decisions and propagations that have been made so far
     prop_queue   : Vec<Literal>,
     /// The index up to which all assignments are _forced_. That is to say, these literals are
@@ -105,6 +129,13 @@ pub struct Solver {
     ///       smaller than `i` have been propagated. `prop_queue[propagated]` denotes the next
     ///       assignment to propagate
     propagated   : usize,
+    /// The Gauss-Jordan elimination engine propagating the XOR constraints of the problem
+    gauss        : Gauss,
+    /// The index up to which the assignments of the trail were notified to the `gauss` engine
+    xor_notified : usize,
+    /// The number of clauses currently in the database that explain an implication or a conflict
+    /// of the `gauss` engine. These are not counted among the learned clauses.
+    nb_xor_reasons: usize,
 
     // ~~~ # Clause Learning ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     /// clause minimization
@@ -115,6 +146,15 @@ pub struct Solver {
     reason       : VarIdxVec<Option<Reason>>,
     /// The flags used during conflict analysis. One set of flag is associated with each literal.
     flags        : LitIdxVec<Flags>,
+    /// A flag telling whether or not the learned clauses are minimized with the binary clauses
+    /// containing their asserting literal.
+    /// See `Glucose 2.1: Aggressive, but Reactive, Clause Database Management, Dynamic Restarts`
+    /// -- Audemard, Simon (POS 2012).
+    pub binary_minimization: bool,
+    /// A flag telling whether or not the learned clauses are shrunk. That is, whether or not the
+    /// literals of each level are replaced by the unique implication point of that level.
+    /// See `Efficient All-UIP Learned Clause Minimization` -- Fleury, Biere (SAT 2021).
+    pub shrink: bool,
     /// Bool to enable subsumption
     subsume_enable: bool,
     /// Activate LCM
@@ -124,6 +164,34 @@ pub struct Solver {
     pub removed: usize,
     rl: bool,
 
+    // ~~~ # Backtracking ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    /// A flag telling whether or not the solver is allowed to backtrack chronologically (that is,
+    /// to undo only the last decision level) instead of always backjumping to the assertion level
+    /// of the learned clause.
+    /// See `Chronological Backtracking` -- Nadel, Ryvchin (SAT 2018).
+    pub chrono_backtrack: bool,
+    /// The number of levels a backjump must undo before the solver prefers to backtrack
+    /// chronologically (only meaningful when `chrono_backtrack` is turned on).
+    pub chrono_threshold: u32,
+
+    // ~~~ # Assumptions ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    /// The literals assumed to hold during the current search. These are decided (in order)
+    /// before any other decision is taken.
+    assumptions: Vec<Literal>,
+    /// The number of leading assumptions known to hold in the current assignment (this only
+    /// avoids scanning the assumptions again and again, it is reset upon backtracking).
+    nb_assumed: usize,
+    /// The subset of the assumptions that cannot all hold together (computed when the search
+    /// fails under the assumptions).
+    failed: Vec<Literal>,
+
+    // ~~~ # Portfolio ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    /// A flag which, once raised (ie: by an other solver of the portfolio), tells the search to
+    /// give up as soon as possible.
+    pub terminate: Option<Arc<AtomicBool>>,
+    /// The endpoint through which the learned clauses are shared with the other solvers of the
+    /// portfolio.
+    pub exchange: Option<ClauseExchange>,
 }
 
 impl Solver {
@@ -141,57 +209,91 @@ impl Solver {
             nb_learned: 0,
 
             valuation: VarIdxVec::from(vec![Bool::Undef; nb_vars]),
-            clauses: Vec::with_capacity(nb_clauses),
+            clauses: ClauseArena::with_capacity(nb_clauses),
             is_unsat: false,
 
             var_order: ACIDS::new(nb_vars),
             phase_saving: FixedBitSet::with_capacity(1 + nb_vars),
             max_learned: 1000,
-            // HERE
-            //restart_strat: Glucose::new(),
-            //restart_strat: Luby::new(100),
-            restart_strat: InOut::new(),
-
-            glucose_size: 100, // change also next line !
-            glucose_wind: Vec::with_capacity(100), // correspond to glucose_size
+            reuse_trail: false,
+            restart_strat: RestartStrategy::InOut(InOut::new()),
+            glucose_wind: BoundedQueue::new(100),
             glucose_avg_global: 0.0,
+            restart_blocking: true,
+            trail_wind: BoundedQueue::new(5000),
+            restarts_blocked_until: 0,
 
             level: VarIdxVec::from(vec![0; nb_vars]),
-            lbd  : Vec::with_capacity(nb_clauses),
-            lbd_recently_updated: FixedBitSet::with_capacity(nb_clauses),
+            clause_inc: 1.0,
+            clause_decay: 0.999,
 
             watchers: LitIdxVec::with_capacity(nb_vars),
+            binaries: LitIdxVec::with_capacity(nb_vars),
             prop_queue: Vec::with_capacity(nb_vars),
             forced: 0,
             propagated: 0,
+            gauss: Gauss::new(),
+            xor_notified: 0,
+            nb_xor_reasons: 0,
 
             nb_minimization: 0,
             nb_learned_since_minimiation: 0,
 
             reason: VarIdxVec::with_capacity(nb_vars),
             flags: LitIdxVec::with_capacity(nb_vars),
+            binary_minimization: true,
+            shrink: false,
             subsume_enable: false,
             lcm: false, // HERE
             preprocess: false,
             removed: 0,
             rl: false,
+
+            chrono_backtrack: false,
+            chrono_threshold: 100,
+
+            assumptions: vec![],
+            nb_assumed: 0,
+            failed: vec![],
+
+            terminate: None,
+            exchange: None,
         };
 
         // initialize vectors
         for _ in 0..nb_vars {
             solver.watchers.push_values(vec![], vec![]);
+            solver.binaries.push_values(vec![], vec![]);
             solver.flags.push_values(Flags::new(), Flags::new());
             solver.reason.push(None);
         }
 
         // reclaim wastefully overallocated memory
         solver.watchers.shrink_to_fit();
+        solver.binaries.shrink_to_fit();
         solver.flags.shrink_to_fit();
         solver.reason.shrink_to_fit();
 
         return solver;
     }
 
+    /// Adds a fresh variable to the problem (ie: an auxiliary variable of some encoding, or a
+    /// variable that shows up after the problem was loaded) and returns it. The new variable is
+    /// the one whose index is the new number of variables.
+    pub fn new_var(&mut self) -> Variable {
+        let var = self.var_order.grow();
+
+        self.valuation.push(Bool::Undef);
+        self.level    .push(0);
+        self.reason   .push(None);
+        self.watchers .push_values(vec![], vec![]);
+        self.binaries .push_values(vec![], vec![]);
+        self.flags    .push_values(Flags::new(), Flags::new());
+        self.phase_saving.grow(1 + self.nb_vars());
+
+        var
+    }
+
     // -------------------------------------------------------------------------------------------//
     // ---------------------------- SEARCH -------------------------------------------------------//
     // -------------------------------------------------------------------------------------------//
@@ -203,22 +305,85 @@ impl Solver {
 	/// true if there exist an assignment satisfying the given cnf problem.
 	/// false if there exists no such assignment.
 	///
+	/// # Panics
+	/// - when the search is terminated before it could complete (see `try_solve`).
     pub fn solve(&mut self) -> bool {
+        self.solve_with_assumptions(&[])
+    }
+
+    /// Same as `solve` except that the search gives up as soon as the `terminate` flag is raised.
+    ///
+    /// # Return Value
+    /// Some(true) if there exist an assignment satisfying the given cnf problem.
+    /// Some(false) if there exists no such assignment.
+    /// None if the search was terminated before it could decide.
+    pub fn try_solve(&mut self) -> Option<bool> {
+        self.try_solve_with_assumptions(&[])
+    }
+
+    /// Determines the satisfiability of the problem under the given `assumptions`. That is to
+    /// say, it looks for an assignment satisfying the problem in which all the assumptions hold.
+    ///
+    /// The solver is incremental: it can be called several times (ie: with different assumptions
+    /// or after adding new problem clauses) and keeps the clauses it has learned between calls.
+    /// When the problem is unsatisfiable under the assumptions, `failed_assumptions` tells which
+    /// of the assumptions are responsible for it.
+    ///
+    /// # Return Value
+    /// true if there exist an assignment satisfying the problem and the assumptions.
+    /// false if there exists no such assignment.
+    ///
+    /// # Panics
+    /// - when the search is terminated before it could complete (see `try_solve`).
+    pub fn solve_with_assumptions(&mut self, assumptions: &[Literal]) -> bool {
+        self.try_solve_with_assumptions(assumptions)
+            .expect("the search was terminated before it could complete")
+    }
+
+    /// Same as `solve_with_assumptions` except that the search gives up as soon as the
+    /// `terminate` flag is raised (in which case, None is returned).
+    pub fn try_solve_with_assumptions(&mut self, assumptions: &[Literal]) -> Option<bool> {
+        // a previous search may have left the solver anywhere in the search tree
+        if self.nb_decisions > 0 {
+            self.backtrack(0);
+        }
+        self.assumptions.clear();
+        self.assumptions.extend_from_slice(assumptions);
+        self.nb_assumed = 0;
+        self.failed.clear();
+
         if self.preprocess {
             self.preprocess();
         }
+        self.search()
+    }
+
+    /// Looks for an assignment satisfying the problem and the assumptions, the search being
+    /// resumed from where the last one stopped (see `solve_next`). This is the solver proper.
+    ///
+    /// # Return Value
+    /// Same as `try_solve_with_assumptions`.
+    fn search(&mut self) -> Option<bool> {
         loop {
-            if self.is_unsat { return false; }
+            if self.is_unsat { return Some(false); }
             match self.propagate() {
                 Some(conflict) => {
                     self.nb_conflicts += 1;
                     self.nb_conflicts_since_restart += 1;
 
+                    if self.is_terminated() {
+                        return None;
+                    }
+
+                    if self.restart_blocking {
+                        self.block_restarts();
+                    }
+
                     // if there is a conflict, I try to resolve it. But if I can't, that
                     // means that the problem is UNSAT
                     if self.resolve_conflict(conflict).is_err() {
                         self.is_unsat = true;
-                        return false;
+                        return Some(false);
                     }
 
                     if self.should_restart() {
@@ -229,9 +394,20 @@ impl Solver {
                         self.reduce_db();
                     }
                 },
+                // (a conflict of the XOR constraints may have been detected at the root level)
+                None if self.is_unsat => return Some(false),
                 None => {
-                    match self.decide() {
-                        None => return true,
+                    let decision = match self.next_assumption() {
+                        Ok(Some(assumption)) => Some(assumption),
+                        Ok(None)             => self.decide(),
+                        Err(falsified)       => {
+                            self.analyze_final(falsified);
+                            return Some(false);
+                        }
+                    };
+
+                    match decision {
+                        None => return Some(true),
                         Some(lit) => self.assign(lit, None).ok()
                     };
 
@@ -241,6 +417,81 @@ impl Solver {
         }
     }
 
+    /// Looks for another assignment satisfying the problem and the assumptions of the last search,
+    /// which must have succeeded: the given `blocking` clause (which is falsified by the assignment
+    /// found by that search, typically the negation of a model) is added to the problem clauses
+    /// and the search resumes from where it stopped, rather than from scratch. The solver only
+    /// backtracks to the level where the blocking clause becomes unit (or unassigned).
+    ///
+    /// When the blocking clause is not falsified by the current assignment, this boils down to
+    /// adding it as any other problem clause and solving again (under the same assumptions).
+    ///
+    /// # Return Value
+    /// Same as `solve_with_assumptions`.
+    ///
+    /// # Panics
+    /// - when the search is terminated before it could complete.
+    pub fn solve_next(&mut self, blocking: &[Literal]) -> bool {
+        if !blocking.iter().all(|l| self.is_false(*l)) {
+            let _ = self.add_clause_literals(blocking);
+            let assumptions = self.assumptions.clone();
+            return self.solve_with_assumptions(&assumptions);
+        }
+
+        // the literals falsified at the root level are useless, the others are sorted by
+        // decreasing level so that the two highest ones get watched (and by literal so that the
+        // duplicates are adjacent)
+        let mut clause: Vec<Literal> = blocking.iter()
+            .cloned()
+            .filter(|l| self.level[l.var()] > 0)
+            .collect();
+        clause.sort_by_key(|&l| (Reverse(self.level[l.var()]), l));
+        clause.dedup();
+
+        if self.drat {
+            println!("a {}", Clause::new(clause.clone(), false).to_dimacs());
+        }
+        match clause.len() {
+            0 => self.is_unsat = true,
+            1 => {
+                self.backtrack(0);
+                self.is_unsat |= self.assign(clause[0], Some(CLAUSE_ELIDED)).is_err();
+            },
+            _ => {
+                let highest = self.level[clause[0].var()];
+                let second  = self.level[clause[1].var()];
+                // either the clause is asserting one level below, or it is left unassigned
+                self.backtrack(if second < highest { second } else { highest - 1 });
+
+                let c_id = self.clauses.alloc(&Clause::new(clause, false), false);
+                self.watch_clause(c_id);
+                if second < highest {
+                    let asserted = self.clauses[c_id][0];
+                    let _ = self.assign(asserted, Some(c_id));
+                }
+            }
+        }
+
+        self.search().expect("the search was terminated before it could complete")
+    }
+
+    /// Returns the next assumption to branch on, None if all the assumptions already hold.
+    /// When some assumption is falsified by the current assignment, that assumption is returned
+    /// as an error.
+    fn next_assumption(&mut self) -> Result<Option<Literal>, Literal> {
+        while self.nb_assumed < self.assumptions.len() {
+            let assumption = self.assumptions[self.nb_assumed];
+
+            match self.get_value(assumption) {
+                Bool::True  => self.nb_assumed += 1,
+                Bool::False => return Err(assumption),
+                Bool::Undef => return Ok(Some(assumption))
+            }
+        }
+
+        Ok(None)
+    }
+
     /// Returns the next literal to branch on. This method uses the variable ordering
     /// heuristic (based on vsids) and the phase saving mechanism built-in the variables.
     /// Whenever all variables have been assigned, this method returns None in order to mean
@@ -280,11 +531,53 @@ impl Solver {
     /// Err when the conflict could not be resolved (that is to say, when the problem is proven
     ///     to be UNSAT
     fn resolve_conflict(&mut self, conflict: ClauseId) -> Result<(), ()> {
+        if self.chrono_backtrack {
+            // With chronological backtracking, the conflict is not necessarily located at the
+            // current decision level. Hence, the analysis must start from the conflict level.
+            let conflict_level = self.conflict_level(conflict);
+            if conflict_level == 0 {
+                return Err(());
+            }
+            if conflict_level < self.nb_decisions {
+                self.backtrack(conflict_level);
+            }
+
+            // When there is only one literal at the conflict level, the conflicting clause is in
+            // fact a missed (lower) implication. There is nothing to learn, it suffices to assert
+            // that literal one level below.
+            let second_level = self.level[self.clauses[conflict][1].var()];
+            if second_level < conflict_level {
+                self.backtrack(conflict_level - 1);
+                let asserted = self.clauses[conflict][0];
+                return self.assign(asserted, Some(conflict));
+            }
+        }
+
         let uip = self.find_first_uip(conflict);
-        let learned = self.build_conflict_clause(uip);
-        let backjump = self.find_backjump_point(uip);
+        let mut learned = self.build_conflict_clause(uip);
+
+        if self.shrink {
+            self.shrink_clause(&mut learned);
+        }
+        if self.binary_minimization {
+            self.minimize_with_binaries(&mut learned);
+        }
 
-        self.rollback(backjump);
+        if self.chrono_backtrack {
+            let jump = self.assertion_level(&mut learned);
+            let undone = self.nb_decisions - jump;
+
+            // Unit clauses are always asserted at the root level
+            if learned.len() > 1 && undone > self.chrono_threshold {
+                let previous = self.nb_decisions - 1;
+                self.backtrack(previous);
+            } else {
+                self.backtrack(jump);
+            }
+        } else {
+            let backjump = self.find_backjump_point(uip);
+            self.rollback(backjump);
+        }
 
         match self.add_learned_clause(learned) {
             Err(()) => Err(()),
@@ -296,25 +589,234 @@ impl Solver {
         }
     }
 
+    /// Computes the subset of the assumptions which are responsible for `falsified` (some
+    /// assumption) to be false. These are the assumptions whose decisions imply the negation of
+    /// `falsified`. The result (which comprises `falsified` itself) is stored in `failed`.
+    ///
+    /// This is only meaningful when all the decisions on the trail are assumptions. Which is
+    /// necessarily the case when some assumption is found to be falsified.
+    ///
+    /// See `An Extensible SAT-solver` -- Een, Sorensson (SAT 2003).
+    fn analyze_final(&mut self, falsified: Literal) {
+        self.failed.clear();
+        self.failed.push(falsified);
+
+        // the negation of the assumption is a consequence of the problem
+        if self.level[falsified.var()] == 0 { return; }
+
+        let mut seen = FixedBitSet::with_capacity(1 + self.nb_vars());
+        seen.insert(falsified.var().into());
+
+        for i in (self.forced..self.prop_queue.len()).rev() {
+            let lit = !self.prop_queue[i];
+            let var = lit.var();
+
+            if !seen.contains(var.into()) { continue; }
+
+            match self.reason_of(var) {
+                None => self.failed.push(lit),
+                Some(c_id) if c_id == CLAUSE_ELIDED => { /* forced at the root */ },
+                Some(c_id) => {
+                    for l in self.clauses[c_id].iter() {
+                        if l.var() != var && self.level[l.var()] > 0 {
+                            seen.insert(l.var().into());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the subset of the assumptions that cannot all hold together (given the problem).
+    /// This is only meaningful after the search has failed under some assumptions. When it is
+    /// empty, the problem is unsatisfiable regardless of the assumptions.
+    pub fn failed_assumptions(&self) -> &[Literal] {
+        &self.failed
+    }
+
     /// This method builds a and returns minimized conflict clause by walking the marked literals
     /// to compute a cut.
     ///
     /// `uip` is the position of the 1st uip
     fn build_conflict_clause(&mut self, uip: usize) -> Vec<Literal> {
         let mut learned = Vec::new();
+        let conflict_level = self.nb_decisions;
 
-        for cursor in (self.forced..uip+1).rev() {
+        for cursor in (self.forced..self.prop_queue.len()).rev() {
             let lit = self.prop_queue[cursor];
 
+            // Past the uip, only the literals that were assigned out of order (chronological
+            // backtracking) at some lower level may belong to the learned clause.
+            if cursor > uip && self.level[lit.var()] >= conflict_level { continue; }
+
             if self.flags[lit].is_set(Flag::IsMarked) && !self.is_implied(lit) {
                 learned.push(lit);
                 self.flags[lit].set(Flag::IsInConflictClause);
             }
         }
 
+        // The asserting literal must come first. This is only needed when the trail is not
+        // ordered by decision level (chronological backtracking).
+        if uip < self.prop_queue.len() {
+            let asserting = self.prop_queue[uip];
+            if let Some(pos) = learned.iter().position(|l| *l == asserting) {
+                learned.swap(0, pos);
+            }
+        }
+
         return learned;
     }
 
+    /// Shrinks the `learned` clause. For each decision level (but the conflict level) having
+    /// several literals in the clause, it tries to find the unique implication point of that
+    /// block of literals (block-UIP). Whenever one is found, the block is replaced by its UIP.
+    ///
+    /// A block-UIP is only valid if it can be reached by resolving with reasons whose literals
+    /// are either at the same level, or at some lower level and already in the (minimized) clause.
+    /// All the blocks are processed during one single backwards pass over the trail.
+    ///
+    /// # Note
+    /// The first literal of `learned` is the asserting literal. It is never shrunk.
+    fn shrink_clause(&mut self, learned: &mut Vec<Literal>) {
+        if learned.len() <= 2 { return; }
+
+        let nb_levels = self.nb_decisions as usize + 1;
+        // The number of literals of each block that still need to be resolved
+        let mut open  = vec![0_usize; nb_levels];
+        // The block-UIP of each level (when one has been found)
+        let mut uips  = vec![None; nb_levels];
+        // The levels for which no block-UIP can be found
+        let mut failed= FixedBitSet::with_capacity(nb_levels);
+
+        for l in learned.iter().skip(1) {
+            open[self.level[l.var()] as usize] += 1;
+        }
+
+        // Only the blocks comprising two literals or more can be shrunk
+        let mut pending = open.iter().skip(1).filter(|&&n| n >= 2).count();
+        if pending == 0 { return; }
+
+        let mut in_block = vec![];
+        for l in learned.iter().skip(1) {
+            let level = self.level[l.var()] as usize;
+            if level > 0 && open[level] >= 2 {
+                self.flags[*l].set(Flag::IsInBlock);
+                in_block.push(*l);
+            }
+        }
+
+        let mut cursor = self.prop_queue.len();
+        while pending > 0 && cursor > self.forced {
+            cursor -= 1;
+
+            let lit = self.prop_queue[cursor];
+            if !self.flags[lit].is_set(Flag::IsInBlock) { continue; }
+
+            let level = self.level[lit.var()] as usize;
+            if failed.contains(level) || uips[level].is_some() { continue; }
+
+            // This is the last literal of the block: it is the block-UIP
+            if open[level] == 1 {
+                uips[level] = Some(lit);
+                pending -= 1;
+                continue;
+            }
+
+            // Otherwise, resolve the literal with its reason
+            open[level] -= 1;
+            match self.reason_of(lit.var()) {
+                Some(c_id) if c_id != CLAUSE_ELIDED => {
+                    let c_len = self.clauses[c_id].len();
+                    for i in 1..c_len {
+                        let r = self.clauses[c_id][i];
+                        let r_level = self.level[r.var()] as usize;
+
+                        if r_level == level {
+                            if !self.flags[r].is_set(Flag::IsInBlock) {
+                                self.flags[r].set(Flag::IsInBlock);
+                                in_block.push(r);
+                                open[level] += 1;
+                            }
+                        } else if r_level > 0 && !self.flags[r].is_set(Flag::IsMarked) && !self.is_implied(r) {
+                            // r would have to be added to the clause. Shrinking this block fails
+                            failed.insert(level);
+                            pending -= 1;
+                            break;
+                        }
+                    }
+                },
+                // A decision was reached while some other literals of the block remain open
+                _ => {
+                    failed.insert(level);
+                    pending -= 1;
+                }
+            }
+        }
+
+        for l in in_block {
+            self.flags[l].unset(Flag::IsInBlock);
+        }
+
+        // Replace each shrunk block with its UIP
+        let mut shrunk = vec![learned[0]];
+        for l in learned.iter().skip(1) {
+            if uips[self.level[l.var()] as usize].is_some() {
+                self.flags[*l].unset(Flag::IsInConflictClause);
+            } else {
+                shrunk.push(*l);
+            }
+        }
+        for uip in uips.iter().filter_map(|u| *u) {
+            self.flags[uip].set(Flag::IsInConflictClause);
+            shrunk.push(uip);
+        }
+
+        self.move_highest_level_second(&mut shrunk);
+        *learned = shrunk;
+    }
+
+    /// Removes from the `learned` clause the literals which can be resolved away with some binary
+    /// clause containing the asserting literal. That is, when `a` is the asserting literal of the
+    /// learned clause and the database comprises the binary clause (a, -l), then l is removed
+    /// from the learned clause.
+    ///
+    /// As in Glucose, this minimization is only attempted for the clauses that are short and
+    /// of good quality (since these are the ones that are likely to be kept).
+    fn minimize_with_binaries(&mut self, learned: &mut Vec<Literal>) {
+        if learned.len() <= 1 || learned.len() > 30 || self.lbd_of(learned) > 6 { return; }
+
+        let asserting = learned[0];
+        let mut removed = false;
+        for w in self.binaries[asserting].iter() {
+            let l = !w.blocker;
+            if self.flags[l].is_set(Flag::IsInConflictClause) {
+                self.flags[l].unset(Flag::IsInConflictClause);
+                removed = true;
+            }
+        }
+
+        if removed {
+            let flags = &self.flags;
+            learned.retain(|l| *l == asserting || flags[*l].is_set(Flag::IsInConflictClause));
+            self.move_highest_level_second(learned);
+        }
+    }
+
+    /// Moves the literal having the highest level (but the asserting one, at position 0) to the
+    /// second position of the `learned` clause. This is required since the first two literals of
+    /// the clause are going to be watched.
+    fn move_highest_level_second(&self, learned: &mut [Literal]) {
+        if learned.len() < 2 { return; }
+
+        let mut highest = 1;
+        for i in 2..learned.len() {
+            if self.level[learned[i].var()] > self.level[learned[highest].var()] {
+                highest = i;
+            }
+        }
+        learned.swap(1, highest);
+    }
+
     /// Finds the position (in `prop_queue`) of the first unique implication point
     /// implying the conflict detected because of `conflicting`. Concretely, this
     /// is implemented with a backwards BFS traversal of the implication graph and
@@ -330,8 +832,11 @@ impl Solver {
                 Solver::mark_and_bump(*l, &mut self.flags, &mut self.var_order);
             }
         }
+        self.clauses.set_used(conflict, true);
+        self.bump_clause_activity(conflict);
 
         // backwards BFS rooted at the conflict to identify uip (and mark its cause)
+        let conflict_level = self.nb_decisions;
         let mut cursor = self.prop_queue.len();
         loop {
             cursor -= 1;
@@ -340,18 +845,21 @@ impl Solver {
             // we can stop.
             if cursor < self.forced { break }
 
+            let lit = self.prop_queue[cursor];
+
+            // Literals which were assigned out of order (chronological backtracking) at a lower
+            // level are not resolved: they simply belong to the learned clause.
+            if self.level[lit.var()] != conflict_level { continue }
+
             // Whenever we've found an UIP, it is bound to be the first one. Hence, we can stop
             if self.is_uip(cursor){ break }
 
-            // otherwise, we just proceed with the rest
-            let lit = self.prop_queue[cursor];
-
             // if a literal is not marked, we don't need to care about it
             if !self.flags[lit].is_set(Flag::IsMarked) { continue }
 
             // otherwise, we need to mark all the literal in its antecedent. Note, we know lit is no
             // decision literal because, if it were, the is_uip() would have been true.
-            match self.reason[lit.var()] {
+            match self.reason_of(lit.var()) {
                 // will never happen
                 None => panic!("{:?} is a decision (it has no reason), but is_uip() replied false"),
                 Some(c_id) => match c_id {
@@ -363,12 +871,15 @@ impl Solver {
                         for l in cause.iter().skip(1) {
                             Solver::mark_and_bump(*l, &mut self.flags, &mut self.var_order);
                         }
+                        self.clauses.set_used(reason_id, true);
+                        self.bump_clause_activity(reason_id);
                     }
                 }
             }
         }
 
         self.var_order.decay();
+        self.decay_clause_activity();
 
         return cursor;
     }
@@ -397,7 +908,7 @@ impl Solver {
             marked_lit.push(lit);
 
             // otherwise, we need to mark all the literal in its antecedent.
-            match self.reason[lit.var()] {
+            match self.reason_of(lit.var()) {
                 // will never happen
                 None => {},
                 Some(c_id) => match c_id {
@@ -434,9 +945,14 @@ impl Solver {
             return false;
         }
 
+        let level = self.level[literal.var()];
         for iter in (self.forced..position).rev() {
             let iter_literal= self.prop_queue[iter];
 
+            // skip the literals that were assigned out of order (chronological backtracking)
+            if self.level[iter_literal.var()] != level {
+                continue;
+            }
             if self.flags[iter_literal].is_set(Flag::IsMarked) {
                 return false;
             }
@@ -461,10 +977,10 @@ impl Solver {
             return flags_lit.is_set(Flag::IsImplied);
         }
 
-        match &self.reason[lit.var()] {
+        match self.reason_of(lit.var()) {
             // If it's a decision, there's no way it is implied
-            &None       => return false,
-            &Some(c_id) => match c_id {
+            None       => return false,
+            Some(c_id) => match c_id {
                 // will not happen either
                 CLAUSE_ELIDED => { return true; },
                 // will always happen
@@ -478,6 +994,7 @@ impl Solver {
                         }
                     }
                     self.flags[lit].set(Flag::IsImplied);
+                    self.bump_clause_activity(reason_id);
                     return true;
                 }
             }
@@ -510,31 +1027,181 @@ impl Solver {
         return backjump;
     }
 
+    /// Returns the decision level at which the `learned` clause becomes asserting (the highest
+    /// level among its non-asserting literals, or zero when the clause is unit).
+    ///
+    /// As a side effect, the literal having that highest level is moved to the second position of
+    /// the clause so that it gets watched. This is required when the trail is not ordered by
+    /// decision level (chronological backtracking).
+    fn assertion_level(&self, learned: &mut [Literal]) -> u32 {
+        if learned.len() < 2 { return 0; }
+
+        let mut highest = 1;
+        for i in 2..learned.len() {
+            if self.level[learned[i].var()] > self.level[learned[highest].var()] {
+                highest = i;
+            }
+        }
+        learned.swap(1, highest);
+
+        self.level[learned[1].var()]
+    }
+
+    /// Returns the decision level of the `conflict`: the highest level among the literals of the
+    /// conflicting clause.
+    ///
+    /// As a side effect, the two literals having the highest levels are moved to the watched
+    /// positions of the clause. This way, the watched literals invariants hold again whichever
+    /// level the solver backtracks to.
+    fn conflict_level(&mut self, conflict: ClauseId) -> u32 {
+        self.deactivate_clause(conflict);
+        {
+            let level = &self.level;
+            let clause = &mut self.clauses[conflict];
+            for i in 0..2 {
+                let mut highest = i;
+                for j in i+1..clause.len() {
+                    if level[clause[j].var()] > level[clause[highest].var()] {
+                        highest = j;
+                    }
+                }
+                clause.swap(i, highest);
+            }
+        }
+        self.watch_clause(conflict);
+
+        let wl1 = self.clauses[conflict][0];
+        self.level[wl1.var()]
+    }
+
+    /// Tells whether the search was asked to give up (ie: because an other solver of the
+    /// portfolio found the answer already).
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        match self.terminate {
+            Some(ref flag) => flag.load(Ordering::Relaxed),
+            None           => false
+        }
+    }
+
+    /// Perturbs the initial state of the solver using the given `seed`: the initial phase of the
+    /// variables is chosen at random, and so is a subset of variables which get a head start in
+    /// the variable ordering. This is how the solvers of a portfolio get to explore different
+    /// parts of the search space.
+    pub fn randomize(&mut self, seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        for v in 1..(self.nb_vars()+1) {
+            self.phase_saving.set(v, rng.gen());
+            if rng.gen() {
+                self.var_order.bump(Variable::from(v));
+            }
+        }
+    }
+
     // -------------------------------------------------------------------------------------------//
     // ---------------------------- RESTARTS -----------------------------------------------------//
     // -------------------------------------------------------------------------------------------//
 
     /// Asks the restart strategy and tells if a complete restart of the search should be triggered
     #[inline]
-    fn should_restart(&self) -> bool { // HERE
-        //self.restart_strat.should_restart(self.glucose_avg_global, &self.glucose_wind) // GLUCOSE
-        self.restart_strat.should_restart(self.nb_conflicts_since_restart, &self.glucose_wind) // InOut or Luby
+    fn should_restart(&self) -> bool {
+        if self.restart_strat.is_dynamic() && self.nb_conflicts < self.restarts_blocked_until {
+            return false;
+        }
+
+        match self.restart_strat {
+            RestartStrategy::InOut(ref s)   => s.should_restart(self.nb_conflicts_since_restart, &self.glucose_wind),
+            RestartStrategy::Luby(ref s)    => s.should_restart(self.nb_conflicts_since_restart, &self.glucose_wind),
+            RestartStrategy::Glucose(ref s) => s.should_restart(self.glucose_avg_global, &self.glucose_wind),
+            RestartStrategy::Ema(ref s)     => s.should_restart(self.nb_conflicts_since_restart, &self.glucose_wind),
+        }
+    }
+
+    /// Postpones the (dynamic) restarts when the trail is much larger than it was during the
+    /// recent conflicts. This must be called upon each conflict, before it gets resolved.
+    ///
+    /// A trail that suddenly gets larger than usual suggests that the search is approaching a
+    /// model; restarting would then throw away a valuable part of the assignment. The restarts
+    /// are postponed for as many conflicts as it takes to fill the window of recent lbds again
+    /// (which is what clearing that window does in Glucose).
+    ///
+    /// See `Refining Restarts Strategies for SAT and UNSAT` -- Audemard, Simon (CP 2012).
+    fn block_restarts(&mut self) {
+        let trail = self.prop_queue.len();
+
+        if self.nb_conflicts > RESTART_BLOCKING_MIN_CONFLICTS
+            && self.trail_wind.is_full()
+            && trail as f64 > RESTART_BLOCKING_MARGIN * self.trail_wind.average() {
+            self.restarts_blocked_until = self.nb_conflicts + self.glucose_wind.capacity();
+        }
+
+        self.trail_wind.push(trail as u32);
     }
 
     /// Restarts the search to find a better path towards the solution.
     /// The choice of when to restart is left to the implementation of the restart strategy.
+    ///
+    /// # Note
+    /// When `reuse_trail` is on, the restart is only partial: the decision levels that would be
+    /// re-built identically after a complete restart are kept. Because the learned clause
+    /// minimization (LCM) must happen at the root level, it always triggers complete restarts.
+    ///
+    /// The clauses shared by the other solvers of a portfolio are imported upon restart. Because
+    /// these must be added at the root level, receiving some clauses also triggers a complete
+    /// restart.
     fn restart(&mut self) {
-        let pos = self.root();
-        self.rollback(pos);
+        let imported = match self.exchange {
+            Some(ref exchange) => exchange.import(),
+            None               => vec![]
+        };
+
+        let partial = self.reuse_trail && !self.lcm && imported.is_empty();
+        let level   = if partial { self.reuse_level() } else { 0 };
+        self.backtrack(level);
         if self.lcm {
             self.clause_minimization();
         }
+        for (lbd, literals) in imported {
+            self.import_clause(lbd, &literals);
+        }
         self.restart_strat.set_next_limit();
         self.nb_restarts += 1;
         self.nb_conflicts_since_restart = 0;
         self.glucose_wind.clear();
     }
 
+    /// Returns the decision level the search would reach again (by taking the very same decisions)
+    /// after a complete restart. That is to say, the number of leading decisions which are about
+    /// variables that are more active than the next variable to branch on.
+    fn reuse_level(&mut self) -> u32 {
+        // Find the next decision variable (dropping the assigned ones, just like `decide` would)
+        while !self.var_order.is_empty() {
+            let next = self.var_order.peek_top();
+            if self.is_undef(Literal::positive(next)) {
+                break;
+            }
+            self.var_order.pop_top();
+        }
+        if self.var_order.is_empty() {
+            return self.nb_decisions;
+        }
+
+        let next_score = self.var_order.score(self.var_order.peek_top());
+        let mut level = 0;
+        for i in self.forced..self.prop_queue.len() {
+            let lit = self.prop_queue[i];
+            if !self.is_decision(lit) {
+                continue;
+            }
+            if self.var_order.score(lit.var()) < next_score {
+                break;
+            }
+            level += 1;
+        }
+        level
+    }
+
     // -------------------------------------------------------------------------------------------//
     // ---------------------------- CLAUSE DELETION ----------------------------------------------//
     // -------------------------------------------------------------------------------------------//
@@ -550,10 +1217,10 @@ impl Solver {
     ///
     /// This function tries to dynamically improve the LBD of the bumped clause. If that turns out
     /// to be a success (new, smaller LBD found) then the clause is protected against deletion for
-    /// one round.
+    /// one round and possibly promoted to a better tier.
     #[inline]
     fn clause_bump(&mut self, c_id: ClauseId) {
-        let old_lbd = self.lbd[c_id];
+        let old_lbd = self.clauses.lbd(c_id);
 
         // If it is already a glue clause, there is no point in trying to improve LBD any further
         if old_lbd <= 2 {
@@ -562,33 +1229,87 @@ impl Solver {
 
         let new_lbd = self.literal_block_distance(c_id);
         if new_lbd < old_lbd {
-            self.lbd[c_id] = new_lbd;
-            self.lbd_recently_updated.insert(c_id);
+            // the xor reasons are never promoted (see `add_xor_clause`)
+            let tier = if self.clauses.is_xor_reason(c_id) {
+                Tier::Local
+            } else {
+                self.clauses.tier(c_id).min(Tier::of(new_lbd))
+            };
+            self.clauses.set_lbd(c_id, new_lbd);
+            self.clauses.set_protected(c_id, true);
+            self.clauses.set_tier(c_id, tier);
+        }
+    }
+
+    /// Increases the activity of the given clause. Whenever the activity of some clause grows too
+    /// large, the activity of all clauses is rescaled to avoid overflows.
+    fn bump_clause_activity(&mut self, c_id: ClauseId) {
+        let activity = self.clauses.activity(c_id) + self.clause_inc;
+        self.clauses.set_activity(c_id, activity);
+
+        if activity > 1e20 {
+            let ids: Vec<ClauseId> = self.clauses.ids().collect();
+            for id in ids {
+                let scaled = self.clauses.activity(id) * 1e-20;
+                self.clauses.set_activity(id, scaled);
+            }
+            self.clause_inc *= 1e-20;
         }
     }
 
+    /// Makes the activity of all clauses decay. Rather than scaling all the scores down, the
+    /// increment used for future bumps is scaled up.
+    fn decay_clause_activity(&mut self) {
+        self.clause_inc /= self.clause_decay;
+    }
+
     /// Forgets some of the less useful clauses to speed up the propagation process.
+    ///
+    /// The learned clauses are managed in three tiers: the core clauses are never forgotten, the
+    /// tier2 clauses are demoted to the local tier when they have not been used since the previous
+    /// reduction and the least active half of the local clauses is forgotten.
     fn reduce_db(&mut self) {
-        // sort the clauses according to their heuristic quality score (LBD)
-        let nb_clauses = self.clauses.len();
-        let mut remove_agenda: Vec<ClauseId> = (0..nb_clauses)
+        // sort the local clauses according to their activity (the LBD only breaks the ties)
+        let nb_local = self.clauses.ids()
+            .filter(|id| self.clauses.is_learned(*id) && self.clauses.tier(*id) == Tier::Local)
+            .count();
+
+        let mut remove_agenda: Vec<ClauseId> = self.clauses.ids()
             .filter(|id| self.can_forget(*id))
             .collect();
 
-        remove_agenda.sort_unstable_by_key(|c| self.lbd[*c]);
-        remove_agenda.reverse();
+        // the worst clauses come first: the least active ones and, among these, those with the
+        // highest LBD.
+        {
+            let clauses = &self.clauses;
+            remove_agenda.sort_by(|a, b| clauses.activity(*a).partial_cmp(&clauses.activity(*b)).unwrap()
+                .then(clauses.lbd(*b).cmp(&clauses.lbd(*a))));
+        }
 
-        // reduces the size of the database by removing half of the worst clauses.
+        // reduces the size of the database by removing half of the worst local clauses.
         // It should be noted though that unary and binary clauses are *never* removed
         // and that 'locked' clauses (those who are reason for some assignment) are kept as well
-        let limit = self.nb_learned / 2;
+        let limit = nb_local / 2;
         remove_agenda.truncate(limit);
 
         // Actually proceed to the clause deletion
-        self.remove_all(&mut remove_agenda);
+        self.remove_all(&remove_agenda);
+
+        // Demote the tier2 clauses which have not been used since the last reduction and remove
+        // the 'protection' on all the clauses
+        let ids: Vec<ClauseId> = self.clauses.ids().collect();
+        for id in ids {
+            if self.clauses.tier(id) == Tier::Tier2 && !self.clauses.is_used(id) {
+                self.clauses.set_tier(id, Tier::Local);
+            }
+            self.clauses.set_protected(id, false);
+            self.clauses.set_used(id, false);
+        }
 
-        // Remove 'protection' on all the clauses
-        self.lbd_recently_updated.clear();
+        // Reclaim the memory of the forgotten clauses
+        if self.clauses.should_collect_garbage() {
+            self.collect_garbage();
+        }
 
         // allow the solver to learn somewhat more clauses before we reduce the database again
         self.max_learned = (self.max_learned * 3) / 2;
@@ -598,13 +1319,18 @@ impl Solver {
     /// Normally all clauses that are learned and not being used at the moment (not locked) can
     /// safely be forgotten by the solver. Meanwhile, this method incorporates some heuristic
     /// knowledge and keeps all the the clauses that are 'good enough'.
+    ///
+    /// # Note
+    /// Binary clauses are never forgotten: they live in the binary watch lists and are cheap
+    /// enough to be kept forever.
     fn can_forget(&self, clause_id: ClauseId) -> bool {
-        let ref clause = self.clauses[clause_id];
+        let ref clauses = self.clauses;
 
-        clause.is_learned
-            &&  clause.len() > 2
-            &&  self.lbd[clause_id] > 2
-            && !self.lbd_recently_updated[clause_id]
+        clauses.is_learned(clause_id)
+            &&  clauses[clause_id].len() > 2
+            &&  clauses.tier(clause_id) == Tier::Local
+            && !clauses.is_protected(clause_id)
+            && !clauses.is_used(clause_id)
             && !self.is_locked(clause_id)
     }
 
@@ -612,14 +1338,19 @@ impl Solver {
     fn literal_block_distance(&self, clause_id: ClauseId) -> u32 {
         // Shortcut: Having an LBD of two means it is a glue clause. It will never be deleted so
         // hence there is no point in recomputing it every time as it is not going to be improved.
-        if self.lbd[clause_id] <= 2 { return self.lbd[clause_id]; }
+        let current = self.clauses.lbd(clause_id);
+        if current <= 2 { return current; }
+
+        self.lbd_of(&self.clauses[clause_id])
+    }
 
+    /// Computes the literal block distance (LBD) of the given set of literals.
+    fn lbd_of(&self, literals: &[Literal]) -> u32 {
         let nb_levels = self.level.len();
         let mut blocks = FixedBitSet::with_capacity(nb_levels +1 );
         let mut lbd = 0;
 
-        let ref clause = self.clauses[clause_id];
-        for lit in clause.iter() {
+        for lit in literals.iter() {
             let level = self.level[lit.var()] as usize;
 
             if !blocks.contains(level) {
@@ -628,7 +1359,7 @@ impl Solver {
             }
         }
 
-        return lbd;
+        lbd
     }
 
     // -------------------------------------------------------------------------------------------//
@@ -655,6 +1386,67 @@ impl Solver {
         // shrink the trail and reset the propagated cursor appropriately
         self.propagated = until;
         self.prop_queue.resize(until, lit(iint::max_value()));
+        self.backtrack_xors(until);
+    }
+
+    /// Rolls back the search until the given decision `level`. That is to say, it undoes all the
+    /// assignments that were made at some level strictly greater than `level`.
+    ///
+    /// # Note
+    /// When chronological backtracking is enabled, the trail is not necessarily ordered by
+    /// decision level. In that case, the literals that were assigned out of order at some level
+    /// lower or equal to `level` are kept on the trail (but they will be propagated again).
+    fn backtrack(&mut self, level: u32) {
+        let len = self.prop_queue.len();
+        let until = (self.forced..len)
+            .find(|&i| self.level[self.prop_queue[i].var()] > level)
+            .unwrap_or(len);
+
+        if !self.chrono_backtrack {
+            self.rollback(until);
+            return;
+        }
+
+        // Unravel the literals assigned above `level` and compact the trail with the others
+        let mut kept = until;
+        for i in until..len {
+            let lit = self.prop_queue[i];
+            if self.level[lit.var()] > level {
+                self.undo(lit, true);
+            } else {
+                self.prop_queue[kept] = lit;
+                kept += 1;
+            }
+        }
+
+        // Clear the analysis of the literals which remain on the trail
+        for i in self.forced..kept {
+            let lit = self.prop_queue[i];
+            self.flags[lit].reset();
+        }
+
+        self.propagated = until;
+        self.prop_queue.truncate(kept);
+        self.backtrack_xors(until);
+
+        // Everything that remains when going back to the root follows from the problem statement
+        if level == 0 {
+            for i in self.forced..kept {
+                let lit = self.prop_queue[i];
+                self.flags[!lit].set(Flag::IsForced);
+            }
+            self.forced = kept;
+        }
+    }
+
+    /// Tells the Gauss-Jordan elimination engine that the assignments of the trail from position
+    /// `until` onwards were undone (or moved).
+    #[inline]
+    fn backtrack_xors(&mut self, until: usize) {
+        if self.xor_notified > until {
+            self.xor_notified = until;
+            self.gauss.backtrack();
+        }
     }
 
     /// Same as rollback but disable the phase_saving (for LCM).
@@ -677,6 +1469,7 @@ impl Solver {
         // shrink the trail and reset the propagated cursor appropriately
         self.propagated = until;
         self.prop_queue.resize(until, lit(iint::max_value()));
+        self.backtrack_xors(until);
     }
 
     /// Undo all state changes that have been done for some given literal
@@ -684,6 +1477,8 @@ impl Solver {
         if self.is_decision(lit) {
             self.nb_decisions -= 1;
         }
+        // some assumption might not hold anymore
+        self.nb_assumed = 0;
 
         // clear all flags
         self.flags[lit].reset();
@@ -741,6 +1536,13 @@ impl Solver {
     // ---------------------------- CLAUSE DB ----------------------------------------------------//
     // -------------------------------------------------------------------------------------------//
 
+    /// Adds a problem clause given by its literals (rather than by their DIMACS representation)
+    /// to the database. This is the same as `add_problem_clause` otherwise.
+    pub fn add_clause_literals(&mut self, clause: &[Literal]) -> Result<ClauseId, ()> {
+        let mut clause: Vec<iint> = clause.iter().map(|l| l.to_isize() as iint).collect();
+        self.add_problem_clause(&mut clause)
+    }
+
     /// This function adds a problem clause to the database.
     ///
     /// # Note
@@ -753,6 +1555,11 @@ impl Solver {
     /// This function returns a Result (Ok, Err) with the id of the clause that has been added.
     /// However, when it is decided not to add the clause to database, Ok(CLAUSE_ELIDED) is returned.
     pub fn add_problem_clause(&mut self, c : &mut Vec<iint>) -> Result<ClauseId, ()> {
+        // clauses can only be added at the root level (ie: between two incremental searches)
+        if self.nb_decisions > 0 {
+            self.backtrack(0);
+        }
+
         // don't add the clause if it is a tautology
         c.sort_unstable_by(|x, y| x.abs().cmp(&y.abs()));
 
@@ -782,20 +1589,118 @@ impl Solver {
 
     }
 
-    /// This function adds a learned clause to the database.
-    ///
-    /// In this case, we dont waste time checking for tautologies (both polarities) since the
-    /// conflict resolution algorithm prevents the occurence of such clauses.
+    /// Adds the XOR constraint requiring the exclusive or of the given `literals` to hold (as an
+    /// `x` line of the CryptoMiniSat DIMACS dialect) to the problem. The constraint is not
+    /// turned into clauses: it is propagated by Gauss-Jordan elimination.
     ///
     /// # Note
-    /// It could still be beneficial to avoid adding learned clauses that are forcibly satisfied.
-    /// However, as tempting as it is, I have refrained from doing this since it impacts conflict
-    /// resolution (the conflict resolution strategy asserts the first literal of the learned clause
-    /// and assumes that clause is added to the database).
-    fn add_learned_clause(&mut self, c :Vec<Literal>) -> Result<ClauseId, ()> {
-        let subsume = self.subsume_enable;
+    /// Just like the clauses, the constraints can only be added at the root level. When the
+    /// constraint makes the problem unsat, the solver remembers it.
+    pub fn add_xor(&mut self, literals: &[iint]) {
+        if self.nb_decisions > 0 {
+            self.backtrack(0);
+        }
 
-        let result = self.add_clause(Clause::new(c, true), subsume);
+        let literals: Vec<Literal> = literals.iter().map(|&l| Literal::from(l)).collect();
+        let xor = Xor::new(&literals);
+
+        match xor.vars.len() {
+            // the constraint is either trivially satisfied or unsatisfiable
+            0 => self.is_unsat |= xor.parity,
+            1 => {
+                let sign = if xor.parity { Sign::Positive } else { Sign::Negative };
+                let unit = Literal::from_var(xor.vars[0], sign);
+                self.is_unsat |= self.assign(unit, Some(CLAUSE_ELIDED)).is_err();
+            },
+            _ => self.gauss.add(&xor, false)
+        }
+    }
+
+    /// Looks for the XOR constraints which are encoded as sets of clauses among the problem
+    /// clauses, and hands them over to the Gauss-Jordan elimination engine (the clauses are kept
+    /// in the database).
+    ///
+    /// # Return Value
+    /// The number of XOR constraints that were found.
+    pub fn detect_xors(&mut self) -> usize {
+        let clauses: Vec<Vec<Literal>> = self.clauses.ids()
+            .filter(|&c| !self.clauses.is_learned(c))
+            .map(|c| self.clauses[c].to_vec())
+            .collect();
+
+        let xors = find_xors(&clauses);
+        for xor in xors.iter() {
+            self.gauss.add(xor, true);
+        }
+        xors.len()
+    }
+
+    /// Returns the number of XOR constraints propagated by Gauss-Jordan elimination
+    #[inline]
+    pub fn nb_xors(&self) -> usize { self.gauss.len() }
+
+    /// Removes the problem clauses which are satisfied at the root level and returns their number.
+    /// This is worth it once many clauses have been disabled for good (ie: the clauses guarded by
+    /// an activation literal which has been falsified): they would otherwise still be watched.
+    /// The learned clauses are left to the reduction of the database.
+    pub fn remove_satisfied(&mut self) -> usize {
+        if self.nb_decisions > 0 {
+            self.backtrack(0);
+        }
+
+        let satisfied: Vec<ClauseId> = self.clauses.ids()
+            .filter(|&c| !self.clauses.is_learned(c))
+            .filter(|&c| self.clauses[c].iter().any(|&l| self.is_true(l) && self.level[l.var()] == 0))
+            .collect();
+        self.remove_all(&satisfied);
+
+        if self.clauses.should_collect_garbage() {
+            self.collect_garbage();
+        }
+        satisfied.len()
+    }
+
+    /// Removes the XOR constraints in which the given variable occurs and returns their number.
+    /// This is meant for an auxiliary variable defined by an XOR constraint (see `add_xor`)
+    /// which is no longer needed: since the variable occurs nowhere else, dropping its constraint
+    /// does not change the models of the rest of the problem. The learned clauses in which the
+    /// variable occurs (which may have been derived from the constraint) are dropped as well, so
+    /// that the variable is left entirely unconstrained.
+    pub fn remove_xors(&mut self, v: Variable) -> usize {
+        if self.nb_decisions > 0 {
+            self.backtrack(0);
+        }
+
+        let derived: Vec<ClauseId> = self.clauses.ids()
+            .filter(|&c| self.clauses.is_learned(c))
+            .filter(|&c| self.clauses[c].iter().any(|l| l.var() == v))
+            .collect();
+        self.remove_all(&derived);
+
+        self.gauss.remove(v)
+    }
+
+    /// This function adds a learned clause to the database.
+    ///
+    /// In this case, we dont waste time checking for tautologies (both polarities) since the
+    /// conflict resolution algorithm prevents the occurence of such clauses.
+    ///
+    /// # Note
+    /// It could still be beneficial to avoid adding learned clauses that are forcibly satisfied.
+    /// However, as tempting as it is, I have refrained from doing this since it impacts conflict
+    /// resolution (the conflict resolution strategy asserts the first literal of the learned clause
+    /// and assumes that clause is added to the database).
+    fn add_learned_clause(&mut self, c :Vec<Literal>) -> Result<ClauseId, ()> {
+        let subsume = self.subsume_enable;
+
+        if let Some(ref exchange) = self.exchange {
+            let lbd = self.lbd_of(&c);
+            if exchange.should_export(c.len(), lbd) {
+                exchange.export(lbd, &c);
+            }
+        }
+
+        let result = self.add_clause(Clause::new(c, true), subsume);
 
         if result.is_ok() && result.unwrap() != CLAUSE_ELIDED {
             self.nb_learned += 1;
@@ -803,31 +1708,60 @@ impl Solver {
             // set an initial lbd for learned clauses
             let clause_id = result.unwrap();
             let lbd = self.literal_block_distance(clause_id);
-            self.lbd[clause_id] = lbd;
+            self.clauses.set_lbd(clause_id, lbd);
+            self.clauses.set_tier(clause_id, Tier::of(lbd));
+            self.bump_clause_activity(clause_id);
             self.glucose_avg_global = self.glucose_avg_global + (lbd as f64 - self.glucose_avg_global)/self.nb_learned as f64;
             self.glucose_wind.push(lbd);
-            if self.glucose_wind.len() > self.glucose_size {
-                self.glucose_wind.swap_remove(self.nb_conflicts_since_restart % (self.glucose_size +1));
+            if let RestartStrategy::Ema(ref mut ema) = self.restart_strat {
+                ema.update(lbd);
             }
-            self.lbd_recently_updated.insert(clause_id);
+            self.clauses.set_protected(clause_id, true);
         }
 
         return result;
     }
 
+    /// Adds a clause learned by an other solver of the portfolio to the database. This must
+    /// happen at the root level: the literals falsified at the root are dropped from the clause
+    /// and the clause is ignored if one of its literals is forced.
+    fn import_clause(&mut self, lbd: u32, literals: &[Literal]) {
+        debug_assert_eq!(self.nb_decisions, 0);
+
+        if literals.iter().any(|l| self.is_true(*l)) { return; }
+
+        let literals: Vec<Literal> = literals.iter()
+            .cloned()
+            .filter(|l| !self.is_false(*l))
+            .collect();
+
+        let size   = literals.len() as u32;
+        let result = self.add_clause(Clause::new(literals, true), false);
+
+        if let Ok(clause_id) = result {
+            if clause_id != CLAUSE_ELIDED {
+                let lbd = lbd.min(size);
+                self.nb_learned += 1;
+                self.clauses.set_lbd(clause_id, lbd);
+                self.clauses.set_tier(clause_id, Tier::of(lbd));
+            }
+        }
+    }
+
     /// Removes a clause from the database.
     ///
     /// In order to keep a consistent state while removing a clause from the database, we must
     /// ensure that :
     /// - the clause identifier is removed from the all watchers list.
     /// - no reason depends on the removed clause
-    /// - all references (watchers, reason) to the the clause that "recycles" the removed clause' id
-    ///   are renumbered appropriately. (Note: an identifier might not be recycled if the removed
-    ///   clause was the last one in database).
+    ///
+    /// # Note
+    /// The clause is only marked as deleted in the arena. The memory it occupies is reclaimed
+    /// upon the next garbage collection (see `collect_garbage`).
     fn remove_clause(&mut self, clause_id: ClauseId) {
         // Print the clause to produce the UNSAT certificate if it was required.
         if self.drat {
-            println!("d {}", self.clauses[clause_id].to_dimacs());
+            println!("d {}", self.clauses.to_dimacs(clause_id));
         }
 
         // Remove clause_id from the watchers lists
@@ -836,41 +1770,45 @@ impl Solver {
         // Remove clause_id from the reason
         self.unlock_clause(clause_id);
 
-        // If a clause has been renamed (index swap) reflect that
-        let last = self.clauses.len() - 1;
-        if last != clause_id {
-            self.rename_clause(last, clause_id);
-        }
-
         // Effectively remove the clause
-        if self.clauses[clause_id].is_learned {
+        if self.clauses.is_xor_reason(clause_id) {
+            self.nb_xor_reasons -= 1;
+        } else if self.clauses.is_learned(clause_id) {
             self.nb_learned -= 1;
             self.nb_learned_since_minimiation -= 1;
         }
 
-        self.clauses.swap_remove(clause_id);
-        self.lbd.swap_remove(clause_id);
+        self.clauses.delete(clause_id);
     }
 
     /// Proceed to the deletion of a set of clauses in the database.
     /// All the clauses identified by an id in the remove_agenda will be removed.
-    fn remove_all(&mut self, remove_agenda: &mut [ClauseId]) {
-        // Actually proceed to the clause deletion
-        let nb_delete = remove_agenda.len();
-        for i in 0..nb_delete {
-            let id = remove_agenda[i];
+    fn remove_all(&mut self, remove_agenda: &[ClauseId]) {
+        for id in remove_agenda.iter() {
+            self.remove_clause(*id);
+        }
+    }
 
-            let last = self.clauses.len() - 1;
-            self.remove_clause(id);
+    /// Compacts the clause database to reclaim the memory used by the deleted clauses.
+    ///
+    /// Because the compaction relocates the clauses, all the references (watchers, reasons) to the
+    /// clauses that have moved are updated in bulk.
+    fn collect_garbage(&mut self) {
+        let relocation = self.clauses.collect_garbage();
+
+        for watchers in self.watchers.iter_mut().chain(self.binaries.iter_mut()) {
+            for w in watchers.iter_mut() {
+                w.clause = relocation.get(w.clause);
+            }
+        }
 
-            // Because remove_clause might have swapped `id` and `last`, we need to fix that up in
-            // the in the agenda (to avoid panicking on out of bounds index)
-            if id != last {
-                for j in i+1..nb_delete {
-                    if remove_agenda[j] == last {
-                        remove_agenda[j] = id;
-                    }
-                }
+        for i in 0..self.prop_queue.len() {
+            let v = self.prop_queue[i].var();
+            match self.reason[v] {
+                Some(c_id) if c_id != CLAUSE_ELIDED && c_id != XOR_IMPLIED => {
+                    self.reason[v] = Some(relocation.get(c_id));
+                },
+                _ => { /* nothing to do */ }
             }
         }
     }
@@ -890,10 +1828,6 @@ impl Solver {
             println!("a {}", clause.to_dimacs());
         }
 
-
-
-        let c_id= self.clauses.len();
-
         // if it is the empty clause that we're adding, the problem is solved and probably unsat
         if clause.len() == 0 {
             self.is_unsat = true;
@@ -913,20 +1847,15 @@ impl Solver {
         // Using `self.activate(c_id)` would have been correct too. However, I chose not to opt for
         // that solution since it involves quite a severe performance penalty.
         // -------------------------
-        let wl1 = clause[0];
-        let wl2 = clause[1];
-
-        self.clauses.push(clause);
-        self.lbd.push(u32::max_value());
-
+        let c_id = self.clauses.alloc(&clause, clause.is_learned);
 
         if subsume { // Backward subsumption on creation
             let mut delete_clauses: Vec<ClauseId> = vec![];
-            for clause_id in (self.clauses.len() - self.nb_learned)..self.clauses.len() - 1 {
-                let clause = &(self.clauses[c_id]);
-                let clause2 = &(self.clauses[clause_id]);
-
-                if inprocessing::subsume(clause, clause2) {
+            for clause_id in self.clauses.ids() {
+                if clause_id == c_id || !self.clauses.is_learned(clause_id) {
+                    continue;
+                }
+                if inprocessing::subsume(&self.clauses[c_id], &self.clauses[clause_id]) {
                     delete_clauses.push(clause_id);
                 }
             }
@@ -936,49 +1865,8 @@ impl Solver {
             }
         }
 
-
-        if c_id >= self.lbd_recently_updated.len() {
-            self.lbd_recently_updated.grow( c_id * 2 );
-        }
-        self.watchers[wl1].push(c_id);
-        self.watchers[wl2].push(c_id);
-        return Ok(c_id);
-    }
-
-    /// Renames the clause identified by `from` and gives it the new identifier `into`.
-    ///
-    /// This method is useful to fix the state of the solver after we removed a clause. Indeed, the
-    /// removal is done in O(1) but potentially moves a clause at an other location in the database.
-    /// In that case, is useful to _rename_ the clause so that other parts of the solver are also
-    /// aware of the location change.
-    fn rename_clause(&mut self, from: ClauseId, into: ClauseId) {
-        // Replace last by clause_id in the watchers lists
-        for i in 0..2 { // Note: 0..2 is only ok as long as it is impossible to remove clauses that have become unit
-            let watched = self.clauses[from][i];
-
-            let nb_watchers = self.watchers[watched].len();
-            for i in 0..nb_watchers {
-                if self.watchers[watched][i] == from {
-                    self.watchers[watched][i] = into;
-                    break;
-                }
-            }
-        }
-
-        // Replace last by clause_id in the reason
-        let first_variable = self.clauses[from][0].var();
-        match self.reason[first_variable] {
-            None => { /* nothing to do */ },
-            Some(r) => {
-                if r == from {
-                    self.reason[first_variable] = Some(into)
-                }
-            }
-        }
-
-        // Replace lbd-based protection
-        let protected = self.lbd_recently_updated.contains(from);
-        self.lbd_recently_updated.set(into, protected);
+        self.watch_clause(c_id);
+        Ok(c_id)
     }
 
     fn clause_minimization(&mut self){
@@ -993,12 +1881,14 @@ impl Solver {
     pub fn preprocess(&mut self){
         let mut remove_clauses = vec![];
 
-        for clause_id in 0..self.clauses.len(){
-            if self.clauses[clause_id].len() > 30 || self.lbd[clause_id] > 6 {
+        let ids: Vec<ClauseId> = self.clauses.ids().collect();
+        for clause_id in ids {
+            if self.clauses.is_deleted(clause_id) { continue; }
+            if self.clauses[clause_id].len() > 30 || self.clauses.lbd(clause_id) > 6 {
                 continue;
             }
 
-            let mut clause = self.clauses[clause_id].as_slice().to_vec();
+            let mut clause = self.clauses[clause_id].to_vec();
             let mut minimized_c = vec![];
             let mut remove_lit = vec![];
             let mut added = false;
@@ -1028,9 +1918,8 @@ impl Solver {
                             break;
                         }
                         minimized_c.push(lite);
-                        for j in (i+1..clause.len()).rev(){ // remove all the other literals
-                            self.clauses[clause_id].swap_remove(j);
-                        }
+                        // remove all the other literals
+                        self.clauses.truncate(clause_id, i+1);
                         break;
                     },
                     Bool::False => { // Cannot lead to a conflict
@@ -1088,7 +1977,7 @@ impl Solver {
                     } else {
 
                         for l in remove_lit.iter().rev() {
-                            self.clauses[clause_id].swap_remove(*l);
+                            self.clauses.swap_remove_literal(clause_id, *l);
                         }
                         self.activate_clause(clause_id);
                     }
@@ -1106,12 +1995,18 @@ impl Solver {
     /// The actual function to minimize the learned clauses
     fn minimize_l(&mut self){
         let mut remove_clauses = vec![];
-        for clause_id in self.clauses.len()-self.nb_learned_since_minimiation..self.clauses.len(){
-            if self.clauses[clause_id].len() > 30 || self.lbd[clause_id] > 6 {
+        // the clauses learned since the last minimization are the last ones in the database
+        let learned: Vec<ClauseId> = self.clauses.ids()
+            .filter(|id| self.clauses.is_learned(*id) && !self.clauses.is_xor_reason(*id))
+            .collect();
+        let since = learned.len() - self.nb_learned_since_minimiation;
+        for clause_id in learned[since..].iter().cloned() {
+            if self.clauses.is_deleted(clause_id) { continue; }
+            if self.clauses[clause_id].len() > 30 || self.clauses.lbd(clause_id) > 6 {
                 continue;
             }
 
-            let mut clause = self.clauses[clause_id].as_slice().to_vec();
+            let mut clause = self.clauses[clause_id].to_vec();
             let mut minimized_c = vec![];
             let mut remove_lit = vec![];
             let mut added = false;
@@ -1141,9 +2036,8 @@ impl Solver {
                             break;
                         }
                         minimized_c.push(lite);
-                        for j in (i+1..clause.len()).rev(){ // remove all the other literals
-                            self.clauses[clause_id].swap_remove(j);
-                        }
+                        // remove all the other literals
+                        self.clauses.truncate(clause_id, i+1);
                         break;
                     },
                     Bool::False => { // Cannot lead to a conflict
@@ -1201,7 +2095,7 @@ impl Solver {
                     } else {
 
                         for l in remove_lit.iter().rev() {
-                            self.clauses[clause_id].swap_remove(*l);
+                            self.clauses.swap_remove_literal(clause_id, *l);
                         }
 
                         self.activate_clause(clause_id);
@@ -1223,6 +2117,13 @@ impl Solver {
 
     /// Tries to find a new literal that can be watched by the given clause.
     ///
+    /// # Invariants
+    /// The watched literals scheme relies on the following two invariants:
+    /// - A. The two literals watched by a clause are always the first two literals of the clause
+    ///   (positions 0 and 1).
+    /// - B. Whenever a clause is the reason for some assignment, the literal it implies sits at
+    ///   position 0. (Conflict analysis relies on it to skip the implied literal).
+    ///
     /// # Return Value
     /// This function returns a Result<Literal, Literal> that mut be interpreted as follows:
     /// - Ok( l ) means that the clause found that l is not satisfied and can therefore be
@@ -1304,8 +2205,6 @@ impl Solver {
 
         let mut wl1 = self.clauses[c_id][0];
         let mut pl1 = 0;
-
-        let mut wl2 = self.clauses[c_id][1];
         let mut pl2 = 1;
 
         {
@@ -1315,15 +2214,14 @@ impl Solver {
 
             if let Some((p,&l)) = watchables.next() {
                 // avoid the possible case where both wl1 and wl2 designate the same literal
-                if l == wl2 { wl2 = wl1; pl2 = pl1 }
+                if p == pl2 { pl2 = pl1 }
 
                 wl1 =  l;
                 pl1 =  p;
                 cnt += 1;
             }
 
-            if let Some((p,&l)) = watchables.next() {
-                wl2 =  l;
+            if let Some((p,_)) = watchables.next() {
                 pl2 =  p;
                 cnt += 1;
             }
@@ -1345,8 +2243,19 @@ impl Solver {
         self.clauses[c_id].swap(0, pl1);
         self.clauses[c_id].swap(1, pl2);
 
-        self.watchers[wl1].push(c_id);
-        self.watchers[wl2].push(c_id);
+        self.watch_clause(c_id);
+    }
+
+    /// Starts watching the first two literals of the given clause. Binary clauses are watched in
+    /// the `binaries` lists while all the other clauses are watched in the `watchers` lists.
+    #[inline]
+    fn watch_clause(&mut self, c_id: ClauseId) {
+        let wl1 = self.clauses[c_id][0];
+        let wl2 = self.clauses[c_id][1];
+
+        let watchers = if self.clauses[c_id].len() == 2 { &mut self.binaries } else { &mut self.watchers };
+        watchers[wl1].push(Watcher::new(c_id, wl2));
+        watchers[wl2].push(Watcher::new(c_id, wl1));
     }
 
     /// Deactivate the given clause. That is to say, it removes all watches for the given clause.
@@ -1355,19 +2264,29 @@ impl Solver {
     /// It is assumed that clauses of size 0 and 1 are out of the way and we're certain to be left
     /// only with clauses having at least two literals.
     fn deactivate_clause(&mut self, c_id: ClauseId) {
+        let watchers = if self.clauses[c_id].len() == 2 { &mut self.binaries } else { &mut self.watchers };
+
         for i in 0..2 {
             let watched = self.clauses[c_id][i];
 
-            let nb_watchers = self.watchers[watched].len();
+            let nb_watchers = watchers[watched].len();
             for j in (0..nb_watchers).rev() {
-                if self.watchers[watched][j] == c_id {
-                    self.watchers[watched].swap_remove(j);
+                if watchers[watched][j].clause == c_id {
+                    watchers[watched].swap_remove(j);
                     break;
                 }
             }
         }
     }
 
+    /// Returns an iterator over the literals which are directly implied by `lit` through some
+    /// binary clause. In other words, these are the edges leaving `lit` in the binary implication
+    /// graph. This is meant to be used by inprocessing techniques such as failed literal probing,
+    /// equivalent literal substitution (SCC) or transitive reduction.
+    pub fn binary_implications<'a>(&'a self, lit: Literal) -> impl Iterator<Item=Literal> + 'a {
+        self.binaries[!lit].iter().map(|w| w.blocker)
+    }
+
     // -------------------------------------------------------------------------------------------//
     // ---------------------------- PROPAGATION --------------------------------------------------//
     // -------------------------------------------------------------------------------------------//
@@ -1394,12 +2313,12 @@ impl Solver {
 
 
                 // Level can only be set now that the nb_decisions has been updated if need be
-                self.level [lit.var()] = self.nb_decisions;
+                self.level [lit.var()] = self.assignment_level(lit, reason);
 
                 match reason {
                     None      => {/* it cant be bumped */},
                     Some(c_id)=> {
-                        if c_id != CLAUSE_ELIDED {
+                        if c_id != CLAUSE_ELIDED && c_id != XOR_IMPLIED {
                             self.clause_bump(c_id);
 
                         }
@@ -1418,12 +2337,44 @@ impl Solver {
         }
     }
 
+    /// Returns the decision level at which a literal implied by the given `reason` is assigned.
+    /// Normally, this is the current decision level. However, when chronological backtracking is
+    /// enabled, it is the highest level among the (falsified) literals of the reason clause.
+    #[inline]
+    fn assignment_level(&self, lit: Literal, reason: Option<Reason>) -> u32 {
+        match reason {
+            Some(XOR_IMPLIED) if self.chrono_backtrack => {
+                self.gauss.explanation(lit.var()).iter()
+                    .skip(1)
+                    .map(|l| self.level[l.var()])
+                    .max()
+                    .unwrap_or(0)
+            },
+            Some(c_id) if self.chrono_backtrack && c_id != CLAUSE_ELIDED => {
+                self.clauses[c_id].iter()
+                    .skip(1)
+                    .map(|l| self.level[l.var()])
+                    .max()
+                    .unwrap_or(0)
+            },
+            _ => self.nb_decisions
+        }
+    }
+
     /// This method propagates the information about all the literals that have been
     /// enqueued. It returns an optional conflicting clause whenever conflict is detected
     /// Otherwise, None is returned.
     fn propagate(&mut self) -> Option<Conflict> {
         loop {
-            if self.propagated >= self.prop_queue.len() { break }
+            if self.propagated >= self.prop_queue.len() {
+                // The XOR constraints are only propagated once the clauses have reached their
+                // fixpoint (the elimination is much more expensive than unit propagation).
+                match self.propagate_xors() {
+                    Ok(true)  => continue,
+                    Ok(false) => break,
+                    Err(conflict) => return conflict
+                }
+            }
 
             let nb_propagated = self.propagated;
             let literal = self.prop_queue[nb_propagated];
@@ -1438,38 +2389,200 @@ impl Solver {
         return None;
     }
 
+    /// Propagates the XOR constraints with the Gauss-Jordan elimination engine, which is first
+    /// notified of the assignments it does not know of yet. The reasons of the implied literals
+    /// are only turned into clauses when the conflict analysis needs them (see `reason_of`).
+    ///
+    /// # Return Value
+    /// Ok(true) when some literal was implied, Ok(false) when nothing was, and Err with the
+    /// conflicting clause when the XOR constraints are violated. That clause is None when the
+    /// conflict holds at the root level (the solver is then marked unsat).
+    fn propagate_xors(&mut self) -> Result<bool, Option<Conflict>> {
+        if self.gauss.is_empty() { return Ok(false); }
+
+        // (the trail holds the negation of each literal that was assigned true)
+        while self.xor_notified < self.prop_queue.len() {
+            self.gauss.assign(!self.prop_queue[self.xor_notified]);
+            self.xor_notified += 1;
+        }
+
+        let valuation = &self.valuation;
+        let implied = match self.gauss.propagate(|v| valuation[v]) {
+            Ok(Some(implied)) => implied,
+            Ok(None)          => return Ok(false),
+            Err(conflict)     => return Err(self.add_xor_clause(conflict))
+        };
+
+        let reason = if self.nb_decisions == 0 { CLAUSE_ELIDED } else { XOR_IMPLIED };
+        if self.assign(implied, Some(reason)).is_err() {
+            let explanation = self.gauss.explanation(implied.var()).to_vec();
+            return Err(self.add_xor_clause(explanation));
+        }
+        Ok(true)
+    }
+
+    /// Returns the reason of the assignment of the given variable. When the variable was implied
+    /// by the XOR constraints, the reason clause is generated (and added to the database as a
+    /// learned clause) on the fly.
+    fn reason_of(&mut self, v: Variable) -> Option<Reason> {
+        match self.reason[v] {
+            Some(XOR_IMPLIED) => {
+                let explanation = self.gauss.explanation(v).to_vec();
+                let reason = self.add_xor_clause(explanation);
+                self.reason[v] = Some(reason.unwrap_or(CLAUSE_ELIDED));
+                self.reason[v]
+            },
+            reason => reason
+        }
+    }
+
+    /// Adds a clause derived from the XOR constraints (a reason or a conflict, whose literals are
+    /// all false but maybe the first one) to the database. The clause is flagged as an xor reason:
+    /// it may be forgotten like a learned clause but is not counted among them.
+    ///
+    /// # Return Value
+    /// The id of the clause, or None when the clause comprises less than two literals. This only
+    /// happens at the root level, hence the solver is marked unsat when the clause is falsified.
+    fn add_xor_clause(&mut self, mut clause: Vec<Literal>) -> Option<ClauseId> {
+        if clause.len() < 2 {
+            if clause.iter().all(|l| self.is_false(*l)) {
+                self.is_unsat = true;
+            }
+            return None;
+        }
+
+        // the second literal gets watched: it must be the one having the highest level
+        let start = if self.is_false(clause[0]) { 0 } else { 1 };
+        for i in start..2 {
+            let mut highest = i;
+            for j in i+1..clause.len() {
+                if self.level[clause[j].var()] > self.level[clause[highest].var()] {
+                    highest = j;
+                }
+            }
+            clause.swap(i, highest);
+        }
+
+        let lbd    = self.lbd_of(&clause);
+        let c_id   = self.clauses.alloc(&Clause::new(clause, true), true);
+        self.nb_xor_reasons += 1;
+        self.clauses.set_xor_reason(c_id, true);
+        self.clauses.set_lbd(c_id, lbd);
+        // (it is built anew whenever needed: it must not be kept forever, whatever its lbd)
+        self.clauses.set_tier(c_id, Tier::Local);
+        self.watch_clause(c_id);
+        Some(c_id)
+    }
+
     /// Notifies all the watchers of `lit` that `lit` has been falsified.
 	/// This method optionally returns a conflicting clause if one is found.
     fn propagate_literal(&mut self, lit: Literal) -> Option<Conflict> {
-        // we loop backwards to avoid messing up with the items that are appended to the list while
-        // iterating over it. Logically, the two sets should be separated (but merged after the fn).
-        // This iterating scheme achieves that goal.
-        for i in (0..self.watchers[lit].len()).rev() {
-            let watcher = self.watchers[lit][i];
-            self.watchers[lit].swap_remove(i);
-
-            let new_literal_found = self.find_new_literal(watcher, lit);
+        // Binary clauses go first: the watcher alone tells everything there is to know about the
+        // clause (its blocker is the other literal), the clause memory is only accessed to
+        // maintain invariant B when the clause implies something.
+        for i in 0..self.binaries[lit].len() {
+            let watcher = self.binaries[lit][i];
+            let other   = watcher.blocker;
+
+            if self.is_true(other) {
+                continue;
+            }
+
+            let c_id = watcher.clause;
+            if self.is_undef(other) && self.clauses[c_id][0] != other {
+                self.clauses[c_id].swap(0, 1);
+            }
+
+            if self.assign(other, Some(c_id)).is_err() {
+                return Some(c_id);
+            }
+        }
+
+        // The watch list is compacted in place: `i` is the position of the next watcher to visit
+        // and `j` is the position where the next watcher that keeps watching `lit` is written.
+        // This preserves the relative order of the watchers and spares all the useless writes.
+        // The list is detached from `self.watchers` while it is being compacted. This is safe
+        // because a clause never starts watching `lit` while `lit` is being propagated.
+        let mut watchers = mem::take(&mut self.watchers[lit]);
+        let mut conflict = None;
+
+        let len   = watchers.len();
+        let mut i = 0;
+        let mut j = 0;
+        while i < len {
+            let watcher = watchers[i];
+            i += 1;
+
+            // When the blocker is true, the clause is already satisfied. There is nothing to do
+            // and we don't even need to look at the clause.
+            if self.is_true(watcher.blocker) {
+                watchers[j] = watcher;
+                j += 1;
+                continue;
+            }
+
+            let c_id = watcher.clause;
+            let new_literal_found = self.find_new_literal(c_id, lit);
+            // Whatever happens, the other watched literal is a good blocker for the clause
+            let blocker = self.clauses[c_id][0];
             match new_literal_found {
-                Ok(l) => {
+                Ok(l) if l != lit => {
                     // l was found, its ok. We only need to start watching it
-                    self.watchers[l].push(watcher);
+                    self.watchers[l].push(Watcher::new(c_id, blocker));
+                },
+                Ok(_) => {
+                    // The clause is satisfied by the other watched literal: keep watching `lit`
+                    watchers[j] = Watcher::new(c_id, blocker);
+                    j += 1;
                 },
                 Err(l) => {
-                    // No result could be found, so we need to keep watching `lit`
-                    self.watchers[lit].push(watcher);
+                    // No result could be found, so we need to keep watching `lit`. Unless the trail
+                    // is not ordered by level (chronological backtracking): then the clause must
+                    // watch its falsified literal having the highest level.
+                    let kept = if self.chrono_backtrack { self.highest_level_watch(c_id) } else { lit };
+                    if kept == lit {
+                        watchers[j] = Watcher::new(c_id, blocker);
+                        j += 1;
+                    } else {
+                        self.watchers[kept].push(Watcher::new(c_id, blocker));
+                    }
                     // In the meantime we also need to assign `l`, otherwise the whole
-                    // clause is going to be unsat
-                    match self.assign(l, Some(watcher)) {
-                        // Assignment went on well, we're done
-                        Ok(()) => {},
-                        // Conflict detected, return it !
-                        Err(()) => return Some(watcher)
+                    // clause is going to be unsat. If that fails, we've got a conflict and we stop
+                    // right away (the watchers which have not been visited are kept untouched).
+                    if self.assign(l, Some(c_id)).is_err() {
+                        conflict = Some(c_id);
+                        break;
                     }
                 }
             }
         }
 
-        return None;
+        // Shift the watchers which have not been visited (because of a conflict)
+        while i < len {
+            watchers[j] = watchers[i];
+            i += 1;
+            j += 1;
+        }
+        watchers.truncate(j);
+        self.watchers[lit] = watchers;
+
+        conflict
+    }
+
+    /// Moves the falsified literal having the highest decision level to the second watched position
+    /// of the given clause (which is assumed to be unit or conflicting) and returns it.
+    fn highest_level_watch(&mut self, c_id: ClauseId) -> Literal {
+        let level = &self.level;
+        let clause = &mut self.clauses[c_id];
+
+        let mut highest = 1;
+        for i in 2..clause.len() {
+            if level[clause[i].var()] > level[clause[highest].var()] {
+                highest = i;
+            }
+        }
+        clause.swap(1, highest);
+        clause[1]
     }
 
     // -------------------------------------------------------------------------------------------//
@@ -1486,8 +2599,8 @@ impl Solver {
     /// This method find and remove all clauses that have the forced literal lit, excpect clause c_id
     fn remove_clause_with_lit(&mut self, lit: Literal, c_id: usize){
         let mut clauses_to_remove = vec![];
-        for j in (0..self.clauses.len()).rev(){
-            let clause = &*(self.clauses[j]);
+        for j in self.clauses.ids(){
+            let clause = &self.clauses[j];
             if j != c_id && clause.contains(&lit){
                 clauses_to_remove.push(j);
                 self.removed += 1;
@@ -1503,10 +2616,9 @@ impl Solver {
         assert_ne!(id1,id2);
         let c1 = &self.clauses[id1];
         let c2 = &self.clauses[id2];
-        let len1 = c1.len();
         if c1.len() > c2.len() {return false; }
-        for i in 0..len1 {
-            if !c2.contains(&c1[i]){
+        for l in c1.iter() {
+            if !c2.contains(l){
                 return false
             }
         }
@@ -1516,8 +2628,8 @@ impl Solver {
     /// Backward subsumption on all the learned clauses
     pub fn backward_subsumption(&mut self){
         let mut clauses_to_remove = vec![];
-        for id in (0..self.clauses.len()).rev() {
-            for id2 in (self.clauses.len()-self.nb_learned..self.clauses.len()).rev(){
+        for id in self.clauses.ids() {
+            for id2 in self.clauses.ids().filter(|c| self.clauses.is_learned(*c)) {
                 if id != id2{
                     let clause = &(self.clauses[id]);
                     let clause2 = &(self.clauses[id2]);
@@ -1527,15 +2639,14 @@ impl Solver {
                 }
             }
         }
-        clauses_to_remove.sort();
-        for id in (0..clauses_to_remove.len()).rev(){
-            self.remove_clause(clauses_to_remove[id]);
+        for id in clauses_to_remove {
+            self.remove_clause(id);
         }
     }
 
     /// Forward subsumption with one clause index
     fn forward_subsumption_clause(&mut self, clause_id: ClauseId) -> bool{
-        for id2 in (0..self.clauses.len()).rev(){
+        for id2 in self.clauses.ids(){
             let clause2 = &(self.clauses[id2]);
             let clause = &(self.clauses[clause_id]);
 
@@ -1549,9 +2660,13 @@ impl Solver {
 
     /// Forward subsumption on all the learned clauses
     pub fn forward_subsumption(&mut self){
-        for id in (self.clauses.len()-self.nb_learned..self.clauses.len()).rev() {
+        let learned: Vec<ClauseId> = self.clauses.ids()
+            .filter(|c| self.clauses.is_learned(*c))
+            .collect();
+        for id in learned {
             let mut del = false;
-            for id2 in (0..self.clauses.len()).rev(){
+            let ids: Vec<ClauseId> = self.clauses.ids().collect();
+            for id2 in ids {
                 if id != id2{
                     let clause = &(self.clauses[id]);
                     let clause2 = &(self.clauses[id2]);
@@ -1566,6 +2681,93 @@ impl Solver {
     }
 
 
+    // -------------------------------------------------------------------------------------------//
+    // ---------------------------- LOOKAHEAD ----------------------------------------------------//
+    // -------------------------------------------------------------------------------------------//
+
+    /// Propagates all the literals that have been assigned at the root level but not propagated
+    /// yet. This should be done before probing the consequences of some literal.
+    ///
+    /// # Return Value
+    /// false iff the problem is proven unsatisfiable by propagation alone.
+    pub fn propagate_at_root(&mut self) -> bool {
+        debug_assert_eq!(self.nb_decisions, 0);
+        if !self.is_unsat && self.propagate().is_some() {
+            self.is_unsat = true;
+        }
+        !self.is_unsat
+    }
+
+    /// Opens a new decision level on which the (unassigned) literal `lit` is decided, and
+    /// propagates it. Unlike what happens during the search, a conflict is not analyzed: the
+    /// solver simply remains at the new level until `backtrack_to` is called.
+    ///
+    /// # Return Value
+    /// false iff propagating `lit` yields a conflict.
+    pub fn probe(&mut self, lit: Literal) -> bool {
+        debug_assert!(self.is_undef(lit));
+        self.assign(lit, None).is_ok() && self.propagate().is_none() && !self.is_unsat
+    }
+
+    /// Undoes all the assignments made at some level strictly greater than `level`.
+    #[inline]
+    pub fn backtrack_to(&mut self, level: u32) { self.backtrack(level) }
+
+    // -------------------------------------------------------------------------------------------//
+    // ---------------------------- MODEL COUNTING -----------------------------------------------//
+    // -------------------------------------------------------------------------------------------//
+
+    /// Opens a new decision level on which the (unassigned) literal `lit` is decided, and
+    /// propagates it. This is the same as `probe`, except that the conflicting clause (if any)
+    /// is returned so that it can be analyzed with `learn`.
+    pub fn branch(&mut self, lit: Literal) -> Option<Conflict> {
+        debug_assert!(self.is_undef(lit));
+        let _ = self.assign(lit, None);
+        self.propagate()
+    }
+
+    /// Analyzes the `conflict` met at the current decision level and learns the resulting clause.
+    /// Unlike what happens during the search, the solver does not backjump: it only backtracks
+    /// to the previous decision level (the decisions taken before the conflicting one must be
+    /// kept, ie: because a model counter has not explored both of their branches yet). There,
+    /// the learned clause asserts its first literal which is then propagated.
+    ///
+    /// # Return Value
+    /// The conflict met while propagating the asserted literal, if any. In that case, the
+    /// previous decision level is conflicting as well and the conflict is to be learned in turn.
+    /// When the conflict is met at the root level, the problem is proven unsat (and None is
+    /// returned).
+    pub fn learn(&mut self, conflict: Conflict) -> Option<Conflict> {
+        self.nb_conflicts += 1;
+        if self.nb_decisions == 0 {
+            self.is_unsat = true;
+            return None;
+        }
+
+        let uip = self.find_first_uip(conflict);
+        let mut learned = self.build_conflict_clause(uip);
+        if self.shrink {
+            self.shrink_clause(&mut learned);
+        }
+        if self.binary_minimization {
+            self.minimize_with_binaries(&mut learned);
+        }
+        self.move_highest_level_second(&mut learned);
+
+        let previous = self.nb_decisions - 1;
+        self.backtrack(previous);
+
+        match self.add_learned_clause(learned) {
+            Err(()) => return None,
+            Ok(c) if c == CLAUSE_ELIDED => {},
+            Ok(c_id) => {
+                let asserting_lit = self.clauses[c_id][0];
+                let _ = self.assign(asserting_lit, Some(c_id));
+            }
+        }
+        self.propagate()
+    }
+
     // -------------------------------------------------------------------------------------------//
     // ---------------------------- MISC ---------------------------------------------------------//
     // -------------------------------------------------------------------------------------------//
@@ -1576,6 +2778,38 @@ impl Solver {
     #[inline]
     pub fn root(&self) -> usize { self.forced }
 
+    /// Tells the current decision level. That is to say, the number of decisions on the trail.
+    #[inline]
+    pub fn decision_level(&self) -> u32 { self.nb_decisions }
+
+    /// Returns the number of literals currently assigned (the length of the trail)
+    #[inline]
+    pub fn trail_len(&self) -> usize { self.prop_queue.len() }
+
+    /// Returns the literal which has been assigned at the given `index` of the trail
+    #[inline]
+    pub fn trail_literal(&self, index: usize) -> Literal { !self.prop_queue[index] }
+
+    /// Returns the clauses of a problem which is equivalent to the one that was loaded in the
+    /// solver: the literals forced at the root level (as unit clauses), the problem clauses and
+    /// the clausal encoding of the XOR constraints. The learned clauses are left out.
+    pub fn problem_clauses(&self) -> Vec<Vec<Literal>> {
+        let units = (0..self.prop_queue.len())
+            .map(|i| !self.prop_queue[i])
+            .filter(|l| self.level[l.var()] == 0)
+            .map(|l| vec![l]);
+
+        let clauses = self.clauses.ids()
+            .filter(|&c| !self.clauses.is_learned(c))
+            .map(|c| self.clauses[c].to_vec());
+
+        let xors = self.gauss.xors().into_iter()
+            .flat_map(|xor| xor.clauses());
+
+        units.chain(clauses).chain(xors).collect()
+    }
+
+
     #[inline]
     fn is_decision(&self, lit : Literal) -> bool {
         self.reason[lit.var()].is_none()
@@ -1850,13 +3084,13 @@ mod test_watched_literals {
         tested.set_value(lit(-1), Bool::False);
         tested.set_value(lit(-2), Bool::False);
 
-        assert_eq!(tested.watchers[lit(-1)], &[ ]);
-        assert_eq!(tested.watchers[lit(-2)], &[ ]);
+        assert_eq!(watched_by(&tested, lit(-1)), vec![ ]);
+        assert_eq!(watched_by(&tested, lit(-2)), vec![ ]);
 
         tested.activate_clause(0);
 
-        assert_eq!(tested.watchers[lit(-1)], &[ ]);
-        assert_eq!(tested.watchers[lit(-2)], &[ ]);
+        assert_eq!(watched_by(&tested, lit(-1)), vec![ ]);
+        assert_eq!(watched_by(&tested, lit(-2)), vec![ ]);
     }
 
     #[test]
@@ -1901,13 +3135,13 @@ mod test_watched_literals {
         tested.add_clause(clause,false);
         tested.deactivate_clause(0);
 
-        assert_eq!(tested.watchers[lit(-1)], &[ ]);
-        assert_eq!(tested.watchers[lit(-2)], &[ ]);
+        assert_eq!(watched_by(&tested, lit(-1)), vec![ ]);
+        assert_eq!(watched_by(&tested, lit(-2)), vec![ ]);
 
         tested.activate_clause(0);
 
-        assert_eq!(tested.watchers[lit(-1)], &[0]);
-        assert_eq!(tested.watchers[lit(-2)], &[0]);
+        assert_eq!(watched_by(&tested, lit(-1)), vec![0]);
+        assert_eq!(watched_by(&tested, lit(-2)), vec![0]);
     }
 
     #[test]
@@ -1920,13 +3154,13 @@ mod test_watched_literals {
         tested.add_clause(clause,false);
         tested.deactivate_clause(0);
 
-        assert_eq!(tested.watchers[lit(-1)], &[ ]);
-        assert_eq!(tested.watchers[lit(-2)], &[ ]);
+        assert_eq!(watched_by(&tested, lit(-1)), vec![ ]);
+        assert_eq!(watched_by(&tested, lit(-2)), vec![ ]);
 
         tested.activate_clause(0);
 
-        assert_eq!(tested.watchers[lit(-1)], &[0]);
-        assert_eq!(tested.watchers[lit(-2)], &[0]);
+        assert_eq!(watched_by(&tested, lit(-1)), vec![0]);
+        assert_eq!(watched_by(&tested, lit(-2)), vec![0]);
     }
 
     #[test]
@@ -1940,7 +3174,7 @@ mod test_watched_literals {
         let clause = Clause::new(vec![lit(-1), lit(-2)], false);
         tested.add_clause(clause,false);
 
-        tested.clauses[0].clear();
+        tested.clauses.truncate(0, 0);
         tested.deactivate_clause(0);
     }
     #[test]
@@ -1954,8 +3188,7 @@ mod test_watched_literals {
         let clause = Clause::new(vec![lit(-1), lit(-2)], false);
         tested.add_clause(clause,false);
 
-        tested.clauses[0].clear();
-        tested.clauses[0].push(lit(-1));
+        tested.clauses.truncate(0, 1);
         tested.deactivate_clause(0);
     }
 
@@ -1968,13 +3201,17 @@ mod test_watched_literals {
         let clause = Clause::new(vec![lit(-1), lit(-2)], false);
         tested.add_clause(clause,false);
 
-        assert_eq!(tested.watchers[lit(-1)], &[0]);
-        assert_eq!(tested.watchers[lit(-2)], &[0]);
+        assert_eq!(watched_by(&tested, lit(-1)), vec![0]);
+        assert_eq!(watched_by(&tested, lit(-2)), vec![0]);
 
         tested.deactivate_clause(0);
 
-        assert_eq!(tested.watchers[lit(-1)], &[ ]);
-        assert_eq!(tested.watchers[lit(-2)], &[ ]);
+        assert_eq!(watched_by(&tested, lit(-1)), vec![ ]);
+        assert_eq!(watched_by(&tested, lit(-2)), vec![ ]);
+    }
+
+    fn watched_by(solver : &Solver, l: Literal) -> Vec<ClauseId> {
+        solver.watchers[l].iter().chain(solver.binaries[l].iter()).map(|w| w.clause).collect()
     }
 }
 
@@ -2238,6 +3475,125 @@ mod tests {
         assert_eq!(solver.propagated, 3);
         assert_eq!(solver.prop_queue, vec![lit(-3), lit(-2), lit(-1)]);
     }
+
+    #[test]
+    fn propagate_must_not_touch_the_clause_when_its_blocker_is_true(){
+        let mut solver = SOLVER::new(4);
+        let c0 = solver.add_problem_clause(&mut vec![1, 2, 3, 4]).unwrap();
+
+        assert_eq!(solver.watchers[lit(2)], vec![Watcher::new(c0, lit(1))]);
+
+        solver.assign(lit( 1), None).expect("1 should be assignable");
+        solver.assign(lit(-2), None).expect("-2 should be assignable");
+        assert!(solver.propagate().is_none());
+
+        // the watched literals were not even swapped
+        assert_eq!("[Clause([Literal(1), Literal(2), Literal(3), Literal(4)])]",
+                   format!("{:?}", solver.clauses));
+        assert_eq!(solver.watchers[lit(2)], vec![Watcher::new(c0, lit(1))]);
+        assert_eq!(solver.watchers[lit(3)], vec![]);
+    }
+
+    #[test]
+    fn propagate_must_use_the_other_watched_literal_as_blocker_of_a_new_watch(){
+        let mut solver = SOLVER::new(4);
+        let c0 = solver.add_problem_clause(&mut vec![1, 2, 3, 4]).unwrap();
+
+        solver.assign(lit(-1), None).expect("-1 should be assignable");
+        assert!(solver.propagate().is_none());
+
+        assert_eq!(solver.watchers[lit(1)], vec![]);
+        assert_eq!(solver.watchers[lit(3)], vec![Watcher::new(c0, lit(2))]);
+    }
+
+    #[test]
+    fn propagate_must_preserve_the_order_of_the_watchers(){
+        let mut solver = SOLVER::new(6);
+        let c0 = solver.add_problem_clause(&mut vec![1, 2, 3]).unwrap();
+        let c1 = solver.add_problem_clause(&mut vec![1, 4, 5]).unwrap();
+        let c2 = solver.add_problem_clause(&mut vec![1, 4, 6]).unwrap();
+        let c3 = solver.add_problem_clause(&mut vec![1, 4, 5, 6]).unwrap();
+
+        solver.assign(lit( 4), None).expect("4 should be assignable");
+        solver.assign(lit(-1), None).expect("-1 should be assignable");
+        assert!(solver.propagate().is_none());
+
+        assert_eq!(watched_by(&solver, lit(1)), vec![c1, c2, c3]);
+        assert_eq!(watched_by(&solver, lit(3)), vec![c0]);
+    }
+
+    #[test]
+    fn propagate_must_keep_the_unvisited_watchers_upon_conflict(){
+        let mut solver = SOLVER::new(6);
+        let c0 = solver.add_problem_clause(&mut vec![1, 2, 3]).unwrap();
+        let c1 = solver.add_problem_clause(&mut vec![1, 4, 5]).unwrap();
+        let c2 = solver.add_problem_clause(&mut vec![1, 4, 6]).unwrap();
+
+        solver.assign(lit(-1), None).expect("-1 should be assignable");
+        solver.assign(lit(-2), None).expect("-2 should be assignable");
+        solver.assign(lit(-3), None).expect("-3 should be assignable");
+
+        assert_eq!(Some(c0), solver.propagate());
+        assert_eq!(watched_by(&solver, lit(1)), vec![c0, c1, c2]);
+        assert_eq!(watched_by(&solver, lit(4)), vec![c1, c2]);
+    }
+
+    #[test]
+    fn binary_clauses_must_be_watched_in_the_binary_watch_lists(){
+        let mut solver = SOLVER::new(3);
+        let c0 = solver.add_problem_clause(&mut vec![1, 2]).unwrap();
+        let c1 = solver.add_problem_clause(&mut vec![1, 2, 3]).unwrap();
+
+        assert_eq!(solver.binaries[lit(1)], vec![Watcher::new(c0, lit(2))]);
+        assert_eq!(solver.binaries[lit(2)], vec![Watcher::new(c0, lit(1))]);
+        assert_eq!(solver.watchers[lit(1)], vec![Watcher::new(c1, lit(2))]);
+        assert_eq!(solver.watchers[lit(2)], vec![Watcher::new(c1, lit(1))]);
+
+        solver.deactivate_clause(c0);
+        assert_eq!(solver.binaries[lit(1)], vec![]);
+        assert_eq!(solver.binaries[lit(2)], vec![]);
+        assert_eq!(solver.watchers[lit(1)], vec![Watcher::new(c1, lit(2))]);
+    }
+
+    #[test]
+    fn propagate_must_put_the_literal_implied_by_a_binary_clause_first(){
+        let mut solver = SOLVER::new(2);
+        let c0 = solver.add_problem_clause(&mut vec![1, 2]).unwrap();
+
+        solver.assign(lit(-1), None).expect("-1 should be assignable");
+        assert!(solver.propagate().is_none());
+
+        assert!(solver.is_true(lit(2)));
+        assert_eq!(Some(c0), solver.reason[var(2)]);
+        assert_eq!(&[lit(2), lit(1)], &solver.clauses[c0]);
+    }
+
+    #[test]
+    fn propagate_must_detect_the_conflicts_on_binary_clauses(){
+        let mut solver = SOLVER::new(2);
+        let c0 = solver.add_problem_clause(&mut vec![1, 2]).unwrap();
+
+        solver.assign(lit(-1), None).expect("-1 should be assignable");
+        solver.assign(lit(-2), None).expect("-2 should be assignable");
+
+        assert_eq!(Some(c0), solver.propagate());
+    }
+
+    #[test]
+    fn binary_implications_must_list_the_literals_implied_through_binary_clauses(){
+        let mut solver = SOLVER::new(4);
+        solver.add_problem_clause(&mut vec![1, 2]);
+        solver.add_problem_clause(&mut vec![1, -3]);
+        solver.add_problem_clause(&mut vec![1, 2, 4]);
+
+        let implied : Vec<Literal> = solver.binary_implications(lit(-1)).collect();
+        assert_eq!(vec![lit(2), lit(-3)], implied);
+
+        let implied : Vec<Literal> = solver.binary_implications(lit(3)).collect();
+        assert_eq!(vec![lit(1)], implied);
+
+        assert_eq!(0, solver.binary_implications(lit(1)).count());
+    }
 /*
     #[test]
     fn propagate_stops_when_a_conflict_is_detected() {
@@ -2271,21 +3627,21 @@ mod tests {
          *
          */
         let mut solver = SOLVER::new(8);
-        solver.add_problem_clause(&mut vec![ 1,-8, 3]); // c0
+        let c0 = solver.add_problem_clause(&mut vec![ 1,-8, 3]).unwrap(); // c0
         solver.add_problem_clause(&mut vec![ 1, 4,-5]); // c1
         solver.add_problem_clause(&mut vec![ 5,-6, 7]); // c2
         solver.add_problem_clause(&mut vec![ 6, 2, 7]); // c3
         solver.add_problem_clause(&mut vec![ 4,-7]);    // c4
         solver.add_problem_clause(&mut vec![-2, 8]);    // c5
-        solver.add_problem_clause(&mut vec![-8,-3]);    // c6
+        solver.add_problem_clause(&mut vec![-8,-3]); // c6
 
         assert_eq!(Ok(()), solver.assign(lit(-1), None));
         assert_eq!(Ok(()), solver.assign(lit(-4), None));
 
+        // binary clauses are propagated first: c6 forces -c before c0 gets a chance to force c
         let conflict = solver.propagate();
         assert!(conflict.is_some());
-        assert_eq!(format!("{:?}", 6),
-                   format!("{:?}", conflict.unwrap()));
+        assert_eq!(c0, conflict.unwrap());
     }
 
     // isUIP must be true when the literal is a decision
@@ -2314,21 +3670,22 @@ mod tests {
          *
          */
         let mut solver = SOLVER::new(8);
-        solver.add_problem_clause(&mut vec![ 1,-8, 3]); // c0
+        let c0 = solver.add_problem_clause(&mut vec![ 1,-8, 3]).unwrap(); // c0
         solver.add_problem_clause(&mut vec![ 1, 4,-5]); // c1
         solver.add_problem_clause(&mut vec![ 5,-6, 7]); // c2
         solver.add_problem_clause(&mut vec![ 6, 2, 7]); // c3
         solver.add_problem_clause(&mut vec![ 4,-7]);    // c4
         solver.add_problem_clause(&mut vec![-2, 8]);    // c5
-        solver.add_problem_clause(&mut vec![-8,-3]);    // c6
+        solver.add_problem_clause(&mut vec![-8,-3]); // c6
 
         assert_eq!(Ok(()), solver.assign(lit(-1), None));
         assert_eq!(Ok(()), solver.assign(lit(-4), None));
 
+        // binary clauses are propagated first: c6 forces -c before c0 gets a chance to force c
         let conflict = solver.propagate();
 
         assert!(conflict.is_some());
-        assert_eq!(Some(6), conflict);
+        assert_eq!(Some(c0), conflict);
         assert_eq!(6, solver.find_first_uip(conflict.unwrap()));
         // note: is_uip() *must* be tested *after* find_first_uip() because the former method
         //       is the one setting the IsMarked flag
@@ -2364,20 +3721,21 @@ mod tests {
          *
          */
         let mut solver = SOLVER::new(8);
-        solver.add_problem_clause(&mut vec![ 1,-8, 3]); // c0
+        let c0 = solver.add_problem_clause(&mut vec![ 1,-8, 3]).unwrap(); // c0
         solver.add_problem_clause(&mut vec![ 1, 4,-5]); // c1
         solver.add_problem_clause(&mut vec![ 5,-6, 7]); // c2
         solver.add_problem_clause(&mut vec![ 6, 2, 7]); // c3
         solver.add_problem_clause(&mut vec![ 4,-7]);    // c4
         solver.add_problem_clause(&mut vec![-2, 8]);    // c5
-        solver.add_problem_clause(&mut vec![-8,-3]);    // c6
+        solver.add_problem_clause(&mut vec![-8,-3]); // c6
 
         assert_eq!(Ok(()), solver.assign(lit(-1), None));
         assert_eq!(Ok(()), solver.assign(lit(-4), None));
 
+        // binary clauses are propagated first: c6 forces -c before c0 gets a chance to force c
         let conflict = solver.propagate();
         assert!(conflict.is_some());
-        assert_eq!(Some(6), conflict);
+        assert_eq!(Some(c0), conflict);
 
         assert_eq!(6, solver.find_first_uip(conflict.unwrap()));
         assert!(!solver.is_uip(7)); // just check that no other than the found uip is an uip
@@ -2398,21 +3756,22 @@ mod tests {
          *
          */
         let mut solver = SOLVER::new(8);
-        solver.add_problem_clause(&mut vec![ 1,-8, 3]); // c0
+        let c0 = solver.add_problem_clause(&mut vec![ 1,-8, 3]).unwrap(); // c0
         solver.add_problem_clause(&mut vec![ 1, 4,-5]); // c1
         solver.add_problem_clause(&mut vec![ 5,-6, 7]); // c2
         solver.add_problem_clause(&mut vec![ 6, 2, 7]); // c3
         solver.add_problem_clause(&mut vec![ 4,-7]);    // c4
         solver.add_problem_clause(&mut vec![-2, 8]);    // c5
-        solver.add_problem_clause(&mut vec![-8,-3]);    // c6
+        solver.add_problem_clause(&mut vec![-8,-3]); // c6
 
         assert_eq!(Ok(()), solver.assign(lit(-1), None));
         assert_eq!(Ok(()), solver.assign(lit(-4), None));
 
+        // binary clauses are propagated first: c6 forces -c before c0 gets a chance to force c
         let conflict = solver.propagate();
 
         assert!(conflict.is_some());
-        assert_eq!(Some(6), conflict);
+        assert_eq!(Some(c0), conflict);
         assert_eq!(6, solver.find_first_uip(conflict.unwrap()));
         assert!(solver.is_uip(6));
     }
@@ -2433,14 +3792,15 @@ mod tests {
         solver.add_problem_clause(&mut vec![ 1, 2,-3]);
         solver.add_problem_clause(&mut vec![ 1, 2,-4]);
         solver.add_problem_clause(&mut vec![ 3, 4,-5]);
-        solver.add_problem_clause(&mut vec![ 3, 4, 5]);
+        let c3 = solver.add_problem_clause(&mut vec![ 3, 4, 5]).unwrap();
 
         assert!(solver.assign(lit(-1), None).is_ok());
         assert!(solver.assign(lit(-2), None).is_ok());
 
         let conflict = solver.propagate();
         assert!(conflict.is_some());
-        assert_eq!(Some(2), conflict); // [3, 4, -5]
+        // watchers are visited in order: c2 forces -5, then c3 conflicts
+        assert_eq!(Some(c3), conflict); // [3, 4, 5]
         assert_eq!(1, solver.find_first_uip(conflict.unwrap()));
     }
 
@@ -2463,14 +3823,15 @@ mod tests {
         solver.add_problem_clause(&mut vec![ 3,-4]);
         solver.add_problem_clause(&mut vec![ 3,-5]);
         solver.add_problem_clause(&mut vec![ 4, 5, 6]);
-        solver.add_problem_clause(&mut vec![ 4, 5,-6]);
+        let c4 = solver.add_problem_clause(&mut vec![ 4, 5,-6]).unwrap();
 
         assert!(solver.assign(lit(-1), None).is_ok());
         assert!(solver.assign(lit(-2), None).is_ok());
 
         let conflict = solver.propagate();
         assert!(conflict.is_some());
-        assert_eq!(Some(3), conflict); // [4, 5, 6]
+        // watchers are visited in order: c3 forces 6, then c4 conflicts
+        assert_eq!(Some(c4), conflict); // [4, 5, -6]
         assert_eq!(2, solver.find_first_uip(conflict.unwrap()));
     }
 
@@ -2492,7 +3853,7 @@ mod tests {
         solver.add_problem_clause(&mut vec![ 6, 2, 7]); // c3
         solver.add_problem_clause(&mut vec![ 4,-7]);    // c4
         solver.add_problem_clause(&mut vec![-2, 8]);    // c5
-        solver.add_problem_clause(&mut vec![-8,-3]);    // c6
+        solver.add_problem_clause(&mut vec![-8,-3]); // c6
 
         assert_eq!(Ok(()), solver.assign(lit(-1), None));
         assert_eq!(Ok(()), solver.assign(lit(-4), None));
@@ -2627,7 +3988,83 @@ mod tests {
     }
 
     #[test]
-    fn learned_clause_should_be_unit(){
+    fn shrink_clause_replaces_a_block_with_its_uip(){
+        let mut solver = SOLVER::new(5);
+        solver.add_problem_clause(&mut vec![-1, 2]);
+        solver.add_problem_clause(&mut vec![-1, 3]);
+        solver.add_problem_clause(&mut vec![-4,-2,-3, 5]);
+
+        assert!(solver.assign(lit(1), None).is_ok());
+        assert!(solver.propagate().is_none());
+        assert!(solver.assign(lit(4), None).is_ok());
+        assert!(solver.propagate().is_none());
+
+        let mut learned = vec![lit(-5), lit(-2), lit(-3)];
+        for l in learned.iter() { solver.flags[*l].set(Flag::IsInConflictClause); }
+
+        solver.shrink_clause(&mut learned);
+
+        assert_eq!(vec![lit(-5), lit(-1)], learned);
+        assert!( solver.flags[lit(-1)].is_set(Flag::IsInConflictClause));
+        assert!(!solver.flags[lit(-2)].is_set(Flag::IsInConflictClause));
+        assert!(!solver.flags[lit(-3)].is_set(Flag::IsInConflictClause));
+        assert!(!solver.flags[lit(-1)].is_set(Flag::IsInBlock));
+    }
+
+    #[test]
+    fn shrink_clause_keeps_a_block_depending_on_lower_literals_out_of_the_clause(){
+        let mut solver = SOLVER::new(6);
+        solver.add_problem_clause(&mut vec![-6, 2]);
+        solver.add_problem_clause(&mut vec![-6,-1, 3]);
+        solver.add_problem_clause(&mut vec![-4,-2,-3, 5]);
+
+        assert!(solver.assign(lit(1), None).is_ok());
+        assert!(solver.propagate().is_none());
+        assert!(solver.assign(lit(6), None).is_ok());
+        assert!(solver.propagate().is_none());
+        assert!(solver.assign(lit(4), None).is_ok());
+        assert!(solver.propagate().is_none());
+
+        let mut learned = vec![lit(-5), lit(-2), lit(-3)];
+        for l in learned.iter() { solver.flags[*l].set(Flag::IsInConflictClause); }
+
+        solver.shrink_clause(&mut learned);
+
+        assert_eq!(vec![lit(-5), lit(-2), lit(-3)], learned);
+        assert!(solver.flags[lit(-2)].is_set(Flag::IsInConflictClause));
+        assert!(solver.flags[lit(-3)].is_set(Flag::IsInConflictClause));
+        assert!(!solver.flags[lit(-6)].is_set(Flag::IsInBlock));
+    }
+
+    #[test]
+    fn minimize_with_binaries_removes_the_literals_resolved_by_a_binary_clause(){
+        let mut solver = SOLVER::new(4);
+        solver.add_problem_clause(&mut vec![1, -2]);
+
+        let mut learned = vec![lit(1), lit(2), lit(3)];
+        for l in learned.iter() { solver.flags[*l].set(Flag::IsInConflictClause); }
+
+        solver.minimize_with_binaries(&mut learned);
+
+        assert_eq!(vec![lit(1), lit(3)], learned);
+        assert!(!solver.flags[lit(2)].is_set(Flag::IsInConflictClause));
+    }
+
+    #[test]
+    fn minimize_with_binaries_only_uses_clauses_containing_the_asserting_literal(){
+        let mut solver = SOLVER::new(4);
+        solver.add_problem_clause(&mut vec![3, -2]);
+
+        let mut learned = vec![lit(1), lit(2), lit(3)];
+        for l in learned.iter() { solver.flags[*l].set(Flag::IsInConflictClause); }
+
+        solver.minimize_with_binaries(&mut learned);
+
+        assert_eq!(vec![lit(1), lit(2), lit(3)], learned);
+    }
+
+    #[test]
+    fn learned_clause_should_be_unit(){
         let mut solver = SOLVER::new(5);
         solver.add_problem_clause(&mut vec![5,2]);
         solver.add_learned_clause(vec![lit(2), lit(3), lit(4)]);
@@ -2650,13 +4087,13 @@ mod tests {
     #[test]
     fn conflict_anal_mini(){
         let mut solver = SOLVER::new(5);
-        solver.add_problem_clause(&mut vec![5,2]);
-        solver.add_learned_clause(vec![lit(2), lit(3), lit(4)]);
+        let c0 = solver.add_problem_clause(&mut vec![5,2]).unwrap();
+        let c1 = solver.add_learned_clause(vec![lit(2), lit(3), lit(4)]).unwrap();
         solver.add_learned_clause(vec![ lit(2),lit(3), lit(4), lit(1)]);
         solver.add_learned_clause(vec![ lit(2),lit(-3)]);
         solver.add_learned_clause(vec![ lit(4),lit(-1)]);
-        solver.clauses.swap_remove(0);
-        solver.clauses.remove(0);
+        solver.remove_clause(c0);
+        solver.remove_clause(c1);
 //        assert_eq!(false, true);
     }
 
@@ -2696,19 +4133,13 @@ mod tests {
 
         assert_eq!(5, solver.clauses.len());
         solver.forward_subsumption();
-        for i in 0..solver.clauses.len(){
-            println!("{}", format!("{:?}", solver.clauses[i]));
-        }
-        assert_eq!("Clause([Literal(4), Literal(5)])",
-                   &format!("{:?}", solver.clauses[0]));
-        assert_eq!("Clause([Literal(-3), Literal(1)])",
-                   &format!("{:?}", solver.clauses[1]));
-        assert_eq!("Clause([Literal(3), Literal(-5)])",
-                   &format!("{:?}", solver.clauses[2]));
+        let database = format!("[{}, {}, {}]",
+                               "Clause([Literal(-3), Literal(1)])",
+                               "Clause([Literal(3), Literal(-5)])",
+                               "Clause([Literal(4), Literal(5)])");
+        assert_eq!(database, format!("{:?}", solver.clauses));
 
         assert_eq!(3, solver.clauses.len());
-        assert_eq!("Clause([Literal(4), Literal(5)])",
-                   &format!("{:?}", solver.clauses[0]));
     }
 
     #[test]
@@ -2925,28 +4356,729 @@ mod tests {
             solver.flags[-lit].set(Flag::IsInConflictClause);
         }
 
-        assert_eq!(5, solver.nb_decisions);
-        solver.rollback(3);
-        assert_eq!(3, solver.nb_decisions);
+        assert_eq!(5, solver.nb_decisions);
+        solver.rollback(3);
+        assert_eq!(3, solver.nb_decisions);
+    }
+
+    #[test]
+    fn rollback_saves_the_old_phase() {
+        let mut solver = SOLVER::new(5);
+
+        for i in 1..6 {
+            let lit = lit(i);
+            assert!(!solver.phase_saving.contains(lit.var().into() ));
+            assert!(solver.assign(lit, None).is_ok());
+        }
+
+        solver.rollback(3);
+        for i in (4..6).rev() {
+            let l = lit(i);
+            assert!(solver.phase_saving.contains(l.var().into() ));
+        }
+    }
+
+
+    #[test]
+    fn reuse_level_keeps_the_decisions_more_active_than_the_next_decision(){
+        let mut solver = SOLVER::new(5);
+        solver.var_order.bump(var(1));
+        solver.var_order.bump(var(2));
+        solver.var_order.decay();
+        solver.var_order.bump(var(4));
+        solver.var_order.decay();
+        solver.var_order.decay();
+        solver.var_order.bump(var(3));
+
+        // decide 3, 4, 2 (4 is less active than 3 which is the next variable)
+        for v in [3, 4, 2].iter() {
+            solver.var_order.pop_top();
+            assert!(solver.assign(lit(*v), None).is_ok());
+        }
+        solver.rollback(1);
+        assert_eq!(1, solver.nb_decisions);
+
+        // 3 is still assigned, 4 is the next decision and it is more active than 3
+        assert_eq!(1, solver.reuse_level());
+
+        solver.var_order.bump(var(4));
+        assert_eq!(0, solver.reuse_level());
+    }
+
+    #[test]
+    fn reuse_level_keeps_everything_when_all_variables_are_assigned(){
+        let mut solver = SOLVER::new(2);
+        while !solver.var_order.is_empty() { solver.var_order.pop_top(); }
+        assert!(solver.assign(lit(1), None).is_ok());
+        assert!(solver.assign(lit(2), None).is_ok());
+
+        assert_eq!(2, solver.reuse_level());
+    }
+
+    #[test]
+    fn partial_restarts_keep_the_reused_decisions(){
+        let mut solver = SOLVER::new(5);
+        solver.reuse_trail = true;
+        solver.var_order.bump(var(4));
+        solver.var_order.bump(var(4));
+        solver.var_order.bump(var(4));
+        solver.var_order.bump(var(5));
+
+        let d1 = solver.decide().unwrap();
+        assert_eq!(var(4), d1.var());
+        assert!(solver.assign(d1, None).is_ok());
+        let d2 = solver.decide().unwrap();
+        assert_eq!(var(5), d2.var());
+        assert!(solver.assign(d2, None).is_ok());
+
+        // 1 becomes more active than 5 (but less than 4)
+        solver.var_order.bump(var(1));
+        solver.var_order.bump(var(1));
+
+        solver.restart();
+        assert_eq!(1, solver.nb_decisions);
+        assert!(!solver.is_undef(d1));
+        assert!( solver.is_undef(d2));
+        assert_eq!(1, solver.nb_restarts);
+    }
+
+    #[test]
+    fn solve_with_partial_restarts(){
+        let mut solver = SOLVER::new(6);
+        solver.reuse_trail = true;
+        solver.add_problem_clause(&mut vec![ 3, 1]);
+        solver.add_problem_clause(&mut vec![-1, 4]);
+        solver.add_problem_clause(&mut vec![-1,-4]);
+
+        solver.add_problem_clause(&mut vec![ 5, 2]);
+        solver.add_problem_clause(&mut vec![-2, 6]);
+        solver.add_problem_clause(&mut vec![-2,-6]);
+
+        solver.add_problem_clause(&mut vec![ 1, 2]);
+
+        assert!(!solver.solve());
+    }
+
+    #[test]
+    fn glucose_restarts_when_recent_lbds_are_worse_than_average(){
+        let mut solver = SOLVER::new(5);
+        solver.restart_strat = RestartStrategy::Glucose(Glucose::new());
+        solver.glucose_avg_global = 2.0;
+
+        for _ in 0..99 { solver.glucose_wind.push(5); }
+        assert!(!solver.should_restart());
+
+        solver.glucose_wind.push(5);
+        assert!(solver.should_restart());
+
+        solver.restart();
+        assert!(solver.glucose_wind.is_empty());
+        assert!(!solver.should_restart());
+    }
+
+    #[test]
+    fn restart_blocking_postpones_dynamic_restarts(){
+        let mut solver = SOLVER::new(5);
+        solver.restart_strat = RestartStrategy::Glucose(Glucose::new());
+        solver.glucose_avg_global = 2.0;
+        for _ in 0..100 { solver.glucose_wind.push(5); }
+
+        solver.nb_conflicts = 20_000;
+        for _ in 0..5000 { solver.trail_wind.push(2); }
+        assert!(solver.assign(lit(1), None).is_ok());
+        assert!(solver.assign(lit(2), None).is_ok());
+        assert!(solver.assign(lit(3), None).is_ok());
+
+        solver.block_restarts();
+        assert_eq!(20_100, solver.restarts_blocked_until);
+        assert!(!solver.should_restart());
+
+        solver.nb_conflicts = 20_100;
+        assert!(solver.should_restart());
+    }
+
+    #[test]
+    fn restart_blocking_requires_a_large_trail(){
+        let mut solver = SOLVER::new(5);
+        solver.restart_strat = RestartStrategy::Ema(Ema::new());
+        solver.nb_conflicts = 20_000;
+        for _ in 0..5000 { solver.trail_wind.push(2); }
+        assert!(solver.assign(lit(1), None).is_ok());
+        assert!(solver.assign(lit(2), None).is_ok());
+
+        solver.block_restarts();
+        assert_eq!(0, solver.restarts_blocked_until);
+    }
+
+    #[test]
+    fn restart_blocking_requires_enough_conflicts(){
+        let mut solver = SOLVER::new(5);
+        solver.restart_strat = RestartStrategy::Ema(Ema::new());
+        solver.nb_conflicts = 100;
+        for _ in 0..5000 { solver.trail_wind.push(1); }
+        assert!(solver.assign(lit(1), None).is_ok());
+        assert!(solver.assign(lit(2), None).is_ok());
+
+        solver.block_restarts();
+        assert_eq!(0, solver.restarts_blocked_until);
+    }
+
+    #[test]
+    fn restart_blocking_does_not_affect_static_strategies(){
+        let mut solver = SOLVER::new(5);
+        solver.nb_conflicts = 200;
+        solver.nb_conflicts_since_restart = 100;
+        solver.restarts_blocked_until = 1000;
+
+        // InOut restarts after 100 conflicts
+        assert!(solver.should_restart());
+    }
+
+    #[test]
+    fn learned_clauses_feed_the_ema_restarts(){
+        let mut solver = SOLVER::new(8);
+        solver.restart_strat = RestartStrategy::Ema(Ema::new());
+        solver.nb_conflicts_since_restart = 10;
+        assert!(!solver.should_restart());
+
+        for v in 1..8 {
+            solver.level[var(v)] = v;
+        }
+        // the lbd of the recently learned clauses is much worse than it used to be
+        for _ in 0..20 {
+            assert!(solver.add_learned_clause(vec![lit(-1), lit(-2)]).is_ok());
+        }
+        assert!(!solver.should_restart());
+        for _ in 0..20 {
+            let clause = (1..8).map(|v| lit(-v)).collect();
+            assert!(solver.add_learned_clause(clause).is_ok());
+        }
+        assert!(solver.should_restart());
+    }
+
+    #[test]
+    fn try_solve_gives_up_when_terminated(){
+        let mut solver = SOLVER::new(3);
+        solver.add_problem_clause(&mut vec![ 1, 2]);
+        solver.add_problem_clause(&mut vec![ 1,-2]);
+        solver.add_problem_clause(&mut vec![-1, 2]);
+        solver.add_problem_clause(&mut vec![-1,-2]);
+        solver.terminate = Some(Arc::new(AtomicBool::new(true)));
+
+        assert_eq!(None, solver.try_solve());
+
+        solver.terminate = Some(Arc::new(AtomicBool::new(false)));
+        assert_eq!(Some(false), solver.try_solve());
+    }
+
+    #[test]
+    fn import_clause_drops_the_literals_falsified_at_root(){
+        let mut solver = SOLVER::new(4);
+        solver.add_problem_clause(&mut vec![-1]);
+
+        solver.import_clause(3, &[lit(1), lit(2), lit(3)]);
+
+        let c = get_last_constraint(&solver);
+        assert_eq!(&solver.clauses[c], &[lit(2), lit(3)]);
+        assert!(solver.clauses.is_learned(c));
+        assert_eq!(solver.clauses.lbd(c), 2);
+        assert_eq!(solver.nb_learned, 1);
+    }
+
+    #[test]
+    fn import_clause_ignores_the_clauses_satisfied_at_root(){
+        let mut solver = SOLVER::new(4);
+        solver.add_problem_clause(&mut vec![1]);
+
+        solver.import_clause(2, &[lit(1), lit(2)]);
+
+        assert_eq!(solver.clauses.len(), 0);
+        assert_eq!(solver.nb_learned, 0);
+    }
+
+    #[test]
+    fn import_clause_asserts_unit_clauses(){
+        let mut solver = SOLVER::new(4);
+        solver.add_problem_clause(&mut vec![-1]);
+
+        solver.import_clause(2, &[lit(1), lit(2)]);
+
+        assert!(solver.is_true(lit(2)));
+        assert_eq!(solver.nb_learned, 0);
+    }
+
+    #[test]
+    fn import_clause_detects_unsat(){
+        let mut solver = SOLVER::new(4);
+        solver.add_problem_clause(&mut vec![-1]);
+
+        solver.import_clause(1, &[lit(1)]);
+
+        assert!(solver.is_unsat);
+        assert!(!solver.solve());
+    }
+
+    #[test]
+    fn restart_imports_the_shared_clauses_at_root(){
+        let mut exchanges = ClauseExchange::connect(2);
+        let mut solver = SOLVER::new(4);
+        solver.reuse_trail = true;
+        solver.exchange = exchanges.pop();
+
+        assert!(solver.assign(lit(1), None).is_ok());
+        exchanges[0].export(2, &[lit(3), lit(4)]);
+
+        solver.restart();
+        assert_eq!(0, solver.nb_decisions);
+        let c = get_last_constraint(&solver);
+        assert_eq!(&solver.clauses[c], &[lit(3), lit(4)]);
+    }
+
+    #[test]
+    fn learned_clauses_are_exported_when_worth_it(){
+        let mut exchanges = ClauseExchange::connect(2);
+        let mut solver = SOLVER::new(8);
+        solver.exchange = exchanges.pop();
+
+        for v in 1..8 {
+            solver.level[var(v)] = v;
+        }
+        assert!(solver.add_learned_clause(vec![lit(-1), lit(-2)]).is_ok());
+        let long = (1..8).map(|v| lit(-v)).collect::<Vec<Literal>>();
+        assert!(solver.add_learned_clause(long).is_ok());
+
+        assert_eq!(exchanges[0].import(), vec![(2, vec![lit(-1), lit(-2)])]);
+    }
+
+    #[test]
+    fn randomize_is_deterministic(){
+        let mut s1 = SOLVER::new(50);
+        let mut s2 = SOLVER::new(50);
+        let mut s3 = SOLVER::new(50);
+        s1.randomize(1);
+        s2.randomize(1);
+        s3.randomize(2);
+
+        let order = |s: &mut Solver| (0..50).map(|_| s.decide().unwrap()).collect::<Vec<Literal>>();
+        let o1 = order(&mut s1);
+        assert_eq!(o1, order(&mut s2));
+        assert_ne!(o1, order(&mut s3));
+    }
+
+    #[test]
+    fn solve_with_assumptions_finds_a_model_of_the_assumptions(){
+        let mut solver = SOLVER::new(3);
+        solver.add_problem_clause(&mut vec![1, 2, 3]);
+        solver.add_problem_clause(&mut vec![-1, 2]);
+
+        assert!(solver.solve_with_assumptions(&[lit(-2)]));
+        assert!(solver.is_true(lit(-2)));
+        assert!(solver.is_true(lit(-1)));
+        assert!(solver.is_true(lit(3)));
+    }
+
+    #[test]
+    fn solve_with_assumptions_reports_the_failed_assumptions(){
+        let mut solver = SOLVER::new(4);
+        solver.add_problem_clause(&mut vec![-1, 2]);
+        solver.add_problem_clause(&mut vec![-2, 3]);
+
+        assert!(!solver.solve_with_assumptions(&[lit(4), lit(1), lit(-3)]));
+        let mut failed = solver.failed_assumptions().to_vec();
+        failed.sort_by_key(|l| l.to_isize());
+        assert_eq!(failed, vec![lit(-3), lit(1)]);
+
+        // the solver is not unsat per se
+        assert!(solver.solve());
+        assert!(solver.solve_with_assumptions(&[lit(4), lit(1)]));
+    }
+
+    #[test]
+    fn solve_with_contradictory_assumptions(){
+        let mut solver = SOLVER::new(2);
+        solver.add_problem_clause(&mut vec![1, 2]);
+
+        assert!(!solver.solve_with_assumptions(&[lit(1), lit(-1)]));
+        let mut failed = solver.failed_assumptions().to_vec();
+        failed.sort_by_key(|l| l.to_isize());
+        assert_eq!(failed, vec![lit(-1), lit(1)]);
+    }
+
+    #[test]
+    fn solve_with_assumptions_contradicting_the_root(){
+        let mut solver = SOLVER::new(2);
+        solver.add_problem_clause(&mut vec![-1]);
+
+        assert!(!solver.solve_with_assumptions(&[lit(2), lit(1)]));
+        assert_eq!(solver.failed_assumptions(), &[lit(1)]);
+    }
+
+    #[test]
+    fn solve_next_blocks_every_model_once(){
+        // 1 | 2 has three models
+        let mut solver = SOLVER::new(2);
+        solver.add_problem_clause(&mut vec![1, 2]);
+
+        let mut models = vec![];
+        let mut satisfiable = solver.solve();
+        while satisfiable {
+            let model: Vec<Literal> = [lit(1), lit(2)].iter()
+                .map(|&l| if solver.get_value(l) == Bool::True { l } else { !l })
+                .collect();
+            assert!(!models.contains(&model));
+            models.push(model.clone());
+
+            let blocking: Vec<Literal> = model.iter().map(|&l| !l).collect();
+            satisfiable = solver.solve_next(&blocking);
+        }
+        assert_eq!(models.len(), 3);
+        assert!(!solver.solve());
+    }
+
+    #[test]
+    fn solve_next_drops_the_duplicate_literals_of_the_blocking_clause(){
+        let mut solver = SOLVER::new(3);
+        solver.add_problem_clause(&mut vec![-1, 2]);
+        assert!(solver.solve_with_assumptions(&[lit(1), lit(3)]));
+
+        // 1 and 2 are both assigned at level 1, 3 at level 2
+        let blocking = [lit(-1), lit(-2), lit(-1), lit(-3), lit(-2)];
+        assert!(!solver.solve_next(&blocking));
+
+        let c_id = get_last_constraint(&solver);
+        assert_eq!(solver.clauses[c_id].to_vec(), vec![lit(-3), lit(-2), lit(-1)]);
+    }
+
+    #[test]
+    fn learn_only_backtracks_to_the_previous_level(){
+        let mut solver = SOLVER::new(4);
+        solver.add_problem_clause(&mut vec![-2, -3,  4]);
+        solver.add_problem_clause(&mut vec![-2, -3, -4]);
+
+        assert!(solver.branch(lit(1)).is_none());
+        assert!(solver.branch(lit(2)).is_none());
+        let conflict = solver.branch(lit(3)).expect("3 should yield a conflict");
+
+        // the learned clause (-2 | -3) asserts -3 at the level of 2, 1 remains decided
+        assert!(solver.learn(conflict).is_none());
+        assert_eq!(solver.decision_level(), 2);
+        assert_eq!(solver.get_value(lit(1)), Bool::True);
+        assert_eq!(solver.get_value(lit(-3)), Bool::True);
+    }
+
+    #[test]
+    fn learn_asserts_the_learned_unit_clauses(){
+        let mut solver = SOLVER::new(2);
+        solver.add_problem_clause(&mut vec![1, 2]);
+        solver.add_problem_clause(&mut vec![1, -2]);
+
+        let conflict = solver.branch(lit(-1)).expect("-1 should yield a conflict");
+        assert!(solver.learn(conflict).is_none());
+        assert_eq!(solver.decision_level(), 0);
+        assert_eq!(solver.get_value(lit(1)), Bool::True);
+        assert!(solver.solve());
+    }
+
+    #[test]
+    fn no_failed_assumption_when_the_problem_is_unsat(){
+        let mut solver = SOLVER::new(2);
+        solver.add_problem_clause(&mut vec![ 1, 2]);
+        solver.add_problem_clause(&mut vec![ 1,-2]);
+        solver.add_problem_clause(&mut vec![-1, 2]);
+        solver.add_problem_clause(&mut vec![-1,-2]);
+
+        assert!(!solver.solve_with_assumptions(&[lit(1)]));
+        // either the assumption or nothing at all is to blame, depending on the search
+        assert!(solver.failed_assumptions().len() <= 1);
+        assert!(!solver.solve());
+        assert!(solver.failed_assumptions().is_empty());
+    }
+
+    #[test]
+    fn problem_clauses_can_be_added_between_two_searches(){
+        let mut solver = SOLVER::new(2);
+        solver.add_problem_clause(&mut vec![1, 2]);
+        assert!(solver.solve());
+
+        solver.add_problem_clause(&mut vec![-1]);
+        assert!(solver.solve());
+        assert!(solver.is_true(lit(2)));
+
+        solver.add_problem_clause(&mut vec![-2]);
+        assert!(!solver.solve());
+    }
+
+    #[test]
+    fn add_clause_literals_adds_a_problem_clause(){
+        let mut solver = SOLVER::new(2);
+        let c0 = solver.add_clause_literals(&[lit(1), lit(-2)]).unwrap();
+        assert!(!solver.clauses.is_learned(c0));
+        assert_eq!(solver.clauses[c0].to_vec(), vec![lit(1), lit(-2)]);
+
+        assert!(solver.add_clause_literals(&[lit(-1)]).is_ok());
+        assert!(solver.add_clause_literals(&[lit(2)]).is_ok());
+        assert!(!solver.solve());
+    }
+
+    #[test]
+    fn new_var_can_be_used_in_the_clauses_added_afterwards(){
+        let mut solver = SOLVER::new(2);
+        solver.add_problem_clause(&mut vec![1, 2]);
+        assert!(solver.solve());
+
+        assert_eq!(Variable::from(3_usize), solver.new_var());
+        assert_eq!(Variable::from(4_usize), solver.new_var());
+        assert_eq!(4, solver.nb_vars());
+
+        solver.add_problem_clause(&mut vec![-1, 3]);
+        solver.add_problem_clause(&mut vec![-2, 3]);
+        solver.add_problem_clause(&mut vec![-3, 4]);
+        assert!(!solver.solve_with_assumptions(&[lit(-4)]));
+        assert_eq!(solver.failed_assumptions(), &[lit(-4)]);
+
+        assert!(solver.solve());
+        assert!(solver.is_true(lit(3)));
+        assert!(solver.is_true(lit(4)));
+    }
+
+    #[test]
+    fn problem_clauses_comprise_the_forced_literals(){
+        let mut solver = SOLVER::new(4);
+        solver.add_problem_clause(&mut vec![-1]);
+        solver.add_problem_clause(&mut vec![1, 2, 3]);
+        solver.add_problem_clause(&mut vec![2, 4]);
+
+        assert_eq!(solver.problem_clauses(), vec![
+            vec![lit(-1)],
+            vec![lit(2), lit(3)],
+            vec![lit(2), lit(4)]
+        ]);
+    }
+
+    #[test]
+    fn probe_propagates_and_backtrack_to_undoes(){
+        let mut solver = SOLVER::new(3);
+        solver.add_problem_clause(&mut vec![-1, 2]);
+        solver.add_problem_clause(&mut vec![-2,-3]);
+        assert!(solver.propagate_at_root());
+
+        assert!(solver.probe(lit(1)));
+        assert_eq!(1, solver.decision_level());
+        assert_eq!(3, solver.trail_len());
+        assert_eq!(lit(1), solver.trail_literal(0));
+        assert!(solver.is_true(lit(-3)));
+
+        solver.backtrack_to(0);
+        assert_eq!(0, solver.trail_len());
+
+        assert!(solver.probe(lit(3)));
+        assert!(solver.is_true(lit(-1)));
+        solver.backtrack_to(0);
+        assert!(solver.is_undef(lit(1)));
+    }
+
+    #[test]
+    fn probe_fails_when_the_literal_yields_a_conflict(){
+        let mut solver = SOLVER::new(2);
+        solver.add_problem_clause(&mut vec![-1, 2]);
+        solver.add_problem_clause(&mut vec![-1,-2]);
+        assert!(solver.propagate_at_root());
+
+        assert!(!solver.probe(lit(1)));
+        solver.backtrack_to(0);
+        assert!(solver.is_undef(lit(1)));
+        assert!(solver.is_undef(lit(2)));
+    }
+
+    #[test]
+    fn xor_constraints_are_propagated_at_the_root(){
+        let mut solver = SOLVER::new(3);
+        // x1 + x2 + x3 = 1 and x1 + x2 = 1 imply x3 = 0
+        solver.add_xor(&[1, 2, 3]);
+        solver.add_xor(&[1, 2]);
+        assert_eq!(solver.nb_xors(), 2);
+
+        assert!(solver.propagate_at_root());
+        assert!(solver.is_false(lit(3)));
+        assert!(solver.is_undef(lit(1)));
+    }
+
+    #[test]
+    fn xor_reasons_are_generated_on_demand(){
+        let mut solver = SOLVER::new(3);
+        solver.add_xor(&[1, 2, 3]);
+        assert!(solver.propagate_at_root());
+
+        assert!(solver.probe(lit(1)));
+        assert!(solver.probe(lit(-3)));
+        assert!(solver.is_false(lit(2)));
+        assert_eq!(solver.reason[var(2)], Some(XOR_IMPLIED));
+
+        let reason = solver.reason_of(var(2)).unwrap();
+        assert_eq!(solver.reason[var(2)], Some(reason));
+        assert_eq!(solver.clauses[reason].to_vec(), vec![lit(-2), lit(3), lit(-1)]);
+        assert!(solver.clauses.is_learned(reason));
+        assert!(solver.clauses.is_xor_reason(reason));
+    }
+
+    #[test]
+    fn reduce_db_forgets_the_unlocked_xor_reasons(){
+        let mut solver = SOLVER::new(6);
+        solver.add_xor(&[1, 2, 3]);
+        solver.add_xor(&[4, 5, 6]);
+        assert!(solver.propagate_at_root());
+
+        assert!(solver.probe(lit(1)));
+        assert!(solver.probe(lit(-3)));
+        assert!(solver.probe(lit(4)));
+        assert!(solver.probe(lit(-6)));
+        let r0 = solver.reason_of(var(2)).unwrap();
+        let r1 = solver.reason_of(var(5)).unwrap();
+        // glue clauses, yet they may be forgotten
+        assert_eq!(solver.clauses.lbd(r0), 2);
+        assert_eq!(solver.clauses.tier(r0), Tier::Local);
+        assert_eq!(solver.clauses.tier(r1), Tier::Local);
+
+        solver.backtrack_to(0);
+        solver.reduce_db();
+        assert_eq!(solver.clauses.len(), 1);
+        assert_eq!(solver.nb_xor_reasons, 1);
+    }
+
+    #[test]
+    fn xor_reasons_are_not_counted_as_learned_clauses(){
+        let mut solver = SOLVER::new(3);
+        solver.add_xor(&[1, 2, 3]);
+        assert!(solver.propagate_at_root());
+
+        assert!(solver.probe(lit(1)));
+        assert!(solver.probe(lit(-3)));
+        let reason = solver.reason_of(var(2)).unwrap();
+        assert_eq!(solver.nb_xor_reasons, 1);
+        assert_eq!(solver.nb_learned, 0);
+        assert_eq!(solver.nb_learned_since_minimiation, 0);
+
+        solver.remove_clause(reason);
+        assert_eq!(solver.nb_xor_reasons, 0);
+        assert_eq!(solver.nb_learned, 0);
+        assert_eq!(solver.nb_learned_since_minimiation, 0);
+    }
+
+    #[test]
+    fn trivial_xor_constraints_are_handled_without_gauss(){
+        let mut solver = SOLVER::new(2);
+        solver.add_xor(&[1, -1]);
+        solver.add_xor(&[-2]);
+        assert_eq!(solver.nb_xors(), 0);
+        assert!(solver.solve());
+        assert!(solver.is_false(lit(2)));
+
+        solver.add_xor(&[]);
+        assert!(!solver.solve());
+    }
+
+    #[test]
+    fn an_inconsistent_xor_system_is_unsat(){
+        let mut solver = SOLVER::new(4);
+        // the sum of these constraints is 0 = 1
+        solver.add_xor(&[1, 2, 3]);
+        solver.add_xor(&[2, 3, 4]);
+        solver.add_xor(&[1, 4]);
+        assert!(!solver.solve());
+    }
+
+    #[test]
+    fn xor_constraints_cooperate_with_the_clauses(){
+        let mut solver = SOLVER::new(6);
+        // x1 + x2 + x3 = 1, x4 + x5 + x6 = 1 and x1 + x4 = 0 (which imply x2 + x5 = x3 + x6)
+        // along with some clauses over x2, x3, x5 and x6: the models must satisfy both
+        solver.add_xor(&[1, 2, 3]);
+        solver.add_xor(&[4, 5, 6]);
+        solver.add_xor(&[1, -4]);
+        solver.add_problem_clause(&mut vec![-2, -5, 3]);
+        solver.add_problem_clause(&mut vec![-2, -5, 6]);
+        solver.add_problem_clause(&mut vec![2, 5, -3, -6]);
+        solver.add_problem_clause(&mut vec![-2, 5, -3]);
+        assert!(solver.solve());
+
+        let value = |l: iint| solver.get_value(lit(l)) == Bool::True;
+        assert!(value(1) ^ value(2) ^ value(3));
+        assert!(value(4) ^ value(5) ^ value(6));
+        assert_eq!(value(1), value(4));
+    }
+
+    #[test]
+    fn xor_conflicts_are_learned_from(){
+        let mut solver = SOLVER::new(4);
+        // x1 + x2 = 1, but deciding x4 then x3 forces both x1 and x2 through the clauses: gauss
+        // only finds the conflict once the clauses have reached their fixpoint at level 2
+        solver.add_xor(&[1, 2]);
+        solver.add_problem_clause(&mut vec![-4, -3, 1]);
+        solver.add_problem_clause(&mut vec![-4, -3, 2]);
+
+        assert!(solver.assign(lit(4), None).is_ok());
+        assert!(solver.propagate().is_none());
+        assert!(solver.assign(lit(3), None).is_ok());
+        let conflict = solver.propagate().unwrap();
+        assert!(solver.clauses.is_xor_reason(conflict));
+        assert!(solver.resolve_conflict(conflict).is_ok());
+
+        assert_eq!(solver.nb_learned, 1);
+        let learned = get_last_constraint(&solver);
+        assert!(!solver.clauses.is_xor_reason(learned));
+        assert_eq!(solver.clauses[learned].to_vec(), vec![lit(-3), lit(-4)]);
+        assert_eq!(1, solver.nb_decisions);
+        assert!(solver.is_false(lit(3)));
+    }
+
+    #[test]
+    fn remove_satisfied_drops_the_problem_clauses_satisfied_at_the_root(){
+        let mut solver = SOLVER::new(4);
+        solver.add_problem_clause(&mut vec![-4, 1, 2]);
+        solver.add_problem_clause(&mut vec![-4, -1, 3]);
+        solver.add_problem_clause(&mut vec![1, 2, 3]);
+        solver.add_problem_clause(&mut vec![-4]);
+        assert!(solver.solve());
+
+        assert_eq!(solver.remove_satisfied(), 2);
+        let mut clauses = solver.problem_clauses();
+        clauses[1].sort_by_key(|l| l.to_isize());
+        assert_eq!(clauses, vec![vec![lit(-4)], vec![lit(1), lit(2), lit(3)]]);
+        assert!(solver.solve_with_assumptions(&[lit(-1), lit(-2)]));
+        assert!(solver.is_true(lit(3)));
     }
 
     #[test]
-    fn rollback_saves_the_old_phase() {
-        let mut solver = SOLVER::new(5);
+    fn remove_xors_frees_the_auxiliary_variables(){
+        let mut solver = SOLVER::new(3);
+        // x3 <-> x1 ^ x2
+        solver.add_xor(&[1, 2, -3]);
+        assert!(solver.solve_with_assumptions(&[lit(1), lit(2)]));
+        assert!(solver.is_false(lit(3)));
 
-        for i in 1..6 {
-            let lit = lit(i);
-            assert!(!solver.phase_saving.contains(lit.var().into() ));
-            assert!(solver.assign(lit, None).is_ok());
-        }
+        assert_eq!(solver.remove_xors(var(3)), 1);
+        assert_eq!(solver.nb_xors(), 0);
+        assert!(solver.solve_with_assumptions(&[lit(1), lit(2), lit(3)]));
+        solver.add_problem_clause(&mut vec![-3]);
+        assert!(solver.solve_with_assumptions(&[lit(1), lit(-2)]));
+    }
 
-        solver.rollback(3);
-        for i in (4..6).rev() {
-            let l = lit(i);
-            assert!(solver.phase_saving.contains(l.var().into() ));
+    #[test]
+    fn detect_xors_finds_the_xor_constraints_among_the_clauses(){
+        let mut solver = SOLVER::new(4);
+        for clause in Xor::new(&[lit(1), lit(2), lit(-3)]).clauses() {
+            let mut clause: Vec<iint> = clause.iter().map(|l| l.to_isize() as iint).collect();
+            solver.add_problem_clause(&mut clause);
         }
-    }
+        solver.add_problem_clause(&mut vec![1, 2, 4]);
 
+        assert_eq!(solver.detect_xors(), 1);
+        assert_eq!(solver.nb_xors(), 1);
+        assert!(solver.solve_with_assumptions(&[lit(1), lit(2)]));
+        assert!(solver.is_false(lit(3)));
+        // the constraint remains encoded by the problem clauses (and only by them)
+        assert_eq!(solver.problem_clauses().len(), 5);
+    }
 
     #[test]
     fn solve_must_be_true_when_problem_is_vacuously_satisfiable(){
@@ -3086,21 +5218,23 @@ mod tests {
     // clauses do not derive from the original problem statement)
     fn reduce_db_removes_worst_clauses(){
         let mut solver = SOLVER::new(5);
-        solver.add_learned_clause(vec![lit(1), lit(2), lit(3), lit(4), lit(5)]);
-        solver.add_learned_clause(vec![lit(1), lit(2)]);
+        let c0 = solver.add_learned_clause(vec![lit(1), lit(2), lit(3), lit(4), lit(5)]).unwrap();
+        let c1 = solver.add_learned_clause(vec![lit(1), lit(2)]).unwrap();
 
-        solver.lbd[0] = 5; // should be dropped
-        solver.lbd[1] = 3; // should be kept
+        solver.clauses.set_lbd(c0, 5); // should be dropped
+        solver.clauses.set_lbd(c1, 3); // should be kept
+        solver.clauses.set_tier(c0, Tier::Local);
+        solver.clauses.set_tier(c1, Tier::Local);
 
-        solver.lbd_recently_updated.set(0, false);
-        solver.lbd_recently_updated.set(1, false);
+        solver.clauses.set_protected(c0, false);
+        solver.clauses.set_protected(c1, false);
 
         assert!(solver.assign(lit(1), None).is_ok());
 
         assert_eq!(2, solver.clauses.len());
         solver.reduce_db();
         assert_eq!(1, solver.clauses.len());
-        assert_eq!("Clause([Literal(1), Literal(2)])", format!("{:?}", solver.clauses[0]));
+        assert_eq!("[Clause([Literal(1), Literal(2)])]", format!("{:?}", solver.clauses));
     }
 
     #[test]
@@ -3110,11 +5244,13 @@ mod tests {
     // Nevertheless, it lets me test what I intend to test (and just that!)
     fn reduce_db_does_not_remove_locked_clauses(){
         let mut solver = SOLVER::new(5);
-        solver.add_learned_clause(vec![lit(2), lit(1), lit(3), lit(4), lit(5)]);
-        solver.add_learned_clause(vec![lit(1), lit(2), lit(3)]);
+        let c0 = solver.add_learned_clause(vec![lit(2), lit(1), lit(3), lit(4), lit(5)]).unwrap();
+        let c1 = solver.add_learned_clause(vec![lit(1), lit(2), lit(3)]).unwrap();
 
-        solver.lbd[0] = 5; // should be dropped, but it is locked
-        solver.lbd[1] = 2; // should be kept
+        solver.clauses.set_lbd(c0, 5); // should be dropped, but it is locked
+        solver.clauses.set_lbd(c1, 2); // should be kept
+        solver.clauses.set_tier(c0, Tier::Local);
+        solver.clauses.set_tier(c1, Tier::Core);
 
         assert!(solver.assign(lit(1), None   ).is_ok());
         assert!(solver.assign(lit(2), Some(0)).is_ok());
@@ -3128,24 +5264,26 @@ mod tests {
     #[test]
     fn reduce_db_does_not_impact_problem_clauses(){
         let mut solver = SOLVER::new(5);
-        solver.add_problem_clause(&mut vec![2, 3, 4, 5]);
-        solver.add_learned_clause(vec![lit(1), lit(3), lit(4)]);
-        solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]);
+        let c0 = solver.add_problem_clause(&mut vec![2, 3, 4, 5]).unwrap();
+        let c1 = solver.add_learned_clause(vec![lit(1), lit(3), lit(4)]).unwrap();
+        let c2 = solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]).unwrap();
 
-        solver.lbd[0] = 18; // should be removed but it is a problem clause
-        solver.lbd[1] = 5 ;  // must be dropped
-        solver.lbd[2] = 4 ;  // must be kept
+        solver.clauses.set_lbd(c0, 18); // should be removed but it is a problem clause
+        solver.clauses.set_lbd(c1, 5);  // must be dropped
+        solver.clauses.set_lbd(c2, 4);  // must be kept
+        solver.clauses.set_tier(c1, Tier::Local);
+        solver.clauses.set_tier(c2, Tier::Local);
 
-        solver.lbd_recently_updated.set(0, false);
-        solver.lbd_recently_updated.set(1, false);
-        solver.lbd_recently_updated.set(2, false);
+        solver.clauses.set_protected(c0, false);
+        solver.clauses.set_protected(c1, false);
+        solver.clauses.set_protected(c2, false);
 
         assert!(solver.assign(lit(1), None).is_ok());
 
         assert_eq!(3, solver.clauses.len());
         solver.reduce_db();
         assert_eq!(2, solver.clauses.len());
-        assert!(! solver.clauses[0].is_learned);
+        assert!(! solver.clauses.is_learned(0));
     }
 
     #[test]
@@ -3168,16 +5306,19 @@ mod tests {
     #[test]
     fn reduce_db_tries_to_removes_half_of_the_clauses(){
         let mut solver = SOLVER::new(5);
-        solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]);
-        solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]);
-        solver.add_learned_clause(vec![lit(4), lit(3), lit(5)]);
-
-        solver.lbd[0] = 3;
-        solver.lbd[1] = 3;
-        solver.lbd[2] = 3;
-        solver.lbd_recently_updated.set(0, false);
-        solver.lbd_recently_updated.set(1, false);
-        solver.lbd_recently_updated.set(2, false);
+        let c0 = solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]).unwrap();
+        let c1 = solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]).unwrap();
+        let c2 = solver.add_learned_clause(vec![lit(4), lit(3), lit(5)]).unwrap();
+
+        solver.clauses.set_lbd(c0, 3);
+        solver.clauses.set_lbd(c1, 3);
+        solver.clauses.set_lbd(c2, 3);
+        solver.clauses.set_tier(c0, Tier::Local);
+        solver.clauses.set_tier(c1, Tier::Local);
+        solver.clauses.set_tier(c2, Tier::Local);
+        solver.clauses.set_protected(c0, false);
+        solver.clauses.set_protected(c1, false);
+        solver.clauses.set_protected(c2, false);
 
         assert_eq!(3, solver.clauses.len());
         solver.reduce_db();
@@ -3187,13 +5328,13 @@ mod tests {
     #[test]
     fn reduce_db_does_not_remove_recent_clauses(){
         let mut solver = SOLVER::new(5);
-        solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]);
-        solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]);
-        solver.add_learned_clause(vec![lit(4), lit(3), lit(5)]);
+        let c0 = solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]).unwrap();
+        let c1 = solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]).unwrap();
+        let c2 = solver.add_learned_clause(vec![lit(4), lit(3), lit(5)]).unwrap();
 
-        solver.lbd[0] = 3;
-        solver.lbd[1] = 3;
-        solver.lbd[2] = 3;
+        solver.clauses.set_lbd(c0, 3);
+        solver.clauses.set_lbd(c1, 3);
+        solver.clauses.set_lbd(c2, 3);
 
         assert_eq!(3, solver.clauses.len());
         solver.reduce_db();
@@ -3203,16 +5344,16 @@ mod tests {
     #[test]
     fn reduce_db_does_not_remove_clauses_having_a_recently_updated_lbd(){
         let mut solver = SOLVER::new(5);
-        solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]);
-        solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]);
-        solver.add_learned_clause(vec![lit(4), lit(3), lit(5)]);
+        let c0 = solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]).unwrap();
+        let c1 = solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]).unwrap();
+        let c2 = solver.add_learned_clause(vec![lit(4), lit(3), lit(5)]).unwrap();
 
-        solver.lbd[0] = 3;
-        solver.lbd[1] = 3;
-        solver.lbd[2] = 3;
-        solver.lbd_recently_updated.set(0, true);
-        solver.lbd_recently_updated.set(1, true);
-        solver.lbd_recently_updated.set(2, true);
+        solver.clauses.set_lbd(c0, 3);
+        solver.clauses.set_lbd(c1, 3);
+        solver.clauses.set_lbd(c2, 3);
+        solver.clauses.set_protected(c0, true);
+        solver.clauses.set_protected(c1, true);
+        solver.clauses.set_protected(c2, true);
 
         assert_eq!(3, solver.clauses.len());
         solver.reduce_db();
@@ -3221,33 +5362,34 @@ mod tests {
 
     #[test]
     fn reduce_db_must_maintain_a_coherent_clause_database() {
-        // The ids of the clauses 'replacing' the removed ones must be adapted
+        // The references to the clauses which are relocated by the garbage collection must be
+        // adapted
         let mut solver = SOLVER::new(6);
-        solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]); // c0
-        solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]); // c1
-        solver.add_learned_clause(vec![lit(4), lit(3), lit(5)]); // c2
-        solver.add_learned_clause(vec![lit(6), lit(3), lit(5)]); // c3
-
-        solver.lbd[0] = 7; // c0 is the clause which will be deleted
-        solver.lbd[1] = 4;
-        solver.lbd[2] = 3;
-        solver.lbd[3] = 5; // c3 will also be deleted
-        solver.lbd_recently_updated.set(0, false);
-        solver.lbd_recently_updated.set(1, false);
-        solver.lbd_recently_updated.set(2, false);
-        solver.lbd_recently_updated.set(3, false);
-
-        assert_eq!(&solver.watchers[lit(1)], &vec![0]);
-        assert_eq!(&solver.watchers[lit(2)], &vec![1]);
-        assert_eq!(&solver.watchers[lit(3)], &vec![0, 1, 2, 3]);
-        assert_eq!(&solver.watchers[lit(4)], &vec![2]);
-        assert_eq!(&solver.watchers[lit(5)], &vec![ ]);
-        assert_eq!(&solver.watchers[lit(6)], &vec![3]);
-
-        // let's say that 2nd clause forces the value of lit(5)
+        let c0 = solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]).unwrap();
+        let c1 = solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]).unwrap();
+        let c2 = solver.add_learned_clause(vec![lit(4), lit(3), lit(5)]).unwrap();
+        let c3 = solver.add_learned_clause(vec![lit(6), lit(3), lit(5)]).unwrap();
+
+        solver.clauses.set_lbd(c0, 7); // c0 is the clause which will be deleted
+        solver.clauses.set_lbd(c1, 4);
+        solver.clauses.set_lbd(c2, 3);
+        solver.clauses.set_lbd(c3, 5); // c3 will also be deleted
+        solver.clauses.set_protected(c0, false);
+        solver.clauses.set_protected(c1, false);
+        solver.clauses.set_protected(c2, false);
+        solver.clauses.set_protected(c3, false);
+
+        assert_eq!(watched_by(&solver, lit(1)), vec![c0]);
+        assert_eq!(watched_by(&solver, lit(2)), vec![c1]);
+        assert_eq!(watched_by(&solver, lit(3)), vec![c0, c1, c2, c3]);
+        assert_eq!(watched_by(&solver, lit(4)), vec![c2]);
+        assert_eq!(watched_by(&solver, lit(5)), vec![ ]);
+        assert_eq!(watched_by(&solver, lit(6)), vec![c3]);
+
+        // let's say that 3rd clause forces the value of lit(5)
         solver.assign(lit(-4), None);
         solver.assign(lit(-3), None);
-        solver.propagate(); // solver.assign(lit(5), Some(2));
+        solver.propagate(); // solver.assign(lit(5), Some(c2));
 
         // Ensure state before DB reduction (literals shuffled because of propagation)
         let database = format!("[{}, {}, {}, {}]",
@@ -3258,45 +5400,239 @@ mod tests {
                                );
         assert_eq!(database, format!("{:?}", solver.clauses));
 
-        assert_eq!(&solver.watchers[lit(1)], &vec![0]);
-        assert_eq!(&solver.watchers[lit(2)], &vec![1]);
-        assert_eq!(&solver.watchers[lit(3)], &vec![2]);
-        assert_eq!(&solver.watchers[lit(4)], &vec![]);
-        assert_eq!(&solver.watchers[lit(5)], &vec![2, 3, 1, 0]);
-        assert_eq!(&solver.watchers[lit(6)], &vec![3]);
+        assert_eq!(watched_by(&solver, lit(1)), vec![c0]);
+        assert_eq!(watched_by(&solver, lit(2)), vec![c1]);
+        assert_eq!(watched_by(&solver, lit(3)), vec![c2]);
+        assert_eq!(watched_by(&solver, lit(4)), vec![]);
+        assert_eq!(watched_by(&solver, lit(5)), vec![c2, c0, c1, c3]);
+        assert_eq!(watched_by(&solver, lit(6)), vec![c3]);
+
+        assert_eq!(Some(c2), solver.reason[var(5)]);
 
-        assert_eq!(Some(2), solver.reason[var(5)]);
+        // All clauses are considered local (even though c2 got promoted while propagating)
+        for c in solver.clauses.ids().collect::<Vec<ClauseId>>() { solver.clauses.set_tier(c, Tier::Local); }
 
-        // Reduce DB
+        // Reduce DB: half of the arena is wasted, hence the garbage is collected
         solver.reduce_db(); // if it doesn't panic with out of bounds, it means that reduce_db
-                            // appropriately replaced all references to c3 by 0
+                            // appropriately relocated all references to c1 and c2
 
         // Ensure state after DB reduction
         let database = format!("[{}, {}]",
-        "Clause([Literal(5), Literal(3), Literal(4)])",  // originally c2 (lit shuffled because of UP)
-        "Clause([Literal(2), Literal(5), Literal(3)])"); // originally c1 (lit shuffled because of UP)
+        "Clause([Literal(2), Literal(5), Literal(3)])",  // originally c1 (lit shuffled because of UP)
+        "Clause([Literal(5), Literal(3), Literal(4)])"); // originally c2 (lit shuffled because of UP)
         assert_eq!(database, format!("{:?}", solver.clauses));
 
-        assert_eq!(&solver.watchers[lit(1)], &vec![ ]);
-        assert_eq!(&solver.watchers[lit(2)], &vec![1]);
-        assert_eq!(&solver.watchers[lit(3)], &vec![0]);
-        assert_eq!(&solver.watchers[lit(4)], &vec![]);
-        assert_eq!(&solver.watchers[lit(5)], &vec![0, 1]);
+        let ids: Vec<ClauseId> = solver.clauses.ids().collect();
+        let (n1, n2) = (ids[0], ids[1]);
+        assert_eq!(0, n1);
+
+        assert_eq!(watched_by(&solver, lit(1)), vec![ ]);
+        assert_eq!(watched_by(&solver, lit(2)), vec![n1]);
+        assert_eq!(watched_by(&solver, lit(3)), vec![n2]);
+        assert_eq!(watched_by(&solver, lit(4)), vec![]);
+        assert_eq!(watched_by(&solver, lit(5)), vec![n2, n1]);
+
+        assert_eq!(Some(n2), solver.reason[var(5)]);
+    }
+
+    #[test]
+    fn add_learned_clause_assigns_a_tier_based_on_the_lbd(){
+        let mut solver = SOLVER::new(8);
+        for i in 1..9 {
+            assert!(solver.assign(lit(i), None).is_ok());
+        }
+        let c0 = solver.add_learned_clause(vec![lit(-1), lit(-2)]).unwrap();
+        let c1 = solver.add_learned_clause(vec![lit(-1), lit(-2), lit(-3), lit(-4)]).unwrap();
+        let c2 = solver.add_learned_clause(vec![lit(-1), lit(-2), lit(-3), lit(-4), lit(-5), lit(-6), lit(-7)]).unwrap();
+
+        assert_eq!(Tier::Core , solver.clauses.tier(c0));
+        assert_eq!(Tier::Tier2, solver.clauses.tier(c1));
+        assert_eq!(Tier::Local, solver.clauses.tier(c2));
+    }
+
+    #[test]
+    fn reduce_db_never_removes_core_clauses(){
+        let mut solver = SOLVER::new(5);
+        let c0 = solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]).unwrap();
+        let c1 = solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]).unwrap();
+
+        solver.clauses.set_tier(c0, Tier::Core);
+        solver.clauses.set_tier(c1, Tier::Core);
+        solver.clauses.set_protected(c0, false);
+        solver.clauses.set_protected(c1, false);
+
+        solver.reduce_db();
+        assert_eq!(2, solver.clauses.len());
+    }
+
+    #[test]
+    fn reduce_db_demotes_the_unused_tier2_clauses(){
+        let mut solver = SOLVER::new(5);
+        let c0 = solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]).unwrap();
+        let c1 = solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]).unwrap();
+
+        solver.clauses.set_tier(c0, Tier::Tier2);
+        solver.clauses.set_tier(c1, Tier::Tier2);
+        solver.clauses.set_protected(c0, false);
+        solver.clauses.set_protected(c1, false);
+        solver.clauses.set_used(c1, true);
+
+        solver.reduce_db();
+        assert_eq!(2, solver.clauses.len());
+        assert_eq!(Tier::Local, solver.clauses.tier(c0));
+        assert_eq!(Tier::Tier2, solver.clauses.tier(c1));
+        assert!(!solver.clauses.is_used(c1));
+    }
+
+    #[test]
+    fn reduce_db_does_not_remove_the_local_clauses_used_since_the_last_reduction(){
+        let mut solver = SOLVER::new(5);
+        let c0 = solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]).unwrap();
+        let c1 = solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]).unwrap();
+
+        solver.clauses.set_tier(c0, Tier::Local);
+        solver.clauses.set_tier(c1, Tier::Local);
+        solver.clauses.set_lbd(c0, 9);
+        solver.clauses.set_lbd(c1, 7);
+        solver.clauses.set_protected(c0, false);
+        solver.clauses.set_protected(c1, false);
+        solver.clauses.set_used(c0, true);
+
+        solver.reduce_db();
+        assert_eq!(1, solver.clauses.len());
+        assert_eq!("[Clause([Literal(1), Literal(3), Literal(5)])]", format!("{:?}", solver.clauses));
+    }
+
+    #[test]
+    fn find_first_uip_marks_the_clauses_it_uses(){
+        let mut solver = SOLVER::new(4);
+        solver.add_problem_clause(&mut vec![-1, 2]);
+        solver.add_problem_clause(&mut vec![-1, 3]);
+        let c2 = solver.add_problem_clause(&mut vec![-2, -3, 4]).unwrap();
+        solver.add_problem_clause(&mut vec![-2, -4]);
+
+        assert!(solver.assign(lit(1), None).is_ok());
+        let conflict = solver.propagate();
+        assert!(conflict.is_some());
+
+        solver.find_first_uip(conflict.unwrap());
+        assert!(solver.clauses.is_used(conflict.unwrap()));
+        assert!(solver.clauses.is_used(c2));
+    }
+
+    #[test]
+    fn clause_bump_promotes_the_clause_when_its_lbd_improves(){
+        let mut solver = SOLVER::new(8);
+        let c0 = solver.add_learned_clause(vec![lit(1), lit(2), lit(3), lit(4), lit(5), lit(6), lit(7), lit(8)]).unwrap();
+        solver.clauses.set_lbd(c0, 8);
+        solver.clauses.set_tier(c0, Tier::Local);
+
+        assert!(solver.assign(lit(-8), None).is_ok());
+        assert!(solver.assign(lit(-7), None).is_ok());
+        assert!(solver.assign(lit(-6), None).is_ok());
+        assert!(solver.assign(lit(-5), None).is_ok());
+
+        solver.clause_bump(0);
+        assert_eq!(5, solver.clauses.lbd(c0));
+        assert_eq!(Tier::Tier2, solver.clauses.tier(c0));
+    }
+
+    #[test]
+    fn reduce_db_forgets_the_least_active_local_clauses_whatever_their_lbd(){
+        let mut solver = SOLVER::new(5);
+        let c0 = solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]).unwrap();
+        let c1 = solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]).unwrap();
+
+        solver.clauses.set_tier(c0, Tier::Local);
+        solver.clauses.set_tier(c1, Tier::Local);
+        solver.clauses.set_lbd(c0, 12); // high lbd, but very active: kept
+        solver.clauses.set_lbd(c1, 7);  // low lbd, but hardly active: forgotten
+        solver.clauses.set_activity(c0, 10.0);
+        solver.clauses.set_activity(c1, 0.5);
+        solver.clauses.set_protected(c0, false);
+        solver.clauses.set_protected(c1, false);
+
+        solver.reduce_db();
+        assert_eq!(1, solver.clauses.len());
+        assert_eq!("[Clause([Literal(1), Literal(3), Literal(5)])]", format!("{:?}", solver.clauses));
+    }
+
+    #[test]
+    fn reduce_db_breaks_activity_ties_by_lbd(){
+        let mut solver = SOLVER::new(5);
+        let c0 = solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]).unwrap();
+        let c1 = solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]).unwrap();
+
+        solver.clauses.set_tier(c0, Tier::Local);
+        solver.clauses.set_tier(c1, Tier::Local);
+        solver.clauses.set_lbd(c0, 8);
+        solver.clauses.set_lbd(c1, 9);
+        solver.clauses.set_activity(c0, 5.0);
+        solver.clauses.set_activity(c1, 5.0);
+        solver.clauses.set_protected(c0, false);
+        solver.clauses.set_protected(c1, false);
+
+        solver.reduce_db();
+        assert_eq!(1, solver.clauses.len());
+        assert_eq!("[Clause([Literal(1), Literal(3), Literal(5)])]", format!("{:?}", solver.clauses));
+    }
+
+    #[test]
+    fn bump_clause_activity_increases_the_activity_by_the_increment(){
+        let mut solver = SOLVER::new(5);
+        let c0 = solver.add_problem_clause(&mut vec![1, 3, 5]).unwrap();
+
+        solver.bump_clause_activity(0);
+        assert_eq!(1.0, solver.clauses.activity(c0));
+
+        solver.decay_clause_activity();
+        solver.bump_clause_activity(0);
+        assert!(solver.clauses.activity(c0) > 2.0);
+    }
+
+    #[test]
+    fn bump_clause_activity_rescales_all_activities_on_overflow(){
+        let mut solver = SOLVER::new(5);
+        let c0 = solver.add_problem_clause(&mut vec![1, 3, 5]).unwrap();
+        let c1 = solver.add_problem_clause(&mut vec![2, 3, 5]).unwrap();
+
+        solver.clauses.set_activity(c1, 4e20);
+        solver.clause_inc  = 2e20;
+        solver.bump_clause_activity(0);
+
+        assert!((solver.clauses.activity(c0) - 2.0).abs() < 1e-9);
+        assert!((solver.clauses.activity(c1) - 4.0).abs() < 1e-9);
+        assert!((solver.clause_inc  - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn find_first_uip_bumps_the_activity_of_the_clauses_it_uses(){
+        let mut solver = SOLVER::new(4);
+        solver.add_problem_clause(&mut vec![-1, 2]);
+        solver.add_problem_clause(&mut vec![-1, 3]);
+        let c2 = solver.add_problem_clause(&mut vec![-2, -3, 4]).unwrap();
+        solver.add_problem_clause(&mut vec![-2, -4]);
+
+        assert!(solver.assign(lit(1), None).is_ok());
+        let conflict = solver.propagate().unwrap();
 
-        assert_eq!(Some(0), solver.reason[var(5)]);
+        solver.find_first_uip(conflict);
+        assert!(solver.clauses.activity(conflict) > 0.0);
+        assert!(solver.clauses.activity(c2) > 0.0);
+        assert!(solver.clause_inc > 1.0);
     }
 
     #[test]
     /// This test checks two features of the remove_clause function:
     ///
     /// A. remove_clause must remove all watchers pointing to the removed clause
-    /// B. remove_clause must redirect the watchers pointing to the last clause
+    /// B. remove_clause must not change the watchers pointing to the other clauses
     fn remove_clause_must_remove_the_clause_from_the_watched_lists(){
         let mut solver = SOLVER::new(6);
-        solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]); // c0
-        solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]); // c1
-        solver.add_learned_clause(vec![lit(4), lit(3), lit(5)]); // c2
-        solver.add_learned_clause(vec![lit(6), lit(3), lit(5)]); // c3
+        let c0 = solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]).unwrap();
+        let c1 = solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]).unwrap();
+        let c2 = solver.add_learned_clause(vec![lit(4), lit(3), lit(5)]).unwrap();
+        let c3 = solver.add_learned_clause(vec![lit(6), lit(3), lit(5)]).unwrap();
 
         let database = format!("[{}, {}, {}, {}]",
                                "Clause([Literal(1), Literal(3), Literal(5)])", // c0
@@ -3306,14 +5642,14 @@ mod tests {
         );
         assert_eq!(database, format!("{:?}", solver.clauses));
 
-        assert_eq!(&solver.watchers[lit(1)], &vec![0]);
-        assert_eq!(&solver.watchers[lit(2)], &vec![1]);
-        assert_eq!(&solver.watchers[lit(3)], &vec![0, 1, 2, 3]);
-        assert_eq!(&solver.watchers[lit(4)], &vec![2]);
-        assert_eq!(&solver.watchers[lit(5)], &vec![ ]);
-        assert_eq!(&solver.watchers[lit(6)], &vec![3]);
+        assert_eq!(watched_by(&solver, lit(1)), vec![c0]);
+        assert_eq!(watched_by(&solver, lit(2)), vec![c1]);
+        assert_eq!(watched_by(&solver, lit(3)), vec![c0, c1, c2, c3]);
+        assert_eq!(watched_by(&solver, lit(4)), vec![c2]);
+        assert_eq!(watched_by(&solver, lit(5)), vec![ ]);
+        assert_eq!(watched_by(&solver, lit(6)), vec![c3]);
 
-        solver.remove_clause(2);
+        solver.remove_clause(c2);
 
         let database = format!("[{}, {}, {}]",
                                "Clause([Literal(1), Literal(3), Literal(5)])", // c0
@@ -3322,12 +5658,12 @@ mod tests {
         );
         assert_eq!(database, format!("{:?}", solver.clauses));
 
-        assert_eq!(&solver.watchers[lit(1)], &vec![0]);
-        assert_eq!(&solver.watchers[lit(2)], &vec![1]);
-        assert_eq!(&solver.watchers[lit(3)], &vec![0, 1, 2]);
-        assert_eq!(&solver.watchers[lit(4)], &vec![ ]);
-        assert_eq!(&solver.watchers[lit(5)], &vec![ ]);
-        assert_eq!(&solver.watchers[lit(6)], &vec![2]);
+        assert_eq!(watched_by(&solver, lit(1)), vec![c0]);
+        assert_eq!(watched_by(&solver, lit(2)), vec![c1]);
+        assert_eq!(watched_by(&solver, lit(3)), vec![c0, c1, c3]);
+        assert_eq!(watched_by(&solver, lit(4)), vec![ ]);
+        assert_eq!(watched_by(&solver, lit(5)), vec![ ]);
+        assert_eq!(watched_by(&solver, lit(6)), vec![c3]);
     }
 
     #[test]
@@ -3336,23 +5672,15 @@ mod tests {
         let mut solver = SOLVER::new(6);
         solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]); // c0
         solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]); // c1
-        solver.add_learned_clause(vec![lit(4), lit(3), lit(5)]); // c2
+        let c2 = solver.add_learned_clause(vec![lit(4), lit(3), lit(5)]).unwrap();
         solver.add_learned_clause(vec![lit(6), lit(3), lit(5)]); // c3
 
-        solver.assign(lit(4), Some(2));
+        solver.assign(lit(4), Some(c2));
 
-        let database = format!("[{}, {}, {}, {}]",
-                               "Clause([Literal(1), Literal(3), Literal(5)])", // c0
-                               "Clause([Literal(2), Literal(3), Literal(5)])", // c1
-                               "Clause([Literal(4), Literal(3), Literal(5)])", // c2
-                               "Clause([Literal(6), Literal(3), Literal(5)])", // c3
-        );
-        assert_eq!(database, format!("{:?}", solver.clauses));
-
-        assert!   (solver.is_locked(2));
-        assert_eq!(solver.reason[var(4)], Some(2));
+        assert!   (solver.is_locked(c2));
+        assert_eq!(solver.reason[var(4)], Some(c2));
 
-        solver.remove_clause(2);
+        solver.remove_clause(c2);
 
         let database = format!("[{}, {}, {}]",
                                "Clause([Literal(1), Literal(3), Literal(5)])", // c0
@@ -3361,119 +5689,65 @@ mod tests {
         );
         assert_eq!(database, format!("{:?}", solver.clauses));
 
-        assert!   (!solver.is_locked(2));
+        assert!   (!solver.is_locked(c2));
         assert_eq!(solver.reason[var(4)], None);
     }
 
-
     #[test]
-    fn remove_clause_must_redirect_the_reason_of_the_last_clause(){
+    fn remove_clause_must_not_change_the_reason_of_the_other_clauses(){
         // This test won't succeed if self.rl is set to True
         let mut solver = SOLVER::new(6);
         solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]); // c0
         solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]); // c1
-        solver.add_learned_clause(vec![lit(4), lit(3), lit(5)]); // c2
-        solver.add_learned_clause(vec![lit(6), lit(3), lit(5)]); // c3
-
-        solver.assign(lit(6), Some(3));
-
-        let database = format!("[{}, {}, {}, {}]",
-                               "Clause([Literal(1), Literal(3), Literal(5)])", // c0
-                               "Clause([Literal(2), Literal(3), Literal(5)])", // c1
-                               "Clause([Literal(4), Literal(3), Literal(5)])", // c2
-                               "Clause([Literal(6), Literal(3), Literal(5)])", // c3
-        );
-        assert_eq!(database, format!("{:?}", solver.clauses));
+        let c2 = solver.add_learned_clause(vec![lit(4), lit(3), lit(5)]).unwrap();
+        let c3 = solver.add_learned_clause(vec![lit(6), lit(3), lit(5)]).unwrap();
 
-        assert!   (solver.is_locked(3));
-        assert_eq!(solver.reason[var(6)], Some(3));
+        solver.assign(lit(6), Some(c3));
 
-        solver.remove_clause(2);
+        assert!   (solver.is_locked(c3));
+        assert_eq!(solver.reason[var(6)], Some(c3));
 
-        let database = format!("[{}, {}, {}]",
-                               "Clause([Literal(1), Literal(3), Literal(5)])", // c0
-                               "Clause([Literal(2), Literal(3), Literal(5)])", // c1
-                               "Clause([Literal(6), Literal(3), Literal(5)])", // c3
-        );
-        assert_eq!(database, format!("{:?}", solver.clauses));
+        solver.remove_clause(c2);
 
-        assert!   (solver.is_locked(2));
-        assert_eq!(solver.reason[var(6)], Some(2));
+        assert!   (solver.is_locked(c3));
+        assert_eq!(solver.reason[var(6)], Some(c3));
     }
 
     #[test]
-    fn remove_clause_must_not_redirect_watchers_when_the_last_clause_is_removed(){
+    fn remove_clause_must_not_reclaim_the_memory_of_the_clause(){
         let mut solver = SOLVER::new(6);
-        solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]); // c0
-        solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]); // c1
-        solver.add_learned_clause(vec![lit(4), lit(3), lit(5)]); // c2
-        solver.add_learned_clause(vec![lit(6), lit(3), lit(5)]); // c3
-
-        let database = format!("[{}, {}, {}, {}]",
-                               "Clause([Literal(1), Literal(3), Literal(5)])", // c0
-                               "Clause([Literal(2), Literal(3), Literal(5)])", // c1
-                               "Clause([Literal(4), Literal(3), Literal(5)])", // c2
-                               "Clause([Literal(6), Literal(3), Literal(5)])", // c3
-        );
-        assert_eq!(database, format!("{:?}", solver.clauses));
+        let c0 = solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]).unwrap();
+        let c1 = solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]).unwrap();
 
-        assert_eq!(&solver.watchers[lit(1)], &vec![0]);
-        assert_eq!(&solver.watchers[lit(2)], &vec![1]);
-        assert_eq!(&solver.watchers[lit(3)], &vec![0, 1, 2, 3]);
-        assert_eq!(&solver.watchers[lit(4)], &vec![2]);
-        assert_eq!(&solver.watchers[lit(5)], &vec![ ]);
-        assert_eq!(&solver.watchers[lit(6)], &vec![3]);
-
-        solver.remove_clause(3);
-
-        let database = format!("[{}, {}, {}]",
-                               "Clause([Literal(1), Literal(3), Literal(5)])", // c0
-                               "Clause([Literal(2), Literal(3), Literal(5)])", // c1
-                               "Clause([Literal(4), Literal(3), Literal(5)])", // c2
-        );
-        assert_eq!(database, format!("{:?}", solver.clauses));
+        solver.remove_clause(c0);
 
-        assert_eq!(&solver.watchers[lit(1)], &vec![0]);
-        assert_eq!(&solver.watchers[lit(2)], &vec![1]);
-        assert_eq!(&solver.watchers[lit(3)], &vec![0, 1, 2]);
-        assert_eq!(&solver.watchers[lit(4)], &vec![2]);
-        assert_eq!(&solver.watchers[lit(5)], &vec![ ]);
-        assert_eq!(&solver.watchers[lit(6)], &vec![ ]);
+        assert!(solver.clauses.is_deleted(c0));
+        assert!(!solver.clauses.is_deleted(c1));
+        assert_eq!(1, solver.clauses.len());
+        assert_eq!(vec![c1], solver.clauses.ids().collect::<Vec<ClauseId>>());
     }
 
     #[test]
-    fn remove_clause_must_not_redirect_reason_when_the_last_clause_is_removed(){
+    fn collect_garbage_must_relocate_the_watchers_and_reasons(){
         let mut solver = SOLVER::new(7);
-        solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]); // c0
-        solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]); // c1
-        solver.add_learned_clause(vec![lit(4), lit(3), lit(5)]); // c2
-        solver.add_learned_clause(vec![lit(6), lit(3), lit(5)]); // c3
+        let c0 = solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]).unwrap();
+        let c1 = solver.add_learned_clause(vec![lit(2), lit(3), lit(5)]).unwrap();
+        let c2 = solver.add_learned_clause(vec![lit(4), lit(3), lit(5)]).unwrap();
 
         solver.assign(lit(7), None);
-        solver.assign(lit(4), Some(2));
+        solver.assign(lit(4), Some(c2));
 
-        let database = format!("[{}, {}, {}, {}]",
-                               "Clause([Literal(1), Literal(3), Literal(5)])", // c0
-                               "Clause([Literal(2), Literal(3), Literal(5)])", // c1
-                               "Clause([Literal(4), Literal(3), Literal(5)])", // c2
-                               "Clause([Literal(6), Literal(3), Literal(5)])", // c3
-        );
-        assert_eq!(database, format!("{:?}", solver.clauses));
-
-        assert!   (solver.is_locked(2));
-        assert_eq!(solver.reason[var(4)], Some(2));
-
-        solver.remove_clause(3);
-
-        let database = format!("[{}, {}, {}]",
-                               "Clause([Literal(1), Literal(3), Literal(5)])", // c0
-                               "Clause([Literal(2), Literal(3), Literal(5)])", // c1
-                               "Clause([Literal(4), Literal(3), Literal(5)])", // c2
-        );
-        assert_eq!(database, format!("{:?}", solver.clauses));
+        solver.remove_clause(c0);
+        solver.collect_garbage();
 
-        assert!   (solver.is_locked(2));
-        assert_eq!(solver.reason[var(4)], Some(2));
+        // c1 moved to where c0 used to be and c2 moved to where c1 used to be
+        assert_eq!(vec![c0, c1], solver.clauses.ids().collect::<Vec<ClauseId>>());
+        assert_eq!(watched_by(&solver, lit(2)), vec![c0]);
+        // removing c0 swapped c2 in its place in the watch list of lit(3)
+        assert_eq!(watched_by(&solver, lit(3)), vec![c1, c0]);
+        assert_eq!(watched_by(&solver, lit(4)), vec![c1]);
+        assert_eq!(solver.reason[var(4)], Some(c1));
+        assert!(solver.is_locked(c1));
     }
 
     #[test]
@@ -3484,9 +5758,9 @@ mod tests {
         solver.level[var(3)] = 5;
         solver.level[var(5)] = 5;
 
-        solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]); // c0
+        let c0 = solver.add_learned_clause(vec![lit(1), lit(3), lit(5)]).unwrap(); // c0
 
-        assert_eq!(2, solver.lbd[0]);
+        assert_eq!(2, solver.clauses.lbd(c0));
     }
 
     #[allow(non_snake_case)]
@@ -3601,19 +5875,19 @@ mod tests {
          */
         let mut solver = SOLVER::new(7);
 
-        solver.add_problem_clause(&mut vec![ 1, 2,-3]); // c0
-        solver.add_problem_clause(&mut vec![ 1, 2,-4]); // c1
-        solver.add_problem_clause(&mut vec![ 3, 4,-5]); // c2
-        solver.add_problem_clause(&mut vec![ 1, 5, 6]); // c3
-        solver.add_problem_clause(&mut vec![ 2, 5,-6]); // c4
-        solver.add_problem_clause(&mut vec![ 7, 2,-6]); // c5
+        let c0 = solver.add_problem_clause(&mut vec![ 1, 2,-3]).unwrap(); // c0
+        let c1 = solver.add_problem_clause(&mut vec![ 1, 2,-4]).unwrap(); // c1
+        let c2 = solver.add_problem_clause(&mut vec![ 3, 4,-5]).unwrap(); // c2
+        let c3 = solver.add_problem_clause(&mut vec![ 1, 5, 6]).unwrap(); // c3
+        let c4 = solver.add_problem_clause(&mut vec![ 2, 5,-6]).unwrap(); // c4
+        let c5 = solver.add_problem_clause(&mut vec![ 7, 2,-6]).unwrap(); // c5
 
-        solver.lbd[0] = 3;
-        solver.lbd[1] = 3;
-        solver.lbd[2] = 3;
-        solver.lbd[3] = 3;
-        solver.lbd[4] = 3;
-        solver.lbd[5] = 3;
+        solver.clauses.set_lbd(c0, 3);
+        solver.clauses.set_lbd(c1, 3);
+        solver.clauses.set_lbd(c2, 3);
+        solver.clauses.set_lbd(c3, 3);
+        solver.clauses.set_lbd(c4, 3);
+        solver.clauses.set_lbd(c5, 3);
 
         assert!(solver.assign(lit(-7), None).is_ok());
         assert!(solver.propagate().is_none());
@@ -3623,13 +5897,145 @@ mod tests {
         assert!(solver.propagate().is_some());
 
         solver.assign(lit(-3), Some(0));
-        assert_eq!(2, solver.lbd[0]);
+        assert_eq!(2, solver.clauses.lbd(c0));
 
         solver.assign(lit(-4), Some(1));
-        assert_eq!(2, solver.lbd[1]);
+        assert_eq!(2, solver.clauses.lbd(c1));
+    }
+
+    #[test]
+    fn assign_uses_the_highest_level_of_the_reason_when_backtracking_chronologically() {
+        let mut solver = SOLVER::new(3);
+        solver.chrono_backtrack = true;
+        solver.add_problem_clause(&mut vec![1, -3]);
+
+        assert!(solver.assign(lit(3), None).is_ok());
+        assert!(solver.assign(lit(2), None).is_ok());
+        assert!(solver.assign(lit(1), Some(0)).is_ok());
+
+        assert_eq!(2, solver.nb_decisions);
+        assert_eq!(1, solver.level[var(1)]);
+    }
+
+    #[test]
+    fn backtrack_keeps_the_literals_assigned_out_of_order() {
+        let mut solver = SOLVER::new(3);
+        solver.chrono_backtrack = true;
+        solver.add_problem_clause(&mut vec![1, -3]);
+
+        assert!(solver.assign(lit(3), None).is_ok());
+        assert!(solver.assign(lit(2), None).is_ok());
+        assert!(solver.assign(lit(1), Some(0)).is_ok());
+
+        solver.backtrack(1);
+
+        assert_eq!(1, solver.nb_decisions);
+        assert_eq!(vec![lit(-3), lit(-1)], solver.prop_queue);
+        assert!(solver.is_true (lit(1)));
+        assert!(solver.is_undef(lit(2)));
+        assert!(solver.is_true (lit(3)));
+        // the literal kept out of order must be propagated again
+        assert_eq!(1, solver.propagated);
+    }
+
+    #[test]
+    fn backtrack_to_the_root_marks_the_kept_literals_as_forced() {
+        let mut solver = SOLVER::new(3);
+        solver.chrono_backtrack = true;
+        solver.add_problem_clause(&mut vec![1, 2]);
+        solver.add_problem_clause(&mut vec![-2]);
+
+        // contrived: 1 should have been propagated at the root level
+        assert!(solver.assign(lit(3), None).is_ok());
+        assert!(solver.assign(lit(1), Some(0)).is_ok());
+        assert_eq!(0, solver.level[var(1)]);
+        assert_eq!(1, solver.forced);
+
+        solver.backtrack(0);
+        assert_eq!(2, solver.forced);
+        assert!(solver.is_true (lit(1)));
+        assert!(solver.is_undef(lit(3)));
+        assert!(solver.flags[lit(1)].is_set(Flag::IsForced));
+    }
+
+    #[test]
+    fn resolve_conflict_backtracks_chronologically_when_the_jump_is_too_long() {
+        let mut solver = SOLVER::new(10);
+        solver.chrono_backtrack = true;
+        solver.chrono_threshold = 0;
+
+        solver.add_problem_clause(&mut vec![ 1,-4]);
+        solver.add_problem_clause(&mut vec![ 2,-3]);
+        solver.add_problem_clause(&mut vec![ 3, 4, 5]);
+        solver.add_problem_clause(&mut vec![ 3, 1,-5]);
+
+        for l in [-1, -6, -7, -8, -9, -10, -2] {
+            assert!(solver.assign(lit(l), None).is_ok());
+            if l != -2 { assert!(solver.propagate().is_none()); }
+        }
+        let conflict = solver.propagate();
+        assert!(conflict.is_some());
+        assert_eq!(7, solver.nb_decisions);
+
+        // learned clause is (3 1) which is asserting at level 1
+        assert!(solver.resolve_conflict(conflict.unwrap()).is_ok());
+
+        assert_eq!(6, solver.nb_decisions);
+        assert!(solver.is_true(lit(3)));
+        assert_eq!(1, solver.level[var(3)]);
+        assert!(solver.is_true(lit(-10)));
+    }
+
+    #[test]
+    fn resolve_conflict_backjumps_when_the_jump_does_not_exceed_the_threshold() {
+        let mut solver = SOLVER::new(10);
+        solver.chrono_backtrack = true;
+        solver.chrono_threshold = 100;
+
+        solver.add_problem_clause(&mut vec![ 1,-4]);
+        solver.add_problem_clause(&mut vec![ 2,-3]);
+        solver.add_problem_clause(&mut vec![ 3, 4, 5]);
+        solver.add_problem_clause(&mut vec![ 3, 1,-5]);
+
+        for l in [-1, -6, -7, -8, -9, -10, -2] {
+            assert!(solver.assign(lit(l), None).is_ok());
+            if l != -2 { assert!(solver.propagate().is_none()); }
+        }
+        let conflict = solver.propagate();
+        assert!(solver.resolve_conflict(conflict.unwrap()).is_ok());
+
+        assert_eq!(1, solver.nb_decisions);
+        assert!(solver.is_true(lit(3)));
+        assert!(solver.is_undef(lit(10)));
+    }
+
+    #[test]
+    fn resolve_conflict_asserts_a_missed_lower_implication() {
+        let mut solver = SOLVER::new(3);
+        solver.chrono_backtrack = true;
+        solver.add_problem_clause(&mut vec![2, 1]);
+
+        assert!(solver.assign(lit(-1), None).is_ok());
+        assert!(solver.assign(lit( 3), None).is_ok());
+        // contrived: decide -2 although (2 1) would have propagated 2 at level 1
+        assert!(solver.assign(lit(-2), None).is_ok());
+
+        let conflict = solver.propagate();
+        assert!(conflict.is_some());
+        assert!(solver.resolve_conflict(conflict.unwrap()).is_ok());
+
+        // nothing is learned, 2 is asserted at level 1 (out of order)
+        assert_eq!(1, solver.clauses.len());
+        assert_eq!(2, solver.nb_decisions);
+        assert!(solver.is_true(lit(2)));
+        assert_eq!(1, solver.level[var(2)]);
     }
 
     fn get_last_constraint(solver : &SOLVER) -> ClauseId {
-        solver.clauses.len() - 1
+        solver.clauses.ids().last().unwrap()
+    }
+
+    fn watched_by(solver : &SOLVER, l: Literal) -> Vec<ClauseId> {
+        solver.watchers[l].iter().chain(solver.binaries[l].iter()).map(|w| w.clause).collect()
     }
 }
@@ -0,0 +1,45 @@
+use core::*;
+use solving::ClauseId;
+
+// -----------------------------------------------------------------------------------------------
+/// # Watcher
+/// A watcher is an entry in the watch list of some literal. Besides the id of the clause watching
+/// that literal, it remembers a *blocker*: some other literal of the clause. Whenever the blocker
+/// is true, the clause is known to be satisfied and there is no need to access the clause memory
+/// at all during propagation.
+///
+/// _Note:_
+/// The blocker is only a hint. It is not required to be one of the two watched literals of the
+/// clause, hence it doesn't need to be updated when the clause changes its watched literals.
+/// See `Minisat 2.2` -- Eén and Sörensson.
+// -----------------------------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Watcher {
+    /// The id of the watching clause
+    pub clause : ClauseId,
+    /// Some literal of the watching clause other than the watched one
+    pub blocker: Literal
+}
+
+impl Watcher {
+    /// Creates a new watcher for the given clause and blocker
+    #[inline]
+    pub fn new(clause: ClauseId, blocker: Literal) -> Watcher {
+        Watcher { clause, blocker }
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+/// # Unit Tests
+// -----------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_must_remember_the_clause_and_the_blocker() {
+        let w = Watcher::new(42, lit(-3));
+        assert_eq!(42, w.clause);
+        assert_eq!(lit(-3), w.blocker);
+    }
+}
@@ -129,6 +129,26 @@ fn bmc_ibm_13() {
     assert_eq!(black_box("./tests/resources/bmc/bmc-ibm-13.cnf"), true);
 }
 
+#[test]
+fn chrono_prest2() {
+    assert!(black_box_chrono("./tests/resources/prest2.cnf"));
+}
+
+#[test]
+fn chrono_ibm_aim_100_no() {
+    assert!(!black_box_chrono("./tests/resources/aim-100-no.cnf"));
+}
+
+#[test]
+fn chrono_hole6() {
+    assert!(!black_box_chrono("./tests/resources/hole6.cnf"));
+}
+
+#[test]
+fn chrono_bmc_ibm_1() {
+    assert!(black_box_chrono("./tests/resources/bmc/bmc-ibm-1.cnf"));
+}
+
 fn black_box(fname : &'static str) -> bool {
     let file = File::open(fname).unwrap();
     let reader = BufReader::new(file);
@@ -138,4 +158,17 @@ fn black_box(fname : &'static str) -> bool {
     load_clauses(&mut solver, &mut lines);
 
     return solver.solve();
-}
\ No newline at end of file
+}
+
+fn black_box_chrono(fname : &'static str) -> bool {
+    let file = File::open(fname).unwrap();
+    let reader = BufReader::new(file);
+    let mut lines = reader.lines();
+
+    let mut solver = parse_header(&mut lines);
+    solver.chrono_backtrack = true;
+    solver.chrono_threshold = 0;
+    load_clauses(&mut solver, &mut lines);
+
+    solver.solve()
+}